@@ -0,0 +1,88 @@
+//! Compares the env loader with a chained reference implementation (the way it used to load variables) over a large
+//! environment.
+//!
+//! Run with `cargo bench --bench env`.
+
+use plugx_config::{
+    loader::{env::Env, Loader},
+    Url,
+};
+use std::time::{Duration, Instant};
+
+/// Loads variables the way the env loader used to.
+fn reference_load(var_list: Vec<(String, String)>, prefix: &str) -> Vec<(String, String)> {
+    let mut result: Vec<(String, String, String)> = Vec::new();
+    var_list
+        .into_iter()
+        .filter(|(key, _)| key.starts_with(prefix))
+        .map(|(key, value)| {
+            (
+                key.chars().skip(prefix.chars().count()).collect::<String>(),
+                value,
+            )
+        })
+        .map(|(key, value)| {
+            let key_list: Vec<String> = key.splitn(2, "__").map(String::from).collect();
+            (key_list, value)
+        })
+        .filter(|(key_list, _)| key_list.len() == 2 && !key_list[0].is_empty())
+        .map(|(key_list, value)| (key_list[0].to_lowercase(), key_list[1].clone(), value))
+        .filter(|(_, key, _)| !key.is_empty())
+        .for_each(|(plugin_name, key, value)| {
+            let key_value = format!("{key}={value:?}");
+            if let Some((_, _, contents)) =
+                result.iter_mut().find(|(name, _, _)| *name == plugin_name)
+            {
+                *contents += "\n";
+                *contents += key_value.as_str();
+            } else {
+                result.push((plugin_name, format!("{prefix}*"), key_value));
+            }
+        });
+    result
+        .into_iter()
+        .map(|(plugin_name, _, contents)| (plugin_name, contents))
+        .collect()
+}
+
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    (0..20).for_each(|_| f());
+    start.elapsed() / 20
+}
+
+fn main() {
+    let var_list: Vec<_> = (0..10_000)
+        .map(|index| {
+            if index % 100 == 0 {
+                (
+                    format!("BENCH__P{}__K{index}", index % 7),
+                    index.to_string(),
+                )
+            } else {
+                (format!("OTHER_VARIABLE_{index}"), "x".repeat(32))
+            }
+        })
+        .collect();
+    let source = var_list.clone();
+    let loader = Env::new()
+        .with_prefix("BENCH")
+        .with_var_source(move || source.clone());
+    let url: Url = "env://".parse().unwrap();
+
+    let loaded: Vec<_> = loader
+        .load(&url, None, false)
+        .unwrap()
+        .into_iter()
+        .map(|(plugin_name, entity)| (plugin_name, entity.maybe_contents().unwrap().clone()))
+        .collect();
+    assert_eq!(loaded, reference_load(var_list.clone(), "BENCH__"));
+
+    let reference = measure(|| {
+        reference_load(var_list.clone(), "BENCH__");
+    });
+    let single_pass = measure(|| {
+        loader.load(&url, None, false).unwrap();
+    });
+    println!("10k environment-variables: reference={reference:?} single-pass={single_pass:?}");
+}
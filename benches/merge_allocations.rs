@@ -0,0 +1,90 @@
+//! Compares allocations and duration of merging one and two sources of a big configuration.
+//!
+//! Run with `cargo bench --bench merge_allocations`.
+
+use plugx_config::{
+    entity::ConfigurationEntity, loader::closure::Closure as LoaderClosure,
+    loader::Error as LoaderError, Configuration, Input, Url,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    time::Instant,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATION_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATION_COUNT.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocation_count() -> usize {
+    ALLOCATION_COUNT.with(|count| count.get())
+}
+
+const KEY_COUNT: usize = 1_000;
+
+fn configuration(source_count: usize) -> Configuration {
+    let mut configuration = Configuration::new().with_loader(LoaderClosure::new(
+        "big",
+        Box::new(|url: &Url, _: Option<&[String]>, _: bool| {
+            let mut input = Input::new_map();
+            (0..KEY_COUNT).for_each(|index| {
+                input
+                    .map_mut()
+                    .insert(format!("key-{index}"), format!("value-{index}").into());
+            });
+            let entity = ConfigurationEntity::new("big", url.clone(), "foo", "big")
+                .with_parsed_contents(input);
+            Ok::<_, LoaderError>(vec![("foo".to_string(), entity)])
+        }),
+        "big",
+    ));
+    (0..source_count).for_each(|index| {
+        configuration
+            .add_url(format!("big://{index}").parse().unwrap())
+            .unwrap()
+    });
+    configuration
+}
+
+/// Allocations and duration of merging (i.e. [Configuration::load_parse_merge] minus
+/// [Configuration::load_and_parse]).
+fn merge_cost(configuration: &Configuration) -> (usize, u128) {
+    let start_count = allocation_count();
+    let start = Instant::now();
+    configuration.load_and_parse(false).unwrap();
+    let load_and_parse_duration = start.elapsed().as_micros();
+    let load_and_parse_count = allocation_count() - start_count;
+
+    let start_count = allocation_count();
+    let start = Instant::now();
+    configuration.load_parse_merge(false).unwrap();
+    let duration = start.elapsed().as_micros();
+    let count = allocation_count() - start_count;
+    (
+        count.saturating_sub(load_and_parse_count),
+        duration.saturating_sub(load_and_parse_duration),
+    )
+}
+
+fn main() {
+    [1, 2].into_iter().for_each(|source_count| {
+        let (count, duration) = merge_cost(&configuration(source_count));
+        println!("{source_count} source(s) of {KEY_COUNT} keys: merged with {count} allocations in {duration}µs");
+    });
+}
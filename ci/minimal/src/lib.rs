@@ -0,0 +1,26 @@
+//! Builds `plugx-config` with `default-features = false, features = ["json"]` which only contains parsers (no
+//! `Configuration`, loaders, or `url` crate).
+//!
+//! Run `make build-minimal` to build it. It has its own workspace, so features of `plugx-config` are not unified
+//! with the ones of its tests.
+
+use plugx_config::{
+    parser::{json::Json, Parser},
+    Input,
+};
+
+pub fn parse_json(bytes: &[u8]) -> Result<Input, plugx_config::parser::Error> {
+    Json::new().parse(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parsed = parse_json(br#"{"hello": "world"}"#).unwrap();
+        assert_eq!(parsed.as_map().get("hello"), Some(&Input::from("world")));
+        assert!(parse_json(b"{").is_err());
+    }
+}
@@ -109,7 +109,7 @@ impl Loader for MyLoader {
                         let contents =
                             fs::read_to_string(&path).map_err(|error| LoaderError::Load {
                                 loader: format!("{self}"),
-                                url: url.clone().into(),
+                                url: url.clone(),
                                 description: format!("Could not read file contents from {path:?}")
                                     .into(),
                                 source: anyhow!(error),
@@ -146,7 +146,7 @@ impl Loader for MyLoader {
                 } else {
                     Err(LoaderError::Load {
                         loader: format!("{self}"),
-                        url: url.clone().into(),
+                        url: url.clone(),
                         description: format!("Could not read directory contents from {sub_path:?}")
                             .into(),
                         source: anyhow!(error),
@@ -187,7 +187,7 @@ fn main() -> Result<()> {
 
     let mut configuration = Configuration::new();
     // Set our custom loader:
-    configuration.add_loader(MyLoader::default());
+    configuration.add_loader(MyLoader);
     url_list
         .into_iter()
         .try_for_each(|url| configuration.add_url(url))?;
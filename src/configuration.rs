@@ -1,11 +1,24 @@
 use crate::{
-    entity::ConfigurationEntity, error::Error, loader::Error as LoaderError, loader::Loader,
+    entity::{ConfigurationEntity, CONTENT_TYPE_METADATA_KEY},
+    error::Error,
+    loader::Error as LoaderError,
+    loader::Loader,
     parser::Parser,
+    LogOptions,
 };
 use anyhow::anyhow;
 use cfg_if::cfg_if;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use plugx_input::{position::InputPosition, schema::InputSchemaType, Input};
-use std::env;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::HashMap,
+    env,
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 use url::Url;
 
 #[derive(Debug, Default)]
@@ -14,6 +27,390 @@ pub struct Configuration {
     loader_list: Vec<Box<dyn Loader>>,
     parser_list: Vec<Box<dyn Parser>>,
     maybe_whitelist: Option<Vec<String>>,
+    alias_list: Vec<(String, String, String)>,
+    alias_conflict_policy: AliasConflictPolicy,
+    default_document_list: Vec<ConfigurationEntity>,
+    always_include_default_documents: bool,
+    result_exclude_list: Vec<String>,
+    extension_format_list: Vec<(String, String)>,
+    mime_format_list: Vec<(String, String)>,
+    plugin_format_list: Vec<(String, String)>,
+    parser_probe_order: Vec<String>,
+    parser_fallback_list: Vec<String>,
+    plugin_pattern_list: Vec<PluginPattern>,
+    plugin_prefix_strip_list: Vec<String>,
+    sort_keys: bool,
+    maybe_parse_concurrency: Option<usize>,
+    maybe_fail_fast: Option<bool>,
+    plugin_precedence_list: Vec<(String, SourcePrecedence)>,
+    last_loaded_list: HashMap<Url, SystemTime>,
+    input_preprocessor_list: InputPreprocessorList,
+    maybe_null_sentinel_list: Option<Vec<String>>,
+    null_unsets: bool,
+    log_options: LogOptions,
+    maybe_max_depth: Option<usize>,
+    dump_values: bool,
+    #[cfg(feature = "units")]
+    duration_key_list: Vec<String>,
+    #[cfg(feature = "units")]
+    bytesize_key_list: Vec<String>,
+}
+
+/// Serializes an [Input] with sorted map keys.
+///
+/// Maps of [Input] are [HashMap]s, so the order of keys in the source is not preserved and serializing an [Input]
+/// directly yields keys in an arbitrary order. Use this wrapper for reproducible output (e.g. for diffing exported
+/// configurations).
+///
+/// ### Example
+/// ```rust
+/// # #[cfg(feature = "json")] {
+/// use plugx_config::{Input, SortedKeys};
+/// use std::collections::HashMap;
+///
+/// let input = Input::from(HashMap::from([
+///     ("b".to_string(), Input::from(2)),
+///     ("a".to_string(), Input::from(1)),
+/// ]));
+/// assert_eq!(serde_json::to_string(&SortedKeys(&input)).unwrap(), r#"{"a":1,"b":2}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SortedKeys<'a>(pub &'a Input);
+
+impl Serialize for SortedKeys<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Input::Map(map) => {
+                let mut key_value_list: Vec<_> = map.iter().collect();
+                key_value_list.sort_by_key(|(key, _)| *key);
+                serializer.collect_map(
+                    key_value_list
+                        .into_iter()
+                        .map(|(key, value)| (key, SortedKeys(value))),
+                )
+            }
+            Input::List(list) => serializer.collect_seq(list.iter().map(SortedKeys)),
+            input => input.serialize(serializer),
+        }
+    }
+}
+
+/// A glob (or `regex:` prefixed regular expression) pattern for plugin names (see [Configuration::add_plugin_pattern]).
+#[derive(Debug, Clone)]
+enum PluginPattern {
+    Glob(Vec<char>),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+/// What to do when both the old and the new path of an alias are set in the same source (see
+/// [Configuration::add_alias]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AliasConflictPolicy {
+    /// Fail with an error.
+    #[default]
+    Error,
+    /// Keep the value of the new path and drop the old one.
+    PreferTo,
+    /// Replace the value of the new path with the old one.
+    PreferFrom,
+}
+
+/// Which configuration of a plugin wins when several sources set the same key (see
+/// [Configuration::set_plugin_precedence]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourcePrecedence {
+    /// Later URLs override earlier ones.
+    #[default]
+    LastWins,
+    /// Earlier URLs override later ones. Default documents are still overridden by every other source.
+    FirstWins,
+}
+
+/// A `|plugin_name, url, &mut Input|` [Fn] that normalizes parsed contents of each source before merging (see
+/// [Configuration::add_input_preprocessor]).
+pub type BoxedInputPreprocessorFn = Box<dyn Fn(&str, &Url, &mut Input) + Send + Sync>;
+
+type InputPreprocessorFn = dyn Fn(&str, &Url, &mut Input) + Send + Sync;
+
+#[derive(Clone, Default)]
+struct InputPreprocessorList(Vec<Arc<InputPreprocessorFn>>);
+
+impl Debug for InputPreprocessorList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(format!("{} input preprocessor(s)", self.0.len()).as_str())
+    }
+}
+
+/// A `|Input| -> Result<Box<dyn Any + Send + Sync>, Error>` [Fn] that materializes a plugin's configuration.
+pub type BoxedMaterializeFn =
+    Box<dyn Fn(Input) -> Result<Box<dyn Any + Send + Sync>, Error> + Send + Sync>;
+
+/// Maps plugin names to functions that turn their merged configuration into a concrete type (see
+/// [Configuration::load_parse_merge_validate_typed]).
+///
+/// Results are [Box]ed [Any]s, so downcast each one to the type that its plugin registered (e.g. via
+/// [Box::downcast] or `downcast_ref` of [Any]).
+///
+/// ### Example
+/// ```rust
+/// # #[cfg(feature = "json")] {
+/// use plugx_config::{Configuration, Input, PluginRegistry};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Server {
+///     port: isize,
+/// }
+///
+/// let registry = PluginRegistry::new().with_plugin("server", |input: Input| {
+///     let port = input.as_map().get("port").map(|port| *port.as_int()).unwrap_or(80);
+///     Ok(Server { port })
+/// });
+/// let configuration = Configuration::new()
+///     .with_default_document("server", "json", r#"{"port": 8080}"#)
+///     .unwrap()
+///     .with_always_include_default_documents(true);
+/// let typed = configuration
+///     .load_parse_merge_validate_typed(&[], &registry, false)
+///     .unwrap();
+/// let server = typed.get("server").unwrap().downcast_ref::<Server>().unwrap();
+/// assert_eq!(server, &Server { port: 8080 });
+/// # }
+/// ```
+#[derive(Default)]
+pub struct PluginRegistry {
+    materialize_map: HashMap<String, BoxedMaterializeFn>,
+}
+
+impl Debug for PluginRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut plugin_name_list: Vec<_> = self.materialize_map.keys().collect();
+        plugin_name_list.sort();
+        f.debug_struct("PluginRegistry")
+            .field("plugin_name_list", &plugin_name_list)
+            .finish()
+    }
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Registers `materialize` for `plugin` and replaces the previous one if any.
+    pub fn add_plugin<P, T, F>(&mut self, plugin: P, materialize: F)
+    where
+        P: AsRef<str>,
+        T: Any + Send + Sync,
+        F: Fn(Input) -> Result<T, Error> + Send + Sync + 'static,
+    {
+        self.add_boxed_plugin(
+            plugin,
+            Box::new(move |input| {
+                materialize(input).map(|value| Box::new(value) as Box<dyn Any + Send + Sync>)
+            }),
+        )
+    }
+
+    /// Registers `materialize` for `plugin` and replaces the previous one if any.
+    pub fn with_plugin<P, T, F>(mut self, plugin: P, materialize: F) -> Self
+    where
+        P: AsRef<str>,
+        T: Any + Send + Sync,
+        F: Fn(Input) -> Result<T, Error> + Send + Sync + 'static,
+    {
+        self.add_plugin(plugin, materialize);
+        self
+    }
+
+    pub fn add_boxed_plugin<P: AsRef<str>>(&mut self, plugin: P, materialize: BoxedMaterializeFn) {
+        self.materialize_map
+            .insert(plugin.as_ref().to_lowercase(), materialize);
+    }
+
+    pub fn with_boxed_plugin<P: AsRef<str>>(
+        mut self,
+        plugin: P,
+        materialize: BoxedMaterializeFn,
+    ) -> Self {
+        self.add_boxed_plugin(plugin, materialize);
+        self
+    }
+
+    pub fn has_plugin<P: AsRef<str>>(&self, plugin: P) -> bool {
+        self.materialize_map
+            .contains_key(&plugin.as_ref().to_lowercase())
+    }
+
+    pub fn remove_plugin<P: AsRef<str>>(&mut self, plugin: P) -> Option<BoxedMaterializeFn> {
+        self.materialize_map.remove(&plugin.as_ref().to_lowercase())
+    }
+
+    /// Materializes `input` of `plugin` or returns `None` if `plugin` is not registered.
+    pub fn materialize<P: AsRef<str>>(
+        &self,
+        plugin: P,
+        input: Input,
+    ) -> Option<Result<Box<dyn Any + Send + Sync>, Error>> {
+        self.materialize_map
+            .get(&plugin.as_ref().to_lowercase())
+            .map(|materialize| materialize(input))
+    }
+}
+
+/// Parsed configurations of a plugin along with their source URL (see [Configuration::parse_only]).
+pub type ParsedSourceList = Vec<(Url, Input)>;
+
+type BuildStepFn = Box<dyn FnOnce(&mut Configuration) -> Result<(), Error> + Send>;
+
+/// Builds a [Configuration] (see [Configuration::builder]).
+///
+/// Loaders, parsers, and options are set right away, but fallible steps (e.g. adding URLs) run in [Self::build] in
+/// order of adding them, so URLs can be added before their loaders. [Self::build] also checks that every format used
+/// in extension formats, parser probe order, parser fallback, and default documents has a parser.
+///
+/// ### Example
+/// ```rust
+/// # #[cfg(feature = "json")] {
+/// use plugx_config::{loader::{closure::Closure, Error}, Configuration, Url};
+///
+/// let configuration = Configuration::builder()
+///     .with_url("xyz:///".parse().unwrap())
+///     .with_loader(Closure::new(
+///         "xyz-loader",
+///         Box::new(|_: &Url, _: Option<&[String]>, _: bool| Ok::<_, Error>(Vec::new())),
+///         "xyz",
+///     ))
+///     .with_whitelist(&["server"])
+///     .with_default_document("server", "json", r#"{"port": 8080}"#)
+///     .build()
+///     .unwrap();
+/// assert_eq!(configuration.url_list().len(), 1);
+///
+/// // There is no loader for `abc` scheme:
+/// assert!(Configuration::builder().with_url("abc:///".parse().unwrap()).build().is_err());
+/// # }
+/// ```
+pub struct ConfigurationBuilder {
+    configuration: Configuration,
+    step_list: Vec<BuildStepFn>,
+}
+
+/// Serializable sources of a [Configuration] (URLs, whitelist, extension and MIME formats, and plugin precedences).
+///
+/// Loaders and parsers are not part of the state. [Configuration::from_state] registers built-in loaders
+/// for URLs (based on enabled Cargo features) and for custom loaders use [Configuration::with_state] after
+/// adding them.
+///
+/// Usernames and passwords of URLs are removed when the state is serialized, so credentials are not written to
+/// files and logs. Add them again (e.g. via [ConfigurationState::url_list_mut]) before restoring such URLs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigurationState {
+    #[serde(serialize_with = "serialize_url_list_without_userinfo")]
+    url_list: Vec<Url>,
+    #[serde(default)]
+    whitelist: Option<Vec<String>>,
+    #[serde(default)]
+    extension_format_list: Vec<(String, String)>,
+    #[serde(default)]
+    mime_format_list: Vec<(String, String)>,
+    #[serde(default)]
+    plugin_precedence_list: Vec<(String, SourcePrecedence)>,
+}
+
+fn serialize_url_list_without_userinfo<S: serde::Serializer>(
+    url_list: &[Url],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(url_list.iter().map(crate::loader::without_userinfo))
+}
+
+/// A configuration which is going to be loaded (see [Configuration::plan]).
+///
+/// If the loader of the URL could not list its configurations without loading them, plugin name and format are
+/// unknown ([None]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadPlanItem {
+    url: Url,
+    maybe_plugin_name: Option<String>,
+    maybe_format: Option<String>,
+    supports_soft_errors: bool,
+}
+
+/// Durations of each phase of [Configuration::load_parse_merge_timed] and
+/// [Configuration::load_parse_merge_validate_timed].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhaseTimings {
+    load: Duration,
+    url_load_list: Vec<(Url, Duration)>,
+    parse: Duration,
+    merge: Duration,
+    validate: Duration,
+}
+
+impl PhaseTimings {
+    pub fn load(&self) -> Duration {
+        self.load
+    }
+
+    /// Load duration of each URL.
+    pub fn url_load_list(&self) -> &[(Url, Duration)] {
+        self.url_load_list.as_slice()
+    }
+
+    pub fn parse(&self) -> Duration {
+        self.parse
+    }
+
+    pub fn merge(&self) -> Duration {
+        self.merge
+    }
+
+    /// It is zero if nothing is validated.
+    pub fn validate(&self) -> Duration {
+        self.validate
+    }
+
+    pub fn total(&self) -> Duration {
+        self.load + self.parse + self.merge + self.validate
+    }
+}
+
+impl PluginPattern {
+    fn is_match(&self, plugin_name: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => {
+                glob_match(pattern, plugin_name.chars().collect::<Vec<_>>().as_slice())
+            }
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(plugin_name),
+        }
+    }
+}
+
+impl LoadPlanItem {
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn maybe_plugin_name(&self) -> Option<&String> {
+        self.maybe_plugin_name.as_ref()
+    }
+
+    pub fn maybe_format(&self) -> Option<&String> {
+        self.maybe_format.as_ref()
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.maybe_plugin_name.is_none()
+    }
+
+    /// Whether the loader of the URL honors `skip_soft_errors` (see [Loader::supports_soft_errors]).
+    pub fn supports_soft_errors(&self) -> bool {
+        self.supports_soft_errors
+    }
 }
 
 impl Configuration {
@@ -28,14 +425,18 @@ impl Configuration {
                 Box::new(crate::parser::toml::Toml::new()),
                 #[cfg(feature = "yaml")]
                 Box::new(crate::parser::yaml::Yaml::new()),
+                #[cfg(feature = "hjson")]
+                Box::new(crate::parser::hjson::Hjson::new()),
+                #[cfg(feature = "hcl")]
+                Box::new(crate::parser::hcl::Hcl::new()),
             ],
+            mime_format_list: DEFAULT_MIME_FORMAT_LIST
+                .iter()
+                .map(|(mime, format)| (mime.to_string(), format.to_string()))
+                .collect(),
             ..Default::default()
         };
-        let parser_name_list: Vec<_> = new
-            .parser_list
-            .iter()
-            .map(|parser| format!("{parser}"))
-            .collect();
+        let parser_name_list: Vec<_> = new.parser_list.iter().map(|parser| parser.name()).collect();
         if parser_name_list.is_empty() {
             cfg_if! {
                 if #[cfg(feature = "tracing")] {
@@ -55,464 +456,4026 @@ impl Configuration {
         }
         new
     }
+
+    /// Drops everything except loaders, parsers (including extension formats, MIME formats, probe order, and
+    /// fallback of parsers), log options, and input preprocessors.
+    ///
+    /// URLs, whitelist, aliases, default documents, excluded plugins, plugin patterns, and other options are reset to
+    /// their defaults, so the source set can be rebuilt (e.g. on a reload signal) without registering custom
+    /// loaders and parsers again.
+    pub fn reset(&mut self) {
+        *self = Self {
+            loader_list: std::mem::take(&mut self.loader_list),
+            parser_list: std::mem::take(&mut self.parser_list),
+            extension_format_list: std::mem::take(&mut self.extension_format_list),
+            mime_format_list: std::mem::take(&mut self.mime_format_list),
+            parser_probe_order: std::mem::take(&mut self.parser_probe_order),
+            parser_fallback_list: std::mem::take(&mut self.parser_fallback_list),
+            input_preprocessor_list: std::mem::take(&mut self.input_preprocessor_list),
+            log_options: self.log_options,
+            ..Default::default()
+        };
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!("Reset configuration");
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Reset configuration\"");
+            }
+        }
+    }
 }
 
 impl Configuration {
-    pub fn url_list(&self) -> &[Url] {
-        self.url_list.as_slice()
+    /// Returns a copy of this configuration which only loads configurations of `plugins`.
+    ///
+    /// The whitelist of the copy is exactly `plugins`, intersected with the current whitelist (if any). Note that it is
+    /// not a sandbox, since the copy can still be changed (e.g. its whitelist and URLs). All loaders and parsers should
+    /// be clonable (see [Loader::boxed_clone] and [Parser::boxed_clone]) which is true for built-in ones, otherwise it
+    /// yields an error.
+    pub fn subset<P: AsRef<str>>(&self, plugins: &[P]) -> Result<Self, Error> {
+        let mut not_clonable_list = Vec::new();
+        let loader_list: Vec<_> = self
+            .loader_list
+            .iter()
+            .filter_map(|loader| {
+                loader.boxed_clone().or_else(|| {
+                    not_clonable_list.push(format!("loader {}", loader.name()));
+                    None
+                })
+            })
+            .collect();
+        let parser_list: Vec<_> = self
+            .parser_list
+            .iter()
+            .filter_map(|parser| {
+                parser.boxed_clone().or_else(|| {
+                    not_clonable_list.push(format!("parser {}", parser.name()));
+                    None
+                })
+            })
+            .collect();
+        if !not_clonable_list.is_empty() {
+            return Err(anyhow!(
+                "Could not clone {} for a subset configuration",
+                not_clonable_list.join(", ")
+            )
+            .into());
+        }
+        let whitelist: Vec<_> = plugins
+            .iter()
+            .map(|plugin| plugin.as_ref().to_lowercase())
+            .filter(|plugin_name| {
+                self.maybe_whitelist
+                    .as_ref()
+                    .map(|whitelist| whitelist.contains(plugin_name))
+                    .unwrap_or(true)
+            })
+            .collect();
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(whitelist=?whitelist, "Created subset configuration");
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Created subset configuration\" whitelist={whitelist:?}");
+            }
+        }
+        Ok(Self {
+            url_list: self.url_list.clone(),
+            loader_list,
+            parser_list,
+            maybe_whitelist: Some(whitelist),
+            alias_list: self.alias_list.clone(),
+            alias_conflict_policy: self.alias_conflict_policy,
+            default_document_list: self.default_document_list.clone(),
+            always_include_default_documents: self.always_include_default_documents,
+            result_exclude_list: self.result_exclude_list.clone(),
+            extension_format_list: self.extension_format_list.clone(),
+            mime_format_list: self.mime_format_list.clone(),
+            plugin_format_list: self.plugin_format_list.clone(),
+            parser_probe_order: self.parser_probe_order.clone(),
+            parser_fallback_list: self.parser_fallback_list.clone(),
+            plugin_pattern_list: self.plugin_pattern_list.clone(),
+            plugin_prefix_strip_list: self.plugin_prefix_strip_list.clone(),
+            sort_keys: self.sort_keys,
+            maybe_parse_concurrency: self.maybe_parse_concurrency,
+            maybe_fail_fast: self.maybe_fail_fast,
+            plugin_precedence_list: self.plugin_precedence_list.clone(),
+            last_loaded_list: self.last_loaded_list.clone(),
+            input_preprocessor_list: self.input_preprocessor_list.clone(),
+            maybe_null_sentinel_list: self.maybe_null_sentinel_list.clone(),
+            null_unsets: self.null_unsets,
+            log_options: self.log_options,
+            maybe_max_depth: self.maybe_max_depth,
+            dump_values: self.dump_values,
+            #[cfg(feature = "units")]
+            duration_key_list: self.duration_key_list.clone(),
+            #[cfg(feature = "units")]
+            bytesize_key_list: self.bytesize_key_list.clone(),
+        })
     }
 
-    pub fn has_url(&mut self, url: &Url) -> bool {
-        self.url_list.contains(url)
+    /// Returns a [ConfigurationBuilder] which starts from [Configuration::new].
+    pub fn builder() -> ConfigurationBuilder {
+        ConfigurationBuilder::new()
     }
+}
 
-    pub fn has_url_scheme(&mut self, url: &Url) -> bool {
-        self.url_list
-            .iter()
-            .any(|inner_url| inner_url.scheme() == url.scheme())
+impl Debug for ConfigurationBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigurationBuilder")
+            .field("configuration", &self.configuration)
+            .field("step_count", &self.step_list.len())
+            .finish()
     }
+}
 
-    pub fn with_url(mut self, url: Url) -> Result<Self, Error> {
-        self.add_url(url)?;
-        Ok(self)
+impl Default for ConfigurationBuilder {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    pub fn add_url(&mut self, url: Url) -> Result<(), Error> {
-        let scheme = url.scheme().to_string();
-        let maybe_loader_name = if let Some(loader) = self
-            .loader_list
-            .iter()
-            .find(|loader| loader.scheme_list().contains(&scheme))
-        {
-            self.url_list.push(url.clone());
-            Some(format!("{loader}"))
-        } else {
-            #[allow(unused_mut)]
-            let mut included_loader_list: Vec<Box<dyn Loader>> = Vec::new();
-
-            #[cfg(feature = "env")]
-            included_loader_list.push(Box::new(crate::loader::env::Env::new()));
+impl ConfigurationBuilder {
+    pub fn new() -> Self {
+        Self {
+            configuration: Configuration::new(),
+            step_list: Vec::new(),
+        }
+    }
 
-            #[cfg(feature = "fs")]
-            included_loader_list.push(Box::new(crate::loader::fs::Fs::new()));
+    fn with_step<F>(mut self, step: F) -> Self
+    where
+        F: FnOnce(&mut Configuration) -> Result<(), Error> + Send + 'static,
+    {
+        self.step_list.push(Box::new(step));
+        self
+    }
 
-            included_loader_list
-                .into_iter()
-                .find(|loader| loader.scheme_list().contains(&scheme))
-                .map(|loader| {
-                    let name = format!("{loader}");
-                    self.add_boxed_loader(loader);
-                    self.url_list.push(url.clone());
-                    name
-                })
-        };
-        maybe_loader_name.map(|_loader_name| {
-            cfg_if! {
-                if #[cfg(feature = "tracing")] {
-                    tracing::debug!(url=%url, loader=_loader_name, "Added configuration URL");
-                } else if #[cfg(feature = "logging")] {
-                    log::debug!("msg=\"Added configuration URL\", url=\"{url}\" loader={_loader_name:?}");
-                }
-            }
-            Ok(())
-        }).unwrap_or(Err(LoaderError::LoaderNotFound { scheme, url }.into()))
+    pub fn with_url(self, url: Url) -> Self {
+        self.with_step(move |configuration| configuration.add_url(url))
     }
 
-    pub fn remove_url(&mut self, url: &Url) -> bool {
-        let mut result = false;
-        while let Some(index) = self.url_list.iter().position(|inner_url| inner_url == url) {
-            self.url_list.remove(index);
-            cfg_if! {
-                if #[cfg(feature = "tracing")] {
-                    tracing::debug!(url=%url, "Removed URL")
-                } else if #[cfg(feature = "logging")] {
-                    log::debug!("msg=\"Removed URL\" url=\"{url}\"")
-                }
-            }
-            result = true;
-        }
-        result
+    /// See [Configuration::add_url_template].
+    pub fn with_url_template<T: AsRef<str>>(self, template: T) -> Self {
+        let template = template.as_ref().to_string();
+        self.with_step(move |configuration| configuration.add_url_template(template))
     }
 
-    pub fn remove_scheme<S: AsRef<str>>(&mut self, scheme: S) -> Vec<Url> {
-        let mut url_list = Vec::new();
-        while let Some(url) = self
-            .url_list
-            .iter()
-            .find(|url| url.scheme() == scheme.as_ref())
-        {
-            url_list.push(url.clone())
-        }
-        url_list.iter().for_each(|url| {
-            self.remove_url(url);
-        });
-        url_list
+    /// See [Configuration::add_path].
+    #[cfg(feature = "fs")]
+    pub fn with_path<P: AsRef<std::path::Path>>(self, path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        self.with_step(move |configuration| configuration.add_path(path))
     }
-}
 
-impl Configuration {
-    pub fn has_loader(&mut self, url: &Url) -> bool {
-        let scheme = url.scheme().to_string();
-        self.loader_list
-            .iter()
-            .any(|loader| loader.scheme_list().contains(&scheme))
+    pub fn with_loader<L: Loader + 'static>(mut self, loader: L) -> Self {
+        self.configuration.add_loader(loader);
+        self
     }
 
-    pub fn with_loader<L>(mut self, loader: L) -> Self
+    pub fn with_loader_for_schemes<L, S>(mut self, loader: L, scheme_list: &[S]) -> Self
     where
         L: Loader + 'static,
+        S: AsRef<str>,
     {
-        self.add_boxed_loader(Box::new(loader));
+        self.configuration
+            .add_loader_for_schemes(loader, scheme_list);
         self
     }
 
-    pub fn add_loader<L>(&mut self, loader: L)
+    pub fn with_boxed_loader(mut self, loader: Box<dyn Loader>) -> Self {
+        self.configuration.add_boxed_loader(loader);
+        self
+    }
+
+    pub fn with_parser<P: Parser + 'static>(mut self, parser: P) -> Self {
+        self.configuration.add_parser(parser);
+        self
+    }
+
+    pub fn with_boxed_parser(mut self, parser: Box<dyn Parser>) -> Self {
+        self.configuration.add_boxed_parser(parser);
+        self
+    }
+
+    pub fn with_parser_for_extension<P, E>(mut self, parser: P, extension: E) -> Self
     where
-        L: Loader + 'static,
+        P: Parser + 'static,
+        E: AsRef<str>,
     {
-        self.add_boxed_loader(Box::new(loader));
+        self.configuration
+            .add_parser_for_extension(parser, extension);
+        self
     }
 
-    pub fn with_boxed_loader(mut self, loader: Box<dyn Loader>) -> Self {
-        self.add_boxed_loader(loader);
+    pub fn with_extension_format<E: AsRef<str>, F: AsRef<str>>(
+        mut self,
+        extension: E,
+        format: F,
+    ) -> Self {
+        self.configuration.add_extension_format(extension, format);
         self
     }
 
-    pub fn add_boxed_loader(&mut self, loader: Box<dyn Loader>) {
-        cfg_if! {
-            if #[cfg(feature = "tracing")] {
-                tracing::debug!(
-                    loader=%loader,
-                    schema_list=?loader.scheme_list(),
-                    "Added configuration loader"
-                );
-            } else if #[cfg(feature = "logging")] {
-                log::debug!(
-                    "msg=\"Added configuration loader\" loader=\"{loader}\" schema_list={:?}",
-                    loader.scheme_list()
-                );
-            }
-        }
-        self.loader_list.push(loader);
+    pub fn with_plugin_format<P: AsRef<str>, F: AsRef<str>>(
+        mut self,
+        plugin: P,
+        format: F,
+    ) -> Self {
+        self.configuration.add_plugin_format(plugin, format);
+        self
     }
 
-    pub fn remove_loader_and_urls<S: AsRef<str>>(
-        &mut self,
-        scheme: S,
-    ) -> Option<(Box<dyn Loader>, Vec<Url>)> {
-        let scheme_string = scheme.as_ref().to_string();
-        if let Some(index) = self
-            .loader_list
-            .iter()
-            .position(|loader| loader.scheme_list().contains(&scheme_string))
-        {
+    pub fn with_parser_for_mime<P, M>(mut self, parser: P, mime: M) -> Self
+    where
+        P: Parser + 'static,
+        M: AsRef<str>,
+    {
+        self.configuration.add_parser_for_mime(parser, mime);
+        self
+    }
+
+    pub fn with_mapped_mime<M: AsRef<str>, F: AsRef<str>>(mut self, mime: M, format: F) -> Self {
+        self.configuration.map_mime(mime, format);
+        self
+    }
+
+    pub fn with_parser_probe_order<F: AsRef<str>>(mut self, format_list: &[F]) -> Self {
+        self.configuration.set_parser_probe_order(format_list);
+        self
+    }
+
+    pub fn with_input_preprocessor(mut self, preprocessor: BoxedInputPreprocessorFn) -> Self {
+        self.configuration.add_input_preprocessor(preprocessor);
+        self
+    }
+
+    pub fn with_null_coercion(mut self, null_coercion: bool) -> Self {
+        self.configuration.set_null_coercion(null_coercion);
+        self
+    }
+
+    pub fn with_null_sentinels<S: AsRef<str>>(mut self, sentinel_list: &[S]) -> Self {
+        self.configuration.set_null_sentinels(sentinel_list);
+        self
+    }
+
+    pub fn with_null_unsets(mut self, null_unsets: bool) -> Self {
+        self.configuration.set_null_unsets(null_unsets);
+        self
+    }
+
+    pub fn with_trace_contents(mut self, trace_contents: bool) -> Self {
+        self.configuration.set_trace_contents(trace_contents);
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.configuration.set_max_depth(max_depth);
+        self
+    }
+
+    pub fn with_dump_values(mut self, dump_values: bool) -> Self {
+        self.configuration.set_dump_values(dump_values);
+        self
+    }
+
+    pub fn with_parser_fallback<F: AsRef<str>>(mut self, format_list: &[F]) -> Self {
+        self.configuration.set_parser_fallback(format_list);
+        self
+    }
+
+    pub fn with_whitelist<N: AsRef<str>>(mut self, whitelist: &[N]) -> Self {
+        self.configuration.set_whitelist(whitelist);
+        self
+    }
+
+    /// See [Configuration::load_whitelist_from_env].
+    pub fn with_whitelist_from_env<K: AsRef<str>>(self, key: K) -> Self {
+        let key = key.as_ref().to_string();
+        self.with_step(move |configuration| configuration.load_whitelist_from_env(key))
+    }
+
+    /// See [Configuration::load_whitelist_from_file].
+    pub fn with_whitelist_from_file<P: AsRef<std::path::Path>>(self, path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        self.with_step(move |configuration| configuration.load_whitelist_from_file(path))
+    }
+
+    pub fn with_whitelist_from_fn<F: FnOnce() -> Vec<String>>(mut self, whitelist_fn: F) -> Self {
+        self.configuration.load_whitelist_from_fn(whitelist_fn);
+        self
+    }
+
+    pub fn with_alias<P: AsRef<str>, F: AsRef<str>, T: AsRef<str>>(
+        mut self,
+        plugin: P,
+        from_path: F,
+        to_path: T,
+    ) -> Self {
+        self.configuration.add_alias(plugin, from_path, to_path);
+        self
+    }
+
+    pub fn with_alias_conflict_policy(mut self, policy: AliasConflictPolicy) -> Self {
+        self.configuration.set_alias_conflict_policy(policy);
+        self
+    }
+
+    pub fn with_default_document<P: AsRef<str>, F: AsRef<str>, C: AsRef<str>>(
+        self,
+        plugin: P,
+        format: F,
+        contents: C,
+    ) -> Self {
+        let (plugin, format, contents) = (
+            plugin.as_ref().to_string(),
+            format.as_ref().to_string(),
+            contents.as_ref().to_string(),
+        );
+        self.with_step(move |configuration| {
+            configuration.add_default_document(plugin, format, contents)
+        })
+    }
+
+    pub fn with_always_include_default_documents(mut self, flag: bool) -> Self {
+        self.configuration
+            .set_always_include_default_documents(flag);
+        self
+    }
+
+    pub fn with_plugin_pattern<P: AsRef<str>>(self, pattern: P) -> Self {
+        let pattern = pattern.as_ref().to_string();
+        self.with_step(move |configuration| configuration.add_plugin_pattern(pattern))
+    }
+
+    pub fn with_plugin_prefix_strip<P: AsRef<str>>(mut self, prefix_list: &[P]) -> Self {
+        self.configuration.set_plugin_prefix_strip(prefix_list);
+        self
+    }
+
+    pub fn with_excluded_from_result<P: AsRef<str>>(mut self, plugin: P) -> Self {
+        self.configuration.add_excluded_from_result(plugin);
+        self
+    }
+
+    pub fn with_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.configuration.set_sort_keys(sort_keys);
+        self
+    }
+
+    pub fn with_parse_concurrency(mut self, parse_concurrency: usize) -> Self {
+        self.configuration.set_parse_concurrency(parse_concurrency);
+        self
+    }
+
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.configuration.set_fail_fast(fail_fast);
+        self
+    }
+
+    pub fn with_plugin_precedence<P: AsRef<str>>(
+        mut self,
+        plugin: P,
+        precedence: SourcePrecedence,
+    ) -> Self {
+        self.configuration.set_plugin_precedence(plugin, precedence);
+        self
+    }
+
+    #[cfg(feature = "units")]
+    pub fn with_duration_keys<K: AsRef<str>>(mut self, key_list: &[K]) -> Self {
+        self.configuration.set_duration_keys(key_list);
+        self
+    }
+
+    #[cfg(feature = "units")]
+    pub fn with_bytesize_keys<K: AsRef<str>>(mut self, key_list: &[K]) -> Self {
+        self.configuration.set_bytesize_keys(key_list);
+        self
+    }
+
+    /// Runs fallible steps in order of adding them and checks formats (see [ConfigurationBuilder]).
+    ///
+    /// It returns the first error.
+    pub fn build(self) -> Result<Configuration, Error> {
+        let Self {
+            mut configuration,
+            step_list,
+        } = self;
+        step_list
+            .into_iter()
+            .try_for_each(|step| step(&mut configuration))?;
+        let missing_format_list: Vec<_> = configuration
+            .extension_format_list
+            .iter()
+            .chain(configuration.plugin_format_list.iter())
+            .map(|(_, format)| format)
+            .chain(configuration.parser_probe_order.iter())
+            .chain(configuration.parser_fallback_list.iter())
+            .chain(
+                configuration
+                    .default_document_list
+                    .iter()
+                    .filter_map(|entity| entity.maybe_format()),
+            )
+            .filter(|format| !configuration.has_parser(format))
+            .fold(Vec::new(), |mut missing_format_list, format| {
+                if !missing_format_list.contains(&format) {
+                    missing_format_list.push(format);
+                }
+                missing_format_list
+            });
+        if !missing_format_list.is_empty() {
+            return Err(Error::Other(anyhow!(
+                "Could not find parser for format(s) {missing_format_list:?}"
+            )));
+        }
+        Ok(configuration)
+    }
+}
+
+impl Configuration {
+    pub fn url_list(&self) -> &[Url] {
+        self.url_list.as_slice()
+    }
+
+    pub fn has_url(&mut self, url: &Url) -> bool {
+        self.url_list.contains(url)
+    }
+
+    pub fn has_url_scheme(&mut self, url: &Url) -> bool {
+        self.url_list
+            .iter()
+            .any(|inner_url| inner_url.scheme() == url.scheme())
+    }
+
+    pub fn with_url(mut self, url: Url) -> Result<Self, Error> {
+        self.add_url(url)?;
+        Ok(self)
+    }
+
+    /// Adds `url` and its loader (if it's one of the included loaders).
+    ///
+    /// The URL is checked via [Loader::validate_url], so invalid options (e.g. a bad query string) fail here instead
+    /// of at load time.
+    pub fn add_url(&mut self, url: Url) -> Result<(), Error> {
+        let scheme = url.scheme().to_string();
+        let maybe_loader_name = if let Some(loader) = self
+            .loader_list
+            .iter()
+            .find(|loader| loader.matches_scheme(&scheme))
+        {
+            loader.validate_url(&url)?;
+            self.url_list.push(url.clone());
+            Some(loader.name())
+        } else {
+            #[allow(unused_mut)]
+            let mut included_loader_list: Vec<Box<dyn Loader>> = Vec::new();
+
+            #[cfg(feature = "env")]
+            included_loader_list.push(Box::new(crate::loader::env::Env::new()));
+
+            #[cfg(feature = "fs")]
+            included_loader_list.push(Box::new(crate::loader::fs::Fs::new()));
+
+            #[cfg(feature = "sqlite")]
+            included_loader_list.push(Box::new(crate::loader::sqlite::Sqlite::new()));
+
+            #[cfg(all(unix, feature = "unix-socket"))]
+            included_loader_list.push(Box::new(crate::loader::unix_socket::UnixSocket::new()));
+
+            #[cfg(feature = "aws")]
+            included_loader_list.push(Box::new(crate::loader::aws::SecretsManager::new()));
+
+            #[cfg(feature = "aws")]
+            included_loader_list.push(Box::new(crate::loader::aws::Ssm::new()));
+
+            #[cfg(feature = "mongodb")]
+            included_loader_list.push(Box::new(crate::loader::mongodb::Mongodb::new()));
+
+            #[cfg(feature = "postgres")]
+            included_loader_list.push(Box::new(crate::loader::postgres::Postgres::new()));
+
+            #[cfg(feature = "git")]
+            included_loader_list.push(Box::new(crate::loader::git::Git::new()));
+
+            if let Some(loader) = included_loader_list
+                .into_iter()
+                .find(|loader| loader.matches_scheme(&scheme))
+            {
+                loader.validate_url(&url)?;
+                let name = loader.name();
+                self.add_boxed_loader(loader);
+                self.url_list.push(url.clone());
+                Some(name)
+            } else {
+                None
+            }
+        };
+        maybe_loader_name.map(|_loader_name| {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::debug!(url=%url, loader=_loader_name, "Added configuration URL");
+                } else if #[cfg(feature = "logging")] {
+                    log::debug!("msg=\"Added configuration URL\", url=\"{url}\" loader={_loader_name:?}");
+                }
+            }
+            Ok(())
+        }).unwrap_or(Err(LoaderError::LoaderNotFound { scheme, url }.into()))
+    }
+
+    /// Expands `$NAME`, `${NAME}`, and `${NAME:-default}` in `template` from environment-variables (`$$` is an
+    /// escaped `$`) and adds the resulting URL via [Configuration::add_url].
+    ///
+    /// Unset variables without a default value yield an error. Values after the scheme are percent-encoded (except
+    /// `/` and `:`), so a value can not add options or other URL parts (e.g. `CONFIG_DIR=/x?soft-errors=all` in
+    /// `file://${CONFIG_DIR}` is the `/x%3Fsoft-errors%3Dall` path). A variable in place of the scheme (e.g.
+    /// `${CONFIG_URL}`) is used as is.
+    pub fn add_url_template<T: AsRef<str>>(&mut self, template: T) -> Result<(), Error> {
+        let template = template.as_ref();
+        let url = crate::expand::expand_env_vars_with(template, |expanded, value| {
+            if expanded.contains(':') {
+                utf8_percent_encode(value.as_str(), URL_TEMPLATE_VALUE_ENCODE_SET).to_string()
+            } else {
+                value
+            }
+        })
+        .map_err(|error| {
+            Error::Other(anyhow!(
+                "Could not expand URL template {template:?}: {error}"
+            ))
+        })?;
+        let url = Url::parse(url.as_str()).map_err(|error| {
+            Error::Other(anyhow!(
+                "Could not parse URL {url:?} expanded from template {template:?}: {error}"
+            ))
+        })?;
+        self.add_url(url)
+    }
+
+    pub fn with_url_template<T: AsRef<str>>(mut self, template: T) -> Result<Self, Error> {
+        self.add_url_template(template)?;
+        Ok(self)
+    }
+
+    /// Adds a filesystem path (file or directory) as a `file://` URL.
+    ///
+    /// The path must be absolute (e.g. use [std::fs::canonicalize] or [std::env::current_dir] for relative paths).
+    #[cfg(feature = "fs")]
+    pub fn add_path<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        if !path.is_absolute() {
+            return Err(Error::Other(anyhow!(
+                "Could not add relative path {path:?} (make it absolute e.g. by joining it to the current directory)"
+            )));
+        }
+        let url = if path.is_dir() {
+            Url::from_directory_path(path)
+        } else {
+            Url::from_file_path(path)
+        }
+        .map_err(|_| {
+            Error::Other(anyhow!(
+                "Could not convert path {path:?} to a `file://` URL"
+            ))
+        })?;
+        self.add_url(url)
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn with_path<P: AsRef<std::path::Path>>(mut self, path: P) -> Result<Self, Error> {
+        self.add_path(path)?;
+        Ok(self)
+    }
+
+    /// Number of URLs per scheme.
+    pub fn scheme_stats(&self) -> HashMap<String, usize> {
+        let mut result = HashMap::new();
+        self.url_list.iter().for_each(|url| {
+            *result.entry(url.scheme().to_string()).or_default() += 1;
+        });
+        result
+    }
+
+    /// Number of URLs per loader name (loaders with no URL are included with zero).
+    pub fn loader_stats(&self) -> HashMap<String, usize> {
+        let mut result: HashMap<String, usize> = self
+            .loader_list
+            .iter()
+            .map(|loader| (loader.name(), 0))
+            .collect();
+        self.url_list.iter().for_each(|url| {
+            let scheme = url.scheme().to_string();
+            if let Some(loader) = self
+                .loader_list
+                .iter()
+                .find(|loader| loader.matches_scheme(&scheme))
+            {
+                *result.entry(loader.name()).or_default() += 1;
+            }
+        });
+        result
+    }
+
+    pub fn remove_url(&mut self, url: &Url) -> bool {
+        let mut result = false;
+        while let Some(index) = self.url_list.iter().position(|inner_url| inner_url == url) {
+            self.url_list.remove(index);
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::debug!(url=%url, "Removed URL")
+                } else if #[cfg(feature = "logging")] {
+                    log::debug!("msg=\"Removed URL\" url=\"{url}\"")
+                }
+            }
+            result = true;
+        }
+        result
+    }
+
+    pub fn remove_scheme<S: AsRef<str>>(&mut self, scheme: S) -> Vec<Url> {
+        let mut url_list = Vec::new();
+        while let Some(url) = self
+            .url_list
+            .iter()
+            .find(|url| url.scheme() == scheme.as_ref())
+        {
+            url_list.push(url.clone())
+        }
+        url_list.iter().for_each(|url| {
+            self.remove_url(url);
+        });
+        url_list
+    }
+
+    /// Removes and returns all URLs (their loaders are kept).
+    pub fn clear_urls(&mut self) -> Vec<Url> {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(count=self.url_list.len(), "Removed all configuration URLs");
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Removed all configuration URLs\" count={}", self.url_list.len());
+            }
+        }
+        std::mem::take(&mut self.url_list)
+    }
+}
+
+impl Configuration {
+    pub fn has_loader(&mut self, url: &Url) -> bool {
+        let scheme = url.scheme().to_string();
+        self.loader_list
+            .iter()
+            .any(|loader| loader.matches_scheme(&scheme))
+    }
+
+    pub fn loaders(&self) -> Vec<(String, Vec<String>)> {
+        self.loader_list
+            .iter()
+            .map(|loader| (loader.name(), loader.scheme_list()))
+            .collect()
+    }
+
+    pub fn with_loader<L>(mut self, loader: L) -> Self
+    where
+        L: Loader + 'static,
+    {
+        self.add_boxed_loader(Box::new(loader));
+        self
+    }
+
+    pub fn add_loader<L>(&mut self, loader: L)
+    where
+        L: Loader + 'static,
+    {
+        self.add_boxed_loader(Box::new(loader));
+    }
+
+    /// Adds `loader` for `scheme_list` instead of its own schemes (see [crate::loader::schemes::Schemes]).
+    pub fn with_loader_for_schemes<L, S>(mut self, loader: L, scheme_list: &[S]) -> Self
+    where
+        L: Loader + 'static,
+        S: AsRef<str>,
+    {
+        self.add_loader_for_schemes(loader, scheme_list);
+        self
+    }
+
+    /// Adds `loader` for `scheme_list` instead of its own schemes (see [crate::loader::schemes::Schemes]).
+    pub fn add_loader_for_schemes<L, S>(&mut self, loader: L, scheme_list: &[S])
+    where
+        L: Loader + 'static,
+        S: AsRef<str>,
+    {
+        self.add_loader(crate::loader::schemes::Schemes::new(loader, scheme_list));
+    }
+
+    pub fn with_boxed_loader(mut self, loader: Box<dyn Loader>) -> Self {
+        self.add_boxed_loader(loader);
+        self
+    }
+
+    pub fn add_boxed_loader(&mut self, mut loader: Box<dyn Loader>) {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(
+                    loader=%loader,
+                    schema_list=?loader.scheme_list(),
+                    "Added configuration loader"
+                );
+            } else if #[cfg(feature = "logging")] {
+                log::debug!(
+                    "msg=\"Added configuration loader\" loader=\"{loader}\" schema_list={:?}",
+                    loader.scheme_list()
+                );
+            }
+        }
+        if self.log_options != LogOptions::default() {
+            loader.set_log_options(self.log_options);
+        }
+        self.loader_list.push(loader);
+    }
+
+    pub fn remove_loader_and_urls<S: AsRef<str>>(
+        &mut self,
+        scheme: S,
+    ) -> Option<(Box<dyn Loader>, Vec<Url>)> {
+        let scheme_string = scheme.as_ref().to_string();
+        if let Some(index) = self
+            .loader_list
+            .iter()
+            .position(|loader| loader.matches_scheme(&scheme_string))
+        {
             let loader = self.loader_list.swap_remove(index);
             cfg_if! {
                 if #[cfg(feature = "tracing")] {
-                    tracing::debug!(
-                        loader=%loader,
-                        schema_list=?loader.scheme_list(),
-                        "Removed configuration loader"
-                    );
+                    tracing::debug!(
+                        loader=%loader,
+                        schema_list=?loader.scheme_list(),
+                        "Removed configuration loader"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::debug!(
+                        "message=\"Removed configuration loader\" loader=\"{loader}\" schema_list={:?}",
+                        loader.scheme_list()
+                    );
+                }
+            }
+            Some((loader, self.remove_scheme(scheme)))
+        } else {
+            None
+        }
+    }
+
+    /// With `false`, loading continues past URLs that fail to load and all of their errors are returned together in
+    /// [Error::MultipleLoad]. Defaults to `true` which returns the first error.
+    pub fn set_fail_fast(&mut self, fail_fast: bool) {
+        self.maybe_fail_fast = Some(fail_fast);
+    }
+
+    /// See [Configuration::set_fail_fast].
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.set_fail_fast(fail_fast);
+        self
+    }
+
+    pub fn fail_fast(&self) -> bool {
+        self.maybe_fail_fast.unwrap_or(true)
+    }
+
+    /// Sets which configuration of `plugin` wins when several sources set the same key (defaults to
+    /// [SourcePrecedence::LastWins]).
+    pub fn set_plugin_precedence<P: AsRef<str>>(
+        &mut self,
+        plugin: P,
+        precedence: SourcePrecedence,
+    ) {
+        let plugin_name = plugin.as_ref().to_lowercase();
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(plugin=plugin_name, precedence=?precedence, "Set plugin precedence");
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Set plugin precedence\" plugin={plugin_name:?} precedence={precedence:?}");
+            }
+        }
+        self.plugin_precedence_list
+            .retain(|(inner_plugin_name, _)| inner_plugin_name != &plugin_name);
+        self.plugin_precedence_list.push((plugin_name, precedence));
+    }
+
+    /// See [Configuration::set_plugin_precedence].
+    pub fn with_plugin_precedence<P: AsRef<str>>(
+        mut self,
+        plugin: P,
+        precedence: SourcePrecedence,
+    ) -> Self {
+        self.set_plugin_precedence(plugin, precedence);
+        self
+    }
+
+    pub fn plugin_precedence<P: AsRef<str>>(&self, plugin: P) -> SourcePrecedence {
+        let plugin_name = plugin.as_ref().to_lowercase();
+        self.plugin_precedence_list
+            .iter()
+            .find(|(inner_plugin_name, _)| inner_plugin_name == &plugin_name)
+            .map(|(_, precedence)| *precedence)
+            .unwrap_or_default()
+    }
+
+    pub fn load(
+        &self,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
+        self.load_and_time(skip_soft_errors, None)
+    }
+
+    /// Loads only `url` (which does not need to be added) with its loader and the current whitelist.
+    ///
+    /// Unlike [Configuration::load], default documents are not included. It's useful for finding out which source
+    /// introduces a value.
+    pub fn load_single_url(
+        &self,
+        url: &Url,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("load", url=%url).entered();
+        let scheme_string = url.scheme().to_string();
+        let loader = self
+            .loader_list
+            .iter()
+            .find(|loader| loader.matches_scheme(&scheme_string))
+            .ok_or_else(|| LoaderError::LoaderNotFound {
+                scheme: scheme_string,
+                url: url.clone(),
+            })?;
+        warn_unsupported_soft_errors(loader.as_ref(), url, skip_soft_errors);
+        let maybe_prefixed_whitelist = self.prefixed_whitelist(self.maybe_whitelist.as_deref());
+        let maybe_loader_whitelist = maybe_prefixed_whitelist
+            .as_deref()
+            .or(self.maybe_whitelist.as_deref());
+        let mut loaded = loader.load(url, maybe_loader_whitelist, skip_soft_errors)?;
+        filter_unsupported_whitelist(&mut loaded, loader.as_ref(), maybe_loader_whitelist);
+        if self.plugin_prefix_strip_list.is_empty() {
+            return Ok(loaded);
+        }
+        Ok(loaded
+            .into_iter()
+            .map(|(plugin_name, mut entity)| {
+                let plugin_name = self.strip_plugin_prefix(plugin_name.as_str()).to_string();
+                *entity.plugin_name_mut() = plugin_name.clone();
+                (plugin_name, entity)
+            })
+            .filter(|(plugin_name, _)| {
+                self.maybe_whitelist
+                    .as_ref()
+                    .map(|whitelist| whitelist.contains(plugin_name))
+                    .unwrap_or(true)
+            })
+            .collect())
+    }
+
+    /// Same as [Configuration::load] but also records when each URL was loaded successfully.
+    ///
+    /// URLs that were loaded before a failing URL are still recorded. See [Configuration::last_loaded] and
+    /// [Configuration::freshness].
+    pub fn load_mut(
+        &mut self,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
+        let mut url_load_list = Vec::new();
+        let result = self.load_and_time(skip_soft_errors, Some(&mut url_load_list));
+        let now = SystemTime::now();
+        url_load_list.into_iter().for_each(|(url, _)| {
+            self.last_loaded_list.insert(url, now);
+        });
+        result
+    }
+
+    /// Returns when `url` was last loaded successfully by [Configuration::load_mut] or
+    /// [Configuration::load_parse_merge_mut].
+    pub fn last_loaded(&self, url: &Url) -> Option<SystemTime> {
+        self.last_loaded_list.get(url).copied()
+    }
+
+    /// Returns each configuration URL (in order) with the time it was last loaded successfully.
+    ///
+    /// URLs that were never loaded by [Configuration::load_mut] or [Configuration::load_parse_merge_mut] have `None`.
+    pub fn freshness(&self) -> Vec<(Url, Option<SystemTime>)> {
+        self.url_list
+            .iter()
+            .map(|url| (url.clone(), self.last_loaded(url)))
+            .collect()
+    }
+
+    fn load_and_time(
+        &self,
+        skip_soft_errors: bool,
+        maybe_url_load_list: Option<&mut Vec<(Url, Duration)>>,
+    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
+        self.load_whitelisted(
+            self.maybe_whitelist.as_deref(),
+            skip_soft_errors,
+            maybe_url_load_list,
+        )
+    }
+
+    fn load_whitelisted(
+        &self,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+        maybe_url_load_list: Option<&mut Vec<(Url, Duration)>>,
+    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
+        let mut maybe_url_load_list = maybe_url_load_list;
+        let (result, error_list) = self.load_url_list(
+            false,
+            maybe_whitelist,
+            skip_soft_errors,
+            self.fail_fast(),
+            &mut |url, result, duration| {
+                if let (Some(url_load_list), Ok(_)) = (maybe_url_load_list.as_mut(), result) {
+                    url_load_list.push((url.clone(), duration));
+                }
+            },
+        )?;
+        if !error_list.is_empty() {
+            return Err(Error::MultipleLoad { error_list });
+        }
+        Ok(result)
+    }
+
+    /// Loads URLs one by one, strips plugin prefixes of each, and prepares the result (see
+    /// [Configuration::prepare_loaded]).
+    ///
+    /// With `detached`, URLs are loaded with clones of loaders (see [load_detached]). `on_url` is called with the
+    /// outcome and duration of loading each URL. Errors are returned at once with `fail_fast`, otherwise they are
+    /// collected and returned next to configurations of other URLs.
+    #[allow(clippy::type_complexity)]
+    fn load_url_list(
+        &self,
+        detached: bool,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+        fail_fast: bool,
+        on_url: &mut dyn FnMut(
+            &Url,
+            Result<&[(String, Vec<ConfigurationEntity>)], &LoaderError>,
+            Duration,
+        ),
+    ) -> Result<
+        (
+            Vec<(String, Vec<ConfigurationEntity>)>,
+            Vec<(Url, LoaderError)>,
+        ),
+        Error,
+    > {
+        let maybe_prefixed_whitelist = self.prefixed_whitelist(maybe_whitelist);
+        let maybe_loader_whitelist = maybe_prefixed_whitelist.as_deref().or(maybe_whitelist);
+        let mut result: Vec<(String, Vec<ConfigurationEntity>)> = Vec::new();
+        let mut error_list = Vec::new();
+        for url in self.url_list.iter() {
+            let start = Instant::now();
+            let load_result = if detached {
+                load_detached(
+                    std::slice::from_ref(url),
+                    self.loader_list.as_slice(),
+                    maybe_loader_whitelist,
+                    skip_soft_errors,
+                )
+            } else {
+                load(
+                    std::slice::from_ref(url),
+                    self.loader_list.as_slice(),
+                    maybe_loader_whitelist,
+                    skip_soft_errors,
+                )
+            };
+            match load_result {
+                Ok(loaded) => {
+                    on_url(url, Ok(loaded.as_slice()), start.elapsed());
+                    append_loaded(&mut result, self.strip_plugin_prefixes(loaded));
+                }
+                Err(error) if !fail_fast => {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::warn!(url=%url, error=%error, "Could not load configuration, continuing");
+                        } else if #[cfg(feature = "logging")] {
+                            log::warn!(
+                                "msg=\"Could not load configuration, continuing\" url=\"{url}\" error={:?}",
+                                error.to_string()
+                            );
+                        }
+                    }
+                    on_url(url, Err(&error), start.elapsed());
+                    error_list.push((url.clone(), error));
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok((self.prepare_loaded(result, maybe_whitelist), error_list))
+    }
+
+    /// Applies the whitelist and plugin patterns, and adds default documents to loaded configurations (whose plugin
+    /// prefixes are already stripped).
+    fn prepare_loaded(
+        &self,
+        result: Vec<(String, Vec<ConfigurationEntity>)>,
+        maybe_whitelist: Option<&[String]>,
+    ) -> Vec<(String, Vec<ConfigurationEntity>)> {
+        let mut result = result;
+        // Loaders are also asked for prefixed plugin names (see `prefixed_whitelist`):
+        if let Some(whitelist) =
+            maybe_whitelist.filter(|_| !self.plugin_prefix_strip_list.is_empty())
+        {
+            result.retain(|(plugin_name, _)| whitelist.contains(plugin_name));
+        }
+        if !self.plugin_pattern_list.is_empty() {
+            result.retain(|(plugin_name, _)| {
+                self.plugin_pattern_list
+                    .iter()
+                    .any(|pattern| pattern.is_match(plugin_name))
+            });
+        }
+        self.default_document_list.iter().rev().for_each(|entity| {
+            let plugin_name = entity.plugin_name();
+            if let Some(whitelist) = maybe_whitelist {
+                if !whitelist.contains(plugin_name) {
+                    return;
+                }
+            }
+            if !self.plugin_pattern_list.is_empty()
+                && !self
+                    .plugin_pattern_list
+                    .iter()
+                    .any(|pattern| pattern.is_match(plugin_name))
+            {
+                return;
+            }
+            if let Some((_, entity_list)) = result
+                .iter_mut()
+                .find(|(loaded_plugin_name, _)| loaded_plugin_name == plugin_name)
+            {
+                entity_list.insert(0, entity.clone());
+            } else if self.always_include_default_documents {
+                result.push((plugin_name.clone(), [entity.clone()].to_vec()));
+            }
+        });
+        result
+    }
+
+    pub fn plan(&self, skip_soft_errors: bool) -> Result<Vec<LoadPlanItem>, Error> {
+        let mut result = Vec::new();
+        self.url_list.iter().try_for_each(|url| {
+            let scheme_string = url.scheme().to_string();
+            let loader = self
+                .loader_list
+                .iter()
+                .find(|loader| loader.matches_scheme(&scheme_string))
+                .ok_or_else(|| LoaderError::LoaderNotFound {
+                    scheme: scheme_string,
+                    url: url.clone(),
+                })?;
+            let supports_soft_errors = loader.supports_soft_errors();
+            match loader.plan(url, self.maybe_whitelist.as_deref(), skip_soft_errors)? {
+                Some(entity_list) => entity_list.into_iter().for_each(|entity| {
+                    result.push(LoadPlanItem {
+                        url: url.clone(),
+                        maybe_plugin_name: Some(entity.plugin_name().clone()),
+                        maybe_format: entity.maybe_format().cloned(),
+                        supports_soft_errors,
+                    })
+                }),
+                None => result.push(LoadPlanItem {
+                    url: url.clone(),
+                    maybe_plugin_name: None,
+                    maybe_format: None,
+                    supports_soft_errors,
+                }),
+            }
+            Ok::<_, Error>(())
+        })?;
+        Ok(result)
+    }
+}
+
+impl Configuration {
+    pub fn has_parser<F: AsRef<str>>(&self, format: F) -> bool {
+        let format = format.as_ref().to_lowercase();
+        self.parser_list
+            .iter()
+            .any(|parser| parser.supported_format_list().contains(&format))
+    }
+
+    /// Returns names of parsers that support `format` in order of adding them (the first one parses it).
+    pub fn parsers_supporting<F: AsRef<str>>(&self, format: F) -> Vec<String> {
+        let format = format.as_ref().to_lowercase();
+        self.parser_list
+            .iter()
+            .filter(|parser| parser.supported_format_list().contains(&format))
+            .map(|parser| parser.name())
+            .collect()
+    }
+
+    pub fn parsers(&self) -> Vec<(String, Vec<String>)> {
+        self.parser_list
+            .iter()
+            .map(|parser| (parser.name(), parser.supported_format_list()))
+            .collect()
+    }
+
+    pub fn with_parser<P>(mut self, parser: P) -> Self
+    where
+        P: Parser + 'static,
+    {
+        self.add_parser(parser);
+        self
+    }
+
+    pub fn add_parser<P>(&mut self, parser: P)
+    where
+        P: Parser + 'static,
+    {
+        self.add_boxed_parser(Box::new(parser));
+    }
+
+    pub fn with_boxed_parser(mut self, parser: Box<dyn Parser>) -> Self {
+        self.add_boxed_parser(parser);
+        self
+    }
+
+    pub fn add_boxed_parser(&mut self, parser: Box<dyn Parser>) {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(
+                    parser=%parser,
+                    format_list=?parser.supported_format_list(),
+                    "Added configuration parser"
+                );
+            } else if #[cfg(feature = "logging")] {
+                log::debug!(
+                    "msg=\"Added configuration parser\" parser=\"{parser}\" format_list={:?}",
+                    parser.supported_format_list()
+                );
+            }
+        }
+        self.parser_list.push(parser);
+    }
+
+    /// Maps configurations with format (e.g. file extension) `extension` to `format`.
+    ///
+    /// The format of a configuration is resolved in this order:
+    /// 1. Explicit overrides: the format of its plugin (see [Configuration::add_plugin_format]), otherwise the mapped
+    ///    MIME type of its contents (see [Configuration::map_mime]).
+    /// 2. Format set by its loader (e.g. file extension) which is mapped here (or kept as is if it's not mapped).
+    /// 3. If the loader did not set the format, content sniffing: mapped formats whose parser supports the contents
+    ///    (in order of adding them), then first parser that supports the contents (see
+    ///    [Configuration::set_parser_probe_order]), then first fallback parser that parses the contents (see
+    ///    [Configuration::set_parser_fallback]).
+    ///
+    /// Extensions are matched case-insensitively (both `extension` and formats set by loaders are lowercased).
+    pub fn add_extension_format<E: AsRef<str>, F: AsRef<str>>(&mut self, extension: E, format: F) {
+        let (extension, format) = (
+            extension.as_ref().to_lowercase(),
+            format.as_ref().to_lowercase(),
+        );
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(extension=extension, format=format, "Added extension format")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added extension format\" extension={extension:?} format={format:?}")
+            }
+        }
+        self.extension_format_list
+            .retain(|(inner_extension, _)| inner_extension != &extension);
+        self.extension_format_list.push((extension, format));
+    }
+
+    pub fn with_extension_format<E: AsRef<str>, F: AsRef<str>>(
+        mut self,
+        extension: E,
+        format: F,
+    ) -> Self {
+        self.add_extension_format(extension, format);
+        self
+    }
+
+    /// Parses all configurations of `plugin` with `format` regardless of their extension, MIME type, or contents (see
+    /// [Configuration::add_extension_format] for the whole resolution order).
+    pub fn add_plugin_format<P: AsRef<str>, F: AsRef<str>>(&mut self, plugin: P, format: F) {
+        let (plugin_name, format) = (
+            plugin.as_ref().to_lowercase(),
+            format.as_ref().to_lowercase(),
+        );
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(plugin=plugin_name, format=format, "Added plugin format")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added plugin format\" plugin={plugin_name:?} format={format:?}")
+            }
+        }
+        self.plugin_format_list
+            .retain(|(inner_plugin_name, _)| inner_plugin_name != &plugin_name);
+        self.plugin_format_list.push((plugin_name, format));
+    }
+
+    pub fn with_plugin_format<P: AsRef<str>, F: AsRef<str>>(
+        mut self,
+        plugin: P,
+        format: F,
+    ) -> Self {
+        self.add_plugin_format(plugin, format);
+        self
+    }
+
+    /// Adds `parser` and maps `extension` to its first supported format (see [Configuration::add_extension_format]).
+    pub fn add_parser_for_extension<P, E>(&mut self, parser: P, extension: E)
+    where
+        P: Parser + 'static,
+        E: AsRef<str>,
+    {
+        if let Some(format) = parser.supported_format_list().first() {
+            self.add_extension_format(extension, format);
+        }
+        self.add_parser(parser);
+    }
+
+    pub fn with_parser_for_extension<P, E>(mut self, parser: P, extension: E) -> Self
+    where
+        P: Parser + 'static,
+        E: AsRef<str>,
+    {
+        self.add_parser_for_extension(parser, extension);
+        self
+    }
+
+    /// Maps MIME type (e.g. `Content-Type` of a response) `mime` to `format`.
+    ///
+    /// Loaders that know the content type of a configuration set it as [CONTENT_TYPE_METADATA_KEY] metadata of its
+    /// entity, and its format is set to the mapped format before parsing (even if the loader has set a format). Common
+    /// MIME types are mapped by default: `application/json` and `text/json` to `json`, `application/yaml`,
+    /// `application/x-yaml`, `text/yaml`, and `text/x-yaml` to `yaml`, `application/toml` and `text/x-toml` to `toml`,
+    /// and `application/hjson` to `hjson`.
+    pub fn map_mime<M: AsRef<str>, F: AsRef<str>>(&mut self, mime: M, format: F) {
+        let (mime, format) = (
+            normalize_mime(mime.as_ref()),
+            format.as_ref().to_lowercase(),
+        );
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(mime=mime, format=format, "Added MIME format")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added MIME format\" mime={mime:?} format={format:?}")
+            }
+        }
+        self.mime_format_list
+            .retain(|(inner_mime, _)| inner_mime != &mime);
+        self.mime_format_list.push((mime, format));
+    }
+
+    pub fn with_mapped_mime<M: AsRef<str>, F: AsRef<str>>(mut self, mime: M, format: F) -> Self {
+        self.map_mime(mime, format);
+        self
+    }
+
+    /// Adds `parser` and maps `mime` to its first supported format (see [Configuration::map_mime]).
+    pub fn add_parser_for_mime<P, M>(&mut self, parser: P, mime: M)
+    where
+        P: Parser + 'static,
+        M: AsRef<str>,
+    {
+        if let Some(format) = parser.supported_format_list().first() {
+            self.map_mime(mime, format);
+        }
+        self.add_parser(parser);
+    }
+
+    pub fn with_parser_for_mime<P, M>(mut self, parser: P, mime: M) -> Self
+    where
+        P: Parser + 'static,
+        M: AsRef<str>,
+    {
+        self.add_parser_for_mime(parser, mime);
+        self
+    }
+
+    /// Returns the mapped format of `mime` (see [Configuration::map_mime]).
+    ///
+    /// MIME type parameters (e.g. `; charset=utf-8`) and case are ignored. If `mime` itself is not mapped but has a
+    /// structured syntax suffix (e.g. `application/vnd.example+json`), the format of `application/<suffix>` is used.
+    pub fn format_for_mime<M: AsRef<str>>(&self, mime: M) -> Option<String> {
+        format_for_mime(mime.as_ref(), self.mime_format_list.as_slice())
+    }
+
+    /// Sets the order of parsers (by their formats) to try when a configuration has no format and its format should
+    /// be guessed from its contents (e.g. JSON is also valid YAML).
+    ///
+    /// Parsers of formats that are not listed are tried after the listed ones in order of adding them.
+    pub fn set_parser_probe_order<F: AsRef<str>>(&mut self, format_list: &[F]) {
+        self.parser_probe_order = format_list
+            .iter()
+            .map(|format| format.as_ref().to_lowercase())
+            .collect();
+    }
+
+    /// Sets the order of parsers (by their formats) to try when a configuration has no format (see
+    /// [Configuration::set_parser_probe_order]).
+    pub fn with_parser_probe_order<F: AsRef<str>>(mut self, format_list: &[F]) -> Self {
+        self.set_parser_probe_order(format_list);
+        self
+    }
+
+    pub fn parser_probe_order(&self) -> &[String] {
+        self.parser_probe_order.as_slice()
+    }
+
+    /// Sets parsers (by their formats) to actually parse a configuration with, in order, when it has no format and no
+    /// parser supports its contents.
+    ///
+    /// The first parser that parses the contents wins, and if all of them fail, the error of the last one is returned.
+    /// It's more forgiving than [Parser::is_format_supported] checks for ambiguous sources.
+    pub fn set_parser_fallback<F: AsRef<str>>(&mut self, format_list: &[F]) {
+        self.parser_fallback_list = format_list
+            .iter()
+            .map(|format| format.as_ref().to_lowercase())
+            .collect();
+    }
+
+    /// Sets parsers (by their formats) to parse configurations without a detectable format with (see
+    /// [Configuration::set_parser_fallback]).
+    pub fn with_parser_fallback<F: AsRef<str>>(mut self, format_list: &[F]) -> Self {
+        self.set_parser_fallback(format_list);
+        self
+    }
+
+    pub fn parser_fallback(&self) -> &[String] {
+        self.parser_fallback_list.as_slice()
+    }
+
+    /// Removes parsers that support `format` and keeps the order of other parsers (which is their probe order).
+    pub fn remove_parser<F: AsRef<str>>(&mut self, format: F) -> Vec<Box<dyn Parser>> {
+        let format = format.as_ref().to_lowercase();
+        let mut parser_list = Vec::new();
+        while let Some(index) = self
+            .parser_list
+            .iter()
+            .position(|parser| parser.supported_format_list().contains(&format))
+        {
+            let parser = self.parser_list.remove(index);
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::debug!(
+                        parser=%parser,
+                        format_list=?parser.supported_format_list(),
+                        "Removed configuration parser"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::debug!(
+                        "msg=\"Removed configuration parser\" parser=\"{parser}\" format_list={:?}",
+                        parser.supported_format_list()
+                    );
+                }
+            }
+            parser_list.push(parser)
+        }
+        parser_list
+    }
+
+    /// Parses up to `parse_concurrency` configurations at the same time (in scoped threads). Loading is still
+    /// sequential.
+    ///
+    /// Defaults to `1` which parses configurations one by one in the current thread (so does `0`). Use
+    /// [std::thread::available_parallelism] for the number of CPUs.
+    pub fn set_parse_concurrency(&mut self, parse_concurrency: usize) {
+        self.maybe_parse_concurrency = Some(parse_concurrency.max(1));
+    }
+
+    /// Parses up to `parse_concurrency` configurations at the same time (see [Configuration::set_parse_concurrency]).
+    pub fn with_parse_concurrency(mut self, parse_concurrency: usize) -> Self {
+        self.set_parse_concurrency(parse_concurrency);
+        self
+    }
+
+    pub fn parse_concurrency(&self) -> usize {
+        self.maybe_parse_concurrency.unwrap_or(1)
+    }
+
+    /// Fails parsing if a configuration has values nested deeper than `max_depth` maps and lists (defaults to `128`).
+    ///
+    /// Merging and other transforms of configurations are recursive, so this guards them against pathological nesting
+    /// (e.g. of a malicious source) instead of overflowing the stack. It's checked after input preprocessors and
+    /// aliases are applied since they may nest values deeper.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.maybe_max_depth = Some(max_depth);
+    }
+
+    /// See [Configuration::set_max_depth].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.set_max_depth(max_depth);
+        self
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.maybe_max_depth.unwrap_or(128)
+    }
+
+    pub fn load_and_parse(
+        &self,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
+        let mut load_result = self.load(skip_soft_errors)?;
+        self.parse_loaded(load_result.as_mut())?;
+        Ok(load_result)
+    }
+
+    fn parse_loaded(
+        &self,
+        load_result: &mut [(String, Vec<ConfigurationEntity>)],
+    ) -> Result<(), Error> {
+        apply_extension_format_list(
+            load_result,
+            self.parser_list.as_slice(),
+            self.extension_format_list.as_slice(),
+        );
+        apply_mime_format_list(load_result, self.mime_format_list.as_slice());
+        apply_plugin_format_list(load_result, self.plugin_format_list.as_slice());
+        apply_parser_probe_order(
+            load_result,
+            self.parser_list.as_slice(),
+            self.parser_probe_order.as_slice(),
+        );
+        apply_parser_fallback(
+            load_result,
+            self.parser_list.as_slice(),
+            self.parser_fallback_list.as_slice(),
+        )?;
+        parse_concurrently(
+            load_result,
+            self.parser_list.as_slice(),
+            self.parse_concurrency(),
+            self.log_options,
+        )?;
+        self.preprocess_loaded(load_result);
+        alias(
+            load_result,
+            self.alias_list.as_slice(),
+            self.alias_conflict_policy,
+        )?;
+        // After everything that may nest values deeper, but before recursively walking them:
+        check_depth(load_result, self.max_depth())?;
+        self.coerce_nulls(load_result);
+        Ok(())
+    }
+
+    fn coerce_nulls(&self, load_result: &mut [(String, Vec<ConfigurationEntity>)]) {
+        let Some(sentinel_list) = self.maybe_null_sentinel_list.as_deref() else {
+            return;
+        };
+        load_result
+            .iter_mut()
+            .for_each(|(plugin_name, configuration_list)| {
+                let precedence = self.plugin_precedence(plugin_name.as_str());
+                let index_list = merge_index_list(configuration_list, precedence);
+                for (position, index) in index_list.iter().enumerate() {
+                    let _url = configuration_list[*index].url().clone();
+                    let Some(input) = configuration_list[*index].maybe_parsed_contents_mut() else {
+                        continue;
+                    };
+                    let path_list = take_null_path_list(input, sentinel_list);
+                    path_list.iter().for_each(|_path| {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::trace!(plugin=plugin_name.as_str(), url=%_url, key=%_path.join("."), "Coerced value to null");
+                            } else if #[cfg(feature = "logging")] {
+                                log::trace!("msg=\"Coerced value to null\" plugin={plugin_name:?} url=\"{_url}\" key={:?}", _path.join("."));
+                            }
+                        }
+                    });
+                    if !self.null_unsets {
+                        continue;
+                    }
+                    // Configurations that are merged before this one:
+                    index_list[..position].iter().for_each(|index| {
+                        if let Some(input) = configuration_list[*index].maybe_parsed_contents_mut() {
+                            path_list
+                                .iter()
+                                .for_each(|path| remove_path(input, path.as_slice()));
+                        }
+                    });
+                }
+            });
+    }
+
+    fn preprocess_loaded(&self, load_result: &mut [(String, Vec<ConfigurationEntity>)]) {
+        if self.input_preprocessor_list.0.is_empty() {
+            return;
+        }
+        load_result
+            .iter_mut()
+            .for_each(|(plugin_name, configuration_list)| {
+                configuration_list.iter_mut().for_each(|configuration| {
+                    let url = configuration.url().clone();
+                    if let Some(input) = configuration.maybe_parsed_contents_mut() {
+                        self.input_preprocessor_list
+                            .0
+                            .iter()
+                            .for_each(|preprocessor| preprocessor(plugin_name, &url, input));
+                    }
+                })
+            });
+    }
+
+    /// Adds a function that is called with parsed contents of each source (including sources that are parsed by their
+    /// loaders and default documents) before merging them, e.g. to lowercase all keys.
+    ///
+    /// Preprocessors are called in order of adding them, right after parsing (so aliases and null sentinels are applied
+    /// to their outputs).
+    pub fn add_input_preprocessor(&mut self, preprocessor: BoxedInputPreprocessorFn) {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!("Added input preprocessor");
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added input preprocessor\"");
+            }
+        }
+        self.input_preprocessor_list.0.push(Arc::from(preprocessor));
+    }
+
+    pub fn with_input_preprocessor(mut self, preprocessor: BoxedInputPreprocessorFn) -> Self {
+        self.add_input_preprocessor(preprocessor);
+        self
+    }
+
+    /// Treats string values that are equal to a null sentinel (defaults to `"null"` and `""`, see
+    /// [Configuration::set_null_sentinels]) as null when parsing.
+    ///
+    /// [Input] has no null, so such keys are removed from the parsed contents of their source, and values of lower
+    /// priority sources are kept for them unless [Configuration::set_null_unsets] is set.
+    pub fn set_null_coercion(&mut self, null_coercion: bool) {
+        if !null_coercion {
+            self.maybe_null_sentinel_list = None;
+        } else if self.maybe_null_sentinel_list.is_none() {
+            self.maybe_null_sentinel_list = Some(["null".to_string(), String::new()].to_vec());
+        }
+    }
+
+    /// See [Configuration::set_null_coercion].
+    pub fn with_null_coercion(mut self, null_coercion: bool) -> Self {
+        self.set_null_coercion(null_coercion);
+        self
+    }
+
+    /// Enables null coercion (see [Configuration::set_null_coercion]) with these sentinels instead of the default
+    /// ones.
+    pub fn set_null_sentinels<S: AsRef<str>>(&mut self, sentinel_list: &[S]) {
+        self.maybe_null_sentinel_list = Some(
+            sentinel_list
+                .iter()
+                .map(|sentinel| sentinel.as_ref().to_string())
+                .collect(),
+        );
+    }
+
+    /// See [Configuration::set_null_sentinels].
+    pub fn with_null_sentinels<S: AsRef<str>>(mut self, sentinel_list: &[S]) -> Self {
+        self.set_null_sentinels(sentinel_list);
+        self
+    }
+
+    /// Returns null sentinels if null coercion is enabled.
+    pub fn null_sentinels(&self) -> Option<&[String]> {
+        self.maybe_null_sentinel_list.as_deref()
+    }
+
+    /// With `true`, a coerced null (see [Configuration::set_null_coercion]) also removes the key from configurations
+    /// that are merged before its source, so a higher priority source can unset a value of a lower priority one.
+    pub fn set_null_unsets(&mut self, null_unsets: bool) {
+        self.null_unsets = null_unsets;
+    }
+
+    /// See [Configuration::set_null_unsets].
+    pub fn with_null_unsets(mut self, null_unsets: bool) -> Self {
+        self.set_null_unsets(null_unsets);
+        self
+    }
+
+    pub fn null_unsets(&self) -> bool {
+        self.null_unsets
+    }
+
+    /// With `false`, trace logs of loaders and parsers omit contents (e.g. read files and parsed outputs) and only
+    /// contain lengths of read contents. Defaults to `true`.
+    ///
+    /// It applies to current loaders and the ones that are added later, and to parsers while this configuration
+    /// parses (see [LogOptions]).
+    pub fn set_trace_contents(&mut self, trace_contents: bool) {
+        self.log_options.trace_contents = trace_contents;
+        let log_options = self.log_options;
+        self.loader_list
+            .iter_mut()
+            .for_each(|loader| loader.set_log_options(log_options));
+    }
+
+    /// See [Configuration::set_trace_contents].
+    pub fn with_trace_contents(mut self, trace_contents: bool) -> Self {
+        self.set_trace_contents(trace_contents);
+        self
+    }
+
+    pub fn trace_contents(&self) -> bool {
+        self.log_options.trace_contents
+    }
+}
+
+impl Configuration {
+    /// Loads and parses the only configuration of `url` and serializes it again in the same format.
+    ///
+    /// Like other configurations, input preprocessors, aliases, and null sentinels are applied to it before it's
+    /// serialized.
+    ///
+    /// Useful as a formatter. Note that comments and key order are not preserved (use
+    /// [Configuration::set_sort_keys] for a stable order). Supported formats are JSON, YAML, and TOML (based on enabled
+    /// Cargo features).
+    pub fn normalize_source(&self, url: &Url) -> Result<String, Error> {
+        let mut load_result = load_detached(
+            [url.clone()].as_slice(),
+            self.loader_list.as_slice(),
+            None,
+            false,
+        )?;
+        self.parse_loaded(load_result.as_mut())?;
+        let mut entity_list: Vec<_> = load_result
+            .into_iter()
+            .flat_map(|(_, entity_list)| entity_list)
+            .collect();
+        if entity_list.len() != 1 {
+            return Err(Error::Other(anyhow!(
+                "Expected exactly one configuration from `{url}`, but found {}",
+                entity_list.len()
+            )));
+        }
+        let entity = entity_list.remove(0);
+        let format = entity
+            .format_or_guess(self.parser_list.as_slice())
+            .unwrap_or_default();
+        let parsed = entity
+            .maybe_parsed_contents()
+            .expect("Contents is parsed in above");
+        serialize(format.as_str(), parsed, self.sort_keys)
+            .map_err(|error| Error::Other(anyhow!("Could not normalize `{url}`: {error}")))
+    }
+
+    /// Sorts map keys in output of [Configuration::normalize_source].
+    ///
+    /// Source order of keys can not be preserved since maps of [Input] are [HashMap]s (see [SortedKeys]), so without
+    /// it keys are in an arbitrary order.
+    pub fn set_sort_keys(&mut self, sort_keys: bool) {
+        self.sort_keys = sort_keys;
+    }
+
+    /// Sorts map keys in output of [Configuration::normalize_source] (see [Configuration::set_sort_keys]).
+    pub fn with_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.set_sort_keys(sort_keys);
+        self
+    }
+}
+
+impl Configuration {
+    pub fn is_in_whitelist<P: AsRef<str>>(&self, name: P) -> bool {
+        let name = name.as_ref().to_lowercase();
+        self.maybe_whitelist
+            .as_ref()
+            .map(|whitelist| whitelist.contains(&name))
+            .unwrap_or(false)
+    }
+
+    pub fn load_whitelist_from_env<K: AsRef<str>>(&mut self, key: K) -> Result<(), Error> {
+        let whitelist = env::var(key.as_ref())
+            .map(|value| split_whitelist(value.as_str(), false))
+            .map_err(|error| {
+                Error::Other(anyhow!("Invalid key or the value is not set: {}", error))
+            })?;
+        if whitelist.is_empty() {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::warn!(key=key.as_ref(), "Whitelist environment-variable is set to empty")
+                } else if #[cfg(feature = "logging")] {
+                    log::warn!("msg=\"Whitelist environment-variable is set to empty\" key={:?}", key.as_ref())
+                }
+            }
+        } else {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::info!(key=key.as_ref(), "Set whitelist from environment-variable")
+                } else if #[cfg(feature = "logging")] {
+                    log::info!("msg=\"Set whitelist from environment-variable\" key={:?}", key.as_ref())
+                }
+            }
+        }
+        self.set_whitelist(whitelist.as_ref());
+        Ok(())
+    }
+
+    pub fn set_whitelist_from_env<K: AsRef<str>>(mut self, key: K) -> Result<Self, Error> {
+        self.load_whitelist_from_env(key)?;
+        Ok(self)
+    }
+
+    /// Loads whitelist from a file of plugin names separated by newlines, whitespaces, commas, or semicolons.
+    ///
+    /// Lines starting with `#` are ignored.
+    pub fn load_whitelist_from_file<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        let whitelist = std::fs::read_to_string(path)
+            .map(|contents| split_whitelist(contents.as_str(), true))
+            .map_err(|error| {
+                Error::Other(anyhow!("Could not read whitelist file {path:?}: {error}"))
+            })?;
+        if whitelist.is_empty() {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::warn!(path=?path, "Whitelist file is empty")
+                } else if #[cfg(feature = "logging")] {
+                    log::warn!("msg=\"Whitelist file is empty\" path={path:?}")
+                }
+            }
+        } else {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::info!(path=?path, "Set whitelist from file")
+                } else if #[cfg(feature = "logging")] {
+                    log::info!("msg=\"Set whitelist from file\" path={path:?}")
+                }
+            }
+        }
+        self.set_whitelist(whitelist.as_ref());
+        Ok(())
+    }
+
+    pub fn set_whitelist_from_file<P: AsRef<std::path::Path>>(
+        mut self,
+        path: P,
+    ) -> Result<Self, Error> {
+        self.load_whitelist_from_file(path)?;
+        Ok(self)
+    }
+
+    /// Loads whitelist from plugin names that `whitelist_fn` returns (each item may contain several names separated
+    /// by whitespaces, commas, or semicolons).
+    pub fn load_whitelist_from_fn<F: FnOnce() -> Vec<String>>(&mut self, whitelist_fn: F) {
+        let whitelist: Vec<_> = whitelist_fn()
+            .iter()
+            .flat_map(|value| split_whitelist(value.as_str(), false))
+            .collect();
+        if whitelist.is_empty() {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::warn!("Whitelist function returned empty list")
+                } else if #[cfg(feature = "logging")] {
+                    log::warn!("msg=\"Whitelist function returned empty list\"")
+                }
+            }
+        } else {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::info!("Set whitelist from function")
+                } else if #[cfg(feature = "logging")] {
+                    log::info!("msg=\"Set whitelist from function\"")
+                }
+            }
+        }
+        self.set_whitelist(whitelist.as_ref());
+    }
+
+    pub fn set_whitelist_from_fn<F: FnOnce() -> Vec<String>>(mut self, whitelist_fn: F) -> Self {
+        self.load_whitelist_from_fn(whitelist_fn);
+        self
+    }
+
+    /// Removes the whitelist, so configurations of all plugins are loaded.
+    pub fn clear_whitelist(&mut self) {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!("Removed whitelist");
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Removed whitelist\"");
+            }
+        }
+        self.maybe_whitelist = None;
+    }
+
+    pub fn set_whitelist<N: AsRef<str>>(&mut self, whitelist: &[N]) {
+        whitelist
+            .iter()
+            .for_each(|name| self.add_to_whitelist(name));
+    }
+
+    pub fn with_whitelist<N: AsRef<str>>(mut self, whitelist: &[N]) -> Self {
+        self.set_whitelist(whitelist);
+        self
+    }
+
+    pub fn add_to_whitelist<N: AsRef<str>>(&mut self, name: N) {
+        let name = name.as_ref().to_lowercase();
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(name=name, "Added to whitelist")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added to whitelist\" name={name:?}")
+            }
+        }
+        if let Some(whitelist) = self.maybe_whitelist.as_mut() {
+            if !whitelist.contains(&name) {
+                whitelist.push(name);
+            }
+        } else {
+            self.maybe_whitelist = Some(Vec::from([name]));
+        }
+    }
+}
+
+impl Configuration {
+    /// Moves the value of the dotted `from_path` (e.g. `server.address`) to `to_path` in every parsed
+    /// configuration of `plugin` before merging them.
+    ///
+    /// Useful for renaming keys without breaking older configuration files.
+    pub fn add_alias<P: AsRef<str>, F: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        plugin: P,
+        from_path: F,
+        to_path: T,
+    ) {
+        let (plugin, from_path, to_path) = (
+            plugin.as_ref().to_lowercase(),
+            from_path.as_ref().to_string(),
+            to_path.as_ref().to_string(),
+        );
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(plugin=plugin, from=from_path, to=to_path, "Added alias")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added alias\" plugin={plugin:?} from={from_path:?} to={to_path:?}")
+            }
+        }
+        self.alias_list.push((plugin, from_path, to_path));
+    }
+
+    pub fn with_alias<P: AsRef<str>, F: AsRef<str>, T: AsRef<str>>(
+        mut self,
+        plugin: P,
+        from_path: F,
+        to_path: T,
+    ) -> Self {
+        self.add_alias(plugin, from_path, to_path);
+        self
+    }
+
+    pub fn alias_conflict_policy(&self) -> AliasConflictPolicy {
+        self.alias_conflict_policy
+    }
+
+    pub fn set_alias_conflict_policy(&mut self, policy: AliasConflictPolicy) {
+        self.alias_conflict_policy = policy
+    }
+
+    pub fn with_alias_conflict_policy(mut self, policy: AliasConflictPolicy) -> Self {
+        self.set_alias_conflict_policy(policy);
+        self
+    }
+}
+
+impl Configuration {
+    /// Adds a built-in configuration document (e.g. from [include_str]) for `plugin` which is merged before all other
+    /// configurations of the plugin, so every loaded configuration overrides it.
+    ///
+    /// By default, it is only used if some other configuration is loaded for the plugin (see
+    /// [Configuration::set_always_include_default_documents]).
+    pub fn add_default_document<P: AsRef<str>, F: AsRef<str>, C: AsRef<str>>(
+        &mut self,
+        plugin: P,
+        format: F,
+        contents: C,
+    ) -> Result<(), Error> {
+        let plugin_name = plugin.as_ref().to_lowercase();
+        let url = Url::parse(format!("{DEFAULT_DOCUMENT_SCHEME}:///{plugin_name}").as_str())?;
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(plugin=plugin_name, format=format.as_ref(), "Added default document")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added default document\" plugin={plugin_name:?} format={:?}", format.as_ref())
+            }
+        }
+        self.default_document_list.push(
+            ConfigurationEntity::new("default", url, plugin_name, "default")
+                .with_format(format.as_ref().to_lowercase())
+                .with_contents(contents.as_ref()),
+        );
+        Ok(())
+    }
+
+    pub fn with_default_document<P: AsRef<str>, F: AsRef<str>, C: AsRef<str>>(
+        mut self,
+        plugin: P,
+        format: F,
+        contents: C,
+    ) -> Result<Self, Error> {
+        self.add_default_document(plugin, format, contents)?;
+        Ok(self)
+    }
+
+    /// If `true`, default documents are included even for plugins that have no other configuration.
+    pub fn set_always_include_default_documents(&mut self, flag: bool) {
+        self.always_include_default_documents = flag
+    }
+
+    pub fn with_always_include_default_documents(mut self, flag: bool) -> Self {
+        self.set_always_include_default_documents(flag);
+        self
+    }
+}
+
+impl Configuration {
+    /// Only keeps loaded plugins whose names match one of the added patterns.
+    ///
+    /// A pattern is a glob (`*` matches any characters and `?` matches one character, e.g. `svc-*`) or a regular
+    /// expression with `regex:` prefix (e.g. `regex:^svc-[0-9]+$`) which needs `regex` feature. Loaders already skip
+    /// plugins that are not whitelisted, so if a whitelist is set, plugins should be in it too.
+    pub fn add_plugin_pattern<P: AsRef<str>>(&mut self, pattern: P) -> Result<(), Error> {
+        let pattern = pattern.as_ref();
+        let plugin_pattern = if let Some(_regex) = pattern.strip_prefix("regex:") {
+            cfg_if! {
+                if #[cfg(feature = "regex")] {
+                    PluginPattern::Regex(regex::Regex::new(_regex).map_err(|error| {
+                        Error::Other(anyhow!("Invalid plugin name pattern `{pattern}`: {error}"))
+                    })?)
+                } else {
+                    return Err(Error::Other(anyhow!(
+                        "Plugin name pattern `{pattern}` needs `regex` feature"
+                    )));
+                }
+            }
+        } else {
+            PluginPattern::Glob(pattern.to_lowercase().chars().collect())
+        };
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(pattern=pattern, "Added plugin name pattern")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Added plugin name pattern\" pattern={pattern:?}")
+            }
+        }
+        self.plugin_pattern_list.push(plugin_pattern);
+        Ok(())
+    }
+
+    /// Only keeps loaded plugins whose names match one of the added patterns (see
+    /// [Configuration::add_plugin_pattern]).
+    pub fn with_plugin_pattern<P: AsRef<str>>(mut self, pattern: P) -> Result<Self, Error> {
+        self.add_plugin_pattern(pattern)?;
+        Ok(self)
+    }
+
+    /// Strips the first matching prefix of these prefixes from names of loaded plugins (e.g. `svc_foo` becomes `foo`
+    /// with `svc_`), after each loader's own prefix handling.
+    ///
+    /// It works for all loaders. Configurations of plugins that end up with the same name are merged in the order of
+    /// their first appearance, and the whitelist, plugin patterns, and default documents apply to stripped names.
+    /// Names that are equal to a prefix are not stripped.
+    pub fn set_plugin_prefix_strip<P: AsRef<str>>(&mut self, prefix_list: &[P]) {
+        self.plugin_prefix_strip_list = prefix_list
+            .iter()
+            .map(|prefix| prefix.as_ref().to_lowercase())
+            .filter(|prefix| !prefix.is_empty())
+            .collect();
+    }
+
+    /// See [Configuration::set_plugin_prefix_strip].
+    pub fn with_plugin_prefix_strip<P: AsRef<str>>(mut self, prefix_list: &[P]) -> Self {
+        self.set_plugin_prefix_strip(prefix_list);
+        self
+    }
+
+    fn strip_plugin_prefix<'a>(&self, plugin_name: &'a str) -> &'a str {
+        self.plugin_prefix_strip_list
+            .iter()
+            .find_map(|prefix| {
+                plugin_name
+                    .strip_prefix(prefix.as_str())
+                    .filter(|plugin_name| !plugin_name.is_empty())
+            })
+            .unwrap_or(plugin_name)
+    }
+
+    /// Whitelist for loaders which also contains prefixed names of whitelisted plugins.
+    fn prefixed_whitelist(&self, maybe_whitelist: Option<&[String]>) -> Option<Vec<String>> {
+        let whitelist = maybe_whitelist?;
+        if self.plugin_prefix_strip_list.is_empty() {
+            return None;
+        }
+        Some(
+            whitelist
+                .iter()
+                .cloned()
+                .chain(whitelist.iter().flat_map(|plugin_name| {
+                    self.plugin_prefix_strip_list
+                        .iter()
+                        .map(move |prefix| format!("{prefix}{plugin_name}"))
+                }))
+                .collect(),
+        )
+    }
+
+    /// Strips plugin prefixes of configurations loaded from one URL.
+    ///
+    /// It's applied to each URL before its configurations are appended to others, so configurations of a plugin stay in
+    /// URL order.
+    fn strip_plugin_prefixes(
+        &self,
+        loaded: Vec<(String, Vec<ConfigurationEntity>)>,
+    ) -> Vec<(String, Vec<ConfigurationEntity>)> {
+        if self.plugin_prefix_strip_list.is_empty() {
+            return loaded;
+        }
+        let mut result: Vec<(String, Vec<ConfigurationEntity>)> = Vec::with_capacity(loaded.len());
+        loaded
+            .into_iter()
+            .for_each(|(plugin_name, mut configuration_list)| {
+                let stripped_plugin_name = self.strip_plugin_prefix(plugin_name.as_str()).to_string();
+                if stripped_plugin_name != plugin_name {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::trace!(plugin=plugin_name, stripped=stripped_plugin_name, "Stripped plugin name prefix");
+                        } else if #[cfg(feature = "logging")] {
+                            log::trace!("msg=\"Stripped plugin name prefix\" plugin={plugin_name:?} stripped={stripped_plugin_name:?}");
+                        }
+                    }
+                    configuration_list.iter_mut().for_each(|configuration| {
+                        *configuration.plugin_name_mut() = stripped_plugin_name.clone()
+                    });
+                }
+                if let Some((_, inner_configuration_list)) = result
+                    .iter_mut()
+                    .find(|(loaded_plugin_name, _)| loaded_plugin_name == &stripped_plugin_name)
+                {
+                    inner_configuration_list.append(&mut configuration_list)
+                } else {
+                    result.push((stripped_plugin_name, configuration_list))
+                }
+            });
+        result
+    }
+}
+
+impl Configuration {
+    /// Removes `plugin` from results of [Configuration::load_parse_merge] and its validating variants.
+    ///
+    /// Unlike the whitelist, its configurations are still loaded, parsed, merged, and validated.
+    pub fn add_excluded_from_result<P: AsRef<str>>(&mut self, plugin: P) {
+        let plugin_name = plugin.as_ref().to_lowercase();
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(plugin=plugin_name, "Excluded from result")
+            } else if #[cfg(feature = "logging")] {
+                log::debug!("msg=\"Excluded from result\" plugin={plugin_name:?}")
+            }
+        }
+        if !self.result_exclude_list.contains(&plugin_name) {
+            self.result_exclude_list.push(plugin_name)
+        }
+    }
+
+    pub fn with_excluded_from_result<P: AsRef<str>>(mut self, plugin: P) -> Self {
+        self.add_excluded_from_result(plugin);
+        self
+    }
+}
+
+#[cfg(feature = "units")]
+impl Configuration {
+    /// Converts human-readable durations (e.g. `30s` or `1h 30m`) of these keys to integer milliseconds after merging
+    /// and before validation.
+    ///
+    /// Each key is a dotted path starting with the plugin name and `*` matches any key (e.g. `*.timeout` or
+    /// `foo.*.timeout`). Values which are not strings are left as is.
+    pub fn set_duration_keys<K: AsRef<str>>(&mut self, key_list: &[K]) {
+        self.duration_key_list = key_list
+            .iter()
+            .map(|key| key.as_ref().to_string())
+            .collect();
+    }
+
+    /// Converts human-readable durations (e.g. `30s` or `1h 30m`) of these keys to integer milliseconds after merging
+    /// and before validation (see [Configuration::set_duration_keys]).
+    pub fn with_duration_keys<K: AsRef<str>>(mut self, key_list: &[K]) -> Self {
+        self.set_duration_keys(key_list);
+        self
+    }
+
+    /// Converts human-readable byte sizes (e.g. `10MB` or `4 KiB`) of these keys to integer bytes after merging and
+    /// before validation (see [Configuration::set_duration_keys] for the key format).
+    pub fn set_bytesize_keys<K: AsRef<str>>(&mut self, key_list: &[K]) {
+        self.bytesize_key_list = key_list
+            .iter()
+            .map(|key| key.as_ref().to_string())
+            .collect();
+    }
+
+    /// Converts human-readable byte sizes (e.g. `10MB` or `4 KiB`) of these keys to integer bytes after merging and
+    /// before validation (see [Configuration::set_duration_keys] for the key format).
+    pub fn with_bytesize_keys<K: AsRef<str>>(mut self, key_list: &[K]) -> Self {
+        self.set_bytesize_keys(key_list);
+        self
+    }
+}
+
+impl Configuration {
+    /// Appends URLs and default documents of `other` after ours, merges whitelists, and adds loaders and parsers of
+    /// `other` that we do not have.
+    ///
+    /// * Whitelists are joined, so if one of them has no whitelist (which allows every plugin), the result has none.
+    /// * If we already have a loader for one of the schemes of a loader of `other` (or a parser for one of the formats
+    ///   of a parser of `other`), ours is kept and a warning is logged.
+    /// * Other settings (e.g. aliases) of `other` are ignored.
+    pub fn extend_from(&mut self, other: Configuration) {
+        let Configuration {
+            url_list,
+            loader_list,
+            parser_list,
+            maybe_whitelist,
+            default_document_list,
+            ..
+        } = other;
+        loader_list.into_iter().for_each(|loader| {
+            let scheme_list = loader.scheme_list();
+            if let Some(_existing) = self.loader_list.iter().find(|existing| {
+                existing
+                    .scheme_list()
+                    .iter()
+                    .any(|scheme| scheme_list.contains(scheme))
+            }) {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(
+                            loader=%loader,
+                            existing_loader=%_existing,
+                            scheme_list=?scheme_list,
+                            "Kept existing configuration loader for the same scheme"
+                        );
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!(
+                            "msg=\"Kept existing configuration loader for the same scheme\" loader=\"{loader}\" existing_loader=\"{_existing}\" scheme_list={scheme_list:?}"
+                        );
+                    }
+                }
+            } else {
+                self.add_boxed_loader(loader);
+            }
+        });
+        parser_list.into_iter().for_each(|parser| {
+            let format_list = parser.supported_format_list();
+            if let Some(_existing) = self.parser_list.iter().find(|existing| {
+                existing
+                    .supported_format_list()
+                    .iter()
+                    .any(|format| format_list.contains(format))
+            }) {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(
+                            parser=%parser,
+                            existing_parser=%_existing,
+                            format_list=?format_list,
+                            "Kept existing configuration parser for the same format"
+                        );
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!(
+                            "msg=\"Kept existing configuration parser for the same format\" parser=\"{parser}\" existing_parser=\"{_existing}\" format_list={format_list:?}"
+                        );
+                    }
+                }
+            } else {
+                self.add_boxed_parser(parser);
+            }
+        });
+        url_list.into_iter().for_each(|url| {
+            if !self.url_list.contains(&url) {
+                self.url_list.push(url)
+            }
+        });
+        // No whitelist allows every plugin, so the union is only restricted if both of them are:
+        match (self.maybe_whitelist.is_some(), maybe_whitelist) {
+            (true, Some(whitelist)) => whitelist
+                .into_iter()
+                .for_each(|name| self.add_to_whitelist(name)),
+            _ => self.maybe_whitelist = None,
+        }
+        self.default_document_list.extend(default_document_list);
+    }
+}
+
+impl Configuration {
+    pub fn state(&self) -> ConfigurationState {
+        ConfigurationState {
+            url_list: self.url_list.clone(),
+            whitelist: self.maybe_whitelist.clone(),
+            extension_format_list: self.extension_format_list.clone(),
+            mime_format_list: self.mime_format_list.clone(),
+            plugin_precedence_list: self.plugin_precedence_list.clone(),
+        }
+    }
+
+    pub fn from_state(state: ConfigurationState) -> Result<Self, Error> {
+        Self::new().with_state(state)
+    }
+
+    pub fn with_state(mut self, state: ConfigurationState) -> Result<Self, Error> {
+        self.add_state(state)?;
+        Ok(self)
+    }
+
+    pub fn add_state(&mut self, state: ConfigurationState) -> Result<(), Error> {
+        state.url_list.into_iter().try_for_each(|url| {
+            if self.url_list.contains(&url) {
+                Ok(())
+            } else {
+                self.add_url(url)
+            }
+        })?;
+        if let Some(whitelist) = state.whitelist {
+            self.set_whitelist(whitelist.as_slice());
+            if self.maybe_whitelist.is_none() {
+                self.maybe_whitelist = Some(Vec::new());
+            }
+        }
+        state
+            .extension_format_list
+            .into_iter()
+            .for_each(|(extension, format)| self.add_extension_format(extension, format));
+        state
+            .mime_format_list
+            .into_iter()
+            .for_each(|(mime, format)| self.map_mime(mime, format));
+        state
+            .plugin_precedence_list
+            .into_iter()
+            .for_each(|(plugin_name, precedence)| {
+                self.set_plugin_precedence(plugin_name, precedence)
+            });
+        Ok(())
+    }
+}
+
+impl ConfigurationState {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn url_list(&self) -> &[Url] {
+        self.url_list.as_slice()
+    }
+
+    pub fn url_list_mut(&mut self) -> &mut Vec<Url> {
+        &mut self.url_list
+    }
+
+    pub fn maybe_whitelist(&self) -> Option<&Vec<String>> {
+        self.whitelist.as_ref()
+    }
+
+    pub fn maybe_whitelist_mut(&mut self) -> &mut Option<Vec<String>> {
+        &mut self.whitelist
+    }
+
+    pub fn extension_format_list(&self) -> &[(String, String)] {
+        self.extension_format_list.as_slice()
+    }
+
+    pub fn extension_format_list_mut(&mut self) -> &mut Vec<(String, String)> {
+        &mut self.extension_format_list
+    }
+
+    pub fn mime_format_list(&self) -> &[(String, String)] {
+        self.mime_format_list.as_slice()
+    }
+
+    pub fn mime_format_list_mut(&mut self) -> &mut Vec<(String, String)> {
+        &mut self.mime_format_list
+    }
+
+    pub fn plugin_precedence_list(&self) -> &[(String, SourcePrecedence)] {
+        self.plugin_precedence_list.as_slice()
+    }
+
+    pub fn plugin_precedence_list_mut(&mut self) -> &mut Vec<(String, SourcePrecedence)> {
+        &mut self.plugin_precedence_list
+    }
+}
+
+impl Configuration {
+    pub fn load_parse_merge(&self, skip_soft_errors: bool) -> Result<Vec<(String, Input)>, Error> {
+        self.load_parse_merge_all(skip_soft_errors)
+            .map(|merged| self.exclude_result(merged))
+    }
+
+    /// Same as [Configuration::load_parse_merge] but also records when each URL was loaded successfully.
+    ///
+    /// See [Configuration::load_mut].
+    pub fn load_parse_merge_mut(
+        &mut self,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let mut loaded = self.load_mut(skip_soft_errors)?;
+        self.parse_loaded(loaded.as_mut())?;
+        let merged = self.finish_merge(loaded.as_mut())?;
+        Ok(self.exclude_result(merged))
+    }
+
+    /// Loads, parses, and merges only configurations of `plugin` and returns `None` if there is none.
+    ///
+    /// Loaders only get `plugin` as their whitelist, so it's much cheaper than [Configuration::load_parse_merge] for
+    /// sources with many plugins. It also returns `None` if `plugin` is not in the whitelist, does not match plugin
+    /// patterns, or is excluded from result.
+    pub fn merged_plugin<P: AsRef<str>>(
+        &self,
+        plugin: P,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Input>, Error> {
+        let plugin = plugin.as_ref().to_lowercase();
+        if self
+            .maybe_whitelist
+            .as_ref()
+            .map(|whitelist| !whitelist.contains(&plugin))
+            .unwrap_or(false)
+            || (!self.plugin_pattern_list.is_empty()
+                && !self
+                    .plugin_pattern_list
+                    .iter()
+                    .any(|pattern| pattern.is_match(plugin.as_str())))
+            || self.result_exclude_list.contains(&plugin)
+        {
+            return Ok(None);
+        }
+        let whitelist = [plugin.clone()];
+        let mut loaded = self.load_whitelisted(Some(&whitelist), skip_soft_errors, None)?;
+        // Some loaders (e.g. closures) may ignore the whitelist:
+        loaded.retain(|(plugin_name, _)| plugin_name == &plugin);
+        self.parse_loaded(loaded.as_mut())?;
+        let merged = self.finish_merge(loaded.as_mut())?;
+        Ok(merged.into_iter().next().map(|(_, input)| input))
+    }
+
+    fn load_parse_merge_all(&self, skip_soft_errors: bool) -> Result<Vec<(String, Input)>, Error> {
+        let mut parsed = self.load_and_parse(skip_soft_errors)?;
+        self.finish_merge(parsed.as_mut())
+    }
+
+    /// Merges parsed configurations of each plugin and converts units of the result (`units` feature).
+    fn finish_merge(
+        &self,
+        plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let merged = merge_owned(plugin_configuration_list, &self.plugin_precedence_list)?;
+        self.convert_merged_units(merged)
+    }
+
+    fn convert_merged_units(
+        &self,
+        merged: Vec<(String, Input)>,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        cfg_if! {
+            if #[cfg(feature = "units")] {
+                let mut merged = merged;
+                convert_units(
+                    merged.as_mut(),
+                    &self.duration_key_list,
+                    &self.bytesize_key_list,
+                )?;
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Same as [Configuration::load_parse_merge] but also returns duration of each phase.
+    pub fn load_parse_merge_timed(
+        &self,
+        skip_soft_errors: bool,
+    ) -> Result<(Vec<(String, Input)>, PhaseTimings), Error> {
+        let mut timings = PhaseTimings::default();
+        let merged = self.load_parse_merge_all_timed(skip_soft_errors, &mut timings)?;
+        Ok((self.exclude_result(merged), timings))
+    }
+
+    /// Same as [Configuration::load_parse_merge_validate] but also returns duration of each phase.
+    pub fn load_parse_merge_validate_timed(
+        &self,
+        schema_list: &[(String, InputSchemaType)],
+        skip_soft_errors: bool,
+    ) -> Result<(Vec<(String, Input)>, PhaseTimings), Error> {
+        let mut timings = PhaseTimings::default();
+        let mut merged = self.load_parse_merge_all_timed(skip_soft_errors, &mut timings)?;
+        let start = Instant::now();
+        let validated = validate(merged.as_mut(), schema_list)?;
+        timings.validate = start.elapsed();
+        Ok((self.exclude_result(validated), timings))
+    }
+
+    fn load_parse_merge_all_timed(
+        &self,
+        skip_soft_errors: bool,
+        timings: &mut PhaseTimings,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let start = Instant::now();
+        let mut loaded = self.load_and_time(skip_soft_errors, Some(&mut timings.url_load_list))?;
+        timings.load = start.elapsed();
+        let start = Instant::now();
+        self.parse_loaded(loaded.as_mut())?;
+        timings.parse = start.elapsed();
+        let start = Instant::now();
+        let merged = merge_owned(loaded.as_mut(), &self.plugin_precedence_list)?;
+        timings.merge = start.elapsed();
+        // Converting units is not a part of merging:
+        let merged = self.convert_merged_units(merged)?;
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::debug!(
+                    load=?timings.load,
+                    parse=?timings.parse,
+                    merge=?timings.merge,
+                    "Loaded, parsed, and merged configurations"
+                );
+            } else if #[cfg(feature = "logging")] {
+                log::debug!(
+                    "msg=\"Loaded, parsed, and merged configurations\" load={:?} parse={:?} merge={:?}",
+                    timings.load,
+                    timings.parse,
+                    timings.merge
+                );
+            }
+        }
+        Ok(merged)
+    }
+
+    fn exclude_result(&self, merged: Vec<(String, Input)>) -> Vec<(String, Input)> {
+        if self.result_exclude_list.is_empty() {
+            return merged;
+        }
+        merged
+            .into_iter()
+            .filter(|(plugin_name, _)| !self.result_exclude_list.contains(plugin_name))
+            .collect()
+    }
+
+    /// Checks environment-variables every `interval` and whenever they are changed (e.g. via [std::env::set_var]),
+    /// reloads configurations and calls `on_change` with the new result if it differs from the previous one.
+    ///
+    /// Only environment-variables of the process are watched, so changes of other sources (or of a custom variable
+    /// source of the env loader) are only picked up together with a change of environment-variables. Errors of
+    /// reloading are logged and the previous result is kept until the next successful reload.
+    ///
+    /// It blocks until `on_change` returns `false`, so run it in its own thread (e.g. via [std::thread::scope] or an
+    /// [std::sync::Arc] of the configuration). This is best-effort polling: changes that are reverted within one
+    /// interval are not detected, and changing environment-variables while other threads read them is inherently
+    /// racy.
+    #[cfg(feature = "env")]
+    pub fn watch_env<F>(
+        &self,
+        interval: Duration,
+        skip_soft_errors: bool,
+        mut on_change: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&[(String, Input)]) -> bool,
+    {
+        let as_map = |merged: &[(String, Input)]| -> HashMap<String, Input> {
+            merged.iter().cloned().collect()
+        };
+        let mut previous_var_list: HashMap<_, _> = env::vars_os().collect();
+        let mut previous = as_map(self.load_parse_merge(skip_soft_errors)?.as_slice());
+        loop {
+            std::thread::sleep(interval);
+            let var_list: HashMap<_, _> = env::vars_os().collect();
+            if var_list == previous_var_list {
+                continue;
+            }
+            let merged = match self.load_parse_merge(skip_soft_errors) {
+                Ok(merged) => merged,
+                Err(_error) => {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::warn!(error=%_error, "Could not reload configurations, kept watching");
+                        } else if #[cfg(feature = "logging")] {
+                            log::warn!(
+                                "msg=\"Could not reload configurations, kept watching\" error={:?}",
+                                _error.to_string()
+                            );
+                        }
+                    }
+                    // Environment-variables are compared to the last successfully loaded ones, so it's retried:
+                    continue;
+                }
+            };
+            previous_var_list = var_list;
+            let current = as_map(merged.as_slice());
+            if current == previous {
+                continue;
+            }
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::debug!("Detected configuration change");
                 } else if #[cfg(feature = "logging")] {
-                    log::debug!(
-                        "message=\"Removed configuration loader\" loader=\"{loader}\" schema_list={:?}",
-                        loader.scheme_list()
-                    );
+                    log::debug!("msg=\"Detected configuration change\"");
+                }
+            }
+            previous = current;
+            if !on_change(merged.as_slice()) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Loads and parses configurations and returns each plugin's parsed sources (not merged yet) in merge order.
+    ///
+    /// Useful for finding out which source contributes which values.
+    pub fn parse_only(
+        &self,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ParsedSourceList)>, Error> {
+        Ok(self
+            .load_and_parse(skip_soft_errors)?
+            .into_iter()
+            .map(|(plugin_name, configuration_list)| {
+                let parsed_list = configuration_list
+                    .into_iter()
+                    .filter_map(|configuration| {
+                        configuration
+                            .maybe_parsed_contents()
+                            .cloned()
+                            .map(|parsed| (configuration.url().clone(), parsed))
+                    })
+                    .collect();
+                (plugin_name, parsed_list)
+            })
+            .collect())
+    }
+
+    /// Returns URLs of each plugin's configurations in the same order that [Configuration::load_parse_merge] merges
+    /// them (later ones override earlier ones).
+    pub fn merge_order(&self, skip_soft_errors: bool) -> Result<Vec<(String, Vec<Url>)>, Error> {
+        Ok(self
+            .load_and_parse(skip_soft_errors)?
+            .into_iter()
+            .map(|(plugin_name, configuration_list)| {
+                let url_list = configuration_list
+                    .iter()
+                    .filter(|configuration| configuration.maybe_parsed_contents().is_some())
+                    .map(|configuration| configuration.url().clone())
+                    .collect();
+                (plugin_name, url_list)
+            })
+            .collect())
+    }
+
+    /// Includes values of merged configurations in reports of [Configuration::dump_debug]. Defaults to `false` which
+    /// only keeps keys and replaces other values with `<redacted>`, since they may contain secrets.
+    pub fn set_dump_values(&mut self, dump_values: bool) {
+        self.dump_values = dump_values;
+    }
+
+    /// See [Configuration::set_dump_values].
+    pub fn with_dump_values(mut self, dump_values: bool) -> Self {
+        self.set_dump_values(dump_values);
+        self
+    }
+
+    pub fn dump_values(&self) -> bool {
+        self.dump_values
+    }
+
+    /// Returns a human-readable report of the whole pipeline (e.g. to attach to a support ticket) without needing to
+    /// enable logging.
+    ///
+    /// The report contains registered loaders and parsers, the whitelist, outcome and duration of loading each URL,
+    /// sources of each plugin in merge order, merged configurations (with redacted values unless
+    /// [Configuration::set_dump_values] is set), and the validation result of each plugin if `maybe_schema_list` is
+    /// given. Each URL is loaded once and errors of loading, parsing, and validating are included in the report instead
+    /// of being returned, so plugins are merged from URLs that are loaded successfully.
+    pub fn dump_debug(
+        &self,
+        maybe_schema_list: Option<&[(String, InputSchemaType)]>,
+        skip_soft_errors: bool,
+    ) -> Result<String, Error> {
+        let mut line_list = vec!["Loaders:".to_string()];
+        self.loaders()
+            .into_iter()
+            .for_each(|(loader_name, scheme_list)| {
+                line_list.push(format!("  {loader_name}: {}", scheme_list.join(", ")))
+            });
+        line_list.push("Parsers:".into());
+        self.parsers()
+            .into_iter()
+            .for_each(|(parser_name, format_list)| {
+                line_list.push(format!("  {parser_name}: {}", format_list.join(", ")))
+            });
+        let maybe_whitelist = self.maybe_whitelist.as_deref();
+        line_list.push(format!(
+            "Whitelist: {}",
+            maybe_whitelist
+                .map(|whitelist| whitelist.join(", "))
+                .unwrap_or_else(|| "<none>".into())
+        ));
+        line_list.push("URLs:".into());
+        // Errors are reported, so they do not fail loading:
+        let (mut loaded, _) = self.load_url_list(
+            true,
+            maybe_whitelist,
+            skip_soft_errors,
+            false,
+            &mut |url, result, duration| match result {
+                Ok(url_loaded) => {
+                    let plugin_name_list: Vec<_> = url_loaded
+                        .iter()
+                        .map(|(plugin_name, _)| plugin_name.as_str())
+                        .collect();
+                    line_list.push(format!(
+                        "  {url}: loaded [{}] in {duration:?}",
+                        plugin_name_list.join(", ")
+                    ))
+                }
+                Err(error) => line_list.push(format!("  {url}: failed in {duration:?} ({error})")),
+            },
+        )?;
+        if let Err(error) = self.parse_loaded(loaded.as_mut()) {
+            line_list.push(format!("Parsing failed: {error}"));
+            return Ok(line_list.join("\n"));
+        }
+        line_list.push("Sources in merge order:".into());
+        loaded.iter().for_each(|(plugin_name, configuration_list)| {
+            let precedence = self.plugin_precedence(plugin_name);
+            let url_list: Vec<_> = merge_index_list(configuration_list, precedence)
+                .into_iter()
+                .map(|index| &configuration_list[index])
+                .filter(|configuration| configuration.is_parsed())
+                .map(|configuration| configuration.url().to_string())
+                .collect();
+            line_list.push(format!(
+                "  {plugin_name} ({precedence:?}): {}",
+                url_list.join(", ")
+            ))
+        });
+        let merged = match self.finish_merge(loaded.as_mut()) {
+            Ok(merged) => self.exclude_result(merged),
+            Err(error) => {
+                line_list.push(format!("Merging failed: {error}"));
+                return Ok(line_list.join("\n"));
+            }
+        };
+        line_list.push("Merged:".into());
+        merged.iter().for_each(|(plugin_name, input)| {
+            if self.dump_values {
+                line_list.push(format!("  {plugin_name}: {input}"))
+            } else {
+                line_list.push(format!("  {plugin_name}: {}", redact(input)))
+            }
+        });
+        if let Some(schema_list) = maybe_schema_list {
+            line_list.push("Validation:".into());
+            merged.iter().for_each(|(plugin_name, input)| {
+                let result = if schema_list
+                    .iter()
+                    .any(|(schema_plugin_name, _)| schema_plugin_name == plugin_name)
+                {
+                    match validate(&[(plugin_name.clone(), input.clone())], schema_list) {
+                        Ok(_) => "valid".to_string(),
+                        Err(error) => format!("invalid ({error})"),
+                    }
+                } else {
+                    "no schema".to_string()
+                };
+                line_list.push(format!("  {plugin_name}: {result}"))
+            });
+        }
+        Ok(line_list.join("\n"))
+    }
+
+    pub fn load_parse_merge_validate(
+        &self,
+        schema_list: &[(String, InputSchemaType)],
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let mut merged = self.load_parse_merge_all(skip_soft_errors)?;
+        validate(merged.as_mut(), schema_list).map(|validated| self.exclude_result(validated))
+    }
+
+    /// Same as [Configuration::load_parse_merge_validate] but validates every plugin's configuration against the same
+    /// `schema`.
+    pub fn load_parse_merge_validate_uniform(
+        &self,
+        schema: &InputSchemaType,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let merged = self.load_parse_merge_all(skip_soft_errors)?;
+        let schema_list: Vec<_> = merged
+            .iter()
+            .map(|(plugin_name, _)| (plugin_name.clone(), schema.clone()))
+            .collect();
+        validate(merged.as_slice(), schema_list.as_slice())
+            .map(|validated| self.exclude_result(validated))
+    }
+
+    /// Same as [Configuration::load_parse_merge_validate] but also materializes each plugin's configuration via
+    /// `registry`.
+    ///
+    /// Plugins that are not registered in `registry` are left out of the result.
+    pub fn load_parse_merge_validate_typed(
+        &self,
+        schema_list: &[(String, InputSchemaType)],
+        registry: &PluginRegistry,
+        skip_soft_errors: bool,
+    ) -> Result<HashMap<String, Box<dyn Any + Send + Sync>>, Error> {
+        self.load_parse_merge_validate(schema_list, skip_soft_errors)?
+            .into_iter()
+            .filter_map(|(plugin_name, input)| {
+                let maybe_materialized = registry.materialize(plugin_name.as_str(), input);
+                if maybe_materialized.is_none() {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::debug!(plugin=plugin_name, "Plugin is not registered to be materialized");
+                        } else if #[cfg(feature = "logging")] {
+                            log::debug!("msg=\"Plugin is not registered to be materialized\" plugin={plugin_name:?}");
+                        }
+                    }
+                }
+                maybe_materialized.map(|result| result.map(|value| (plugin_name, value)))
+            })
+            .collect()
+    }
+
+    /// Loads, parses, and merges configurations and checks that every required dotted path (e.g. `server.port` or
+    /// `servers.0.address` for list items) of each plugin is set.
+    ///
+    /// An empty path list only requires the plugin itself. It returns [Error::Incomplete] listing all missing
+    /// `plugin.path` entries. This is a lighter alternative to schema validation for "must be set" checks.
+    pub fn assert_complete<P: AsRef<str>, K: AsRef<str>>(
+        &self,
+        required_list: &[(P, &[K])],
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let merged = self.load_parse_merge(skip_soft_errors)?;
+        let mut missing_list = Vec::new();
+        required_list.iter().for_each(|(plugin_name, path_list)| {
+            let plugin_name = plugin_name.as_ref().to_lowercase();
+            let maybe_input = merged
+                .iter()
+                .find(|(merged_plugin_name, _)| merged_plugin_name == &plugin_name)
+                .map(|(_, input)| input);
+            if path_list.is_empty() {
+                if maybe_input.is_none() {
+                    missing_list.push(plugin_name);
                 }
+                return;
             }
-            Some((loader, self.remove_scheme(scheme)))
+            path_list.iter().for_each(|path| {
+                let path = path.as_ref();
+                let is_set = maybe_input
+                    .and_then(|input| get_path(input, path))
+                    .is_some();
+                if !is_set {
+                    missing_list.push(format!("{plugin_name}.{path}"));
+                }
+            });
+        });
+        if missing_list.is_empty() {
+            Ok(merged)
         } else {
-            None
+            Err(Error::Incomplete { missing_list })
         }
     }
 
-    pub fn load(
+    /// Loads, parses, and merges configurations and exports them as `export <KEY>="<VALUE>"` lines (the inverse of
+    /// [crate::loader::env::Env]).
+    ///
+    /// Keys are `<PREFIX><SEPARATOR><PLUGIN><SEPARATOR><KEY>...` in uppercase. Lists and strings that would be read
+    /// back as other types (e.g. `"80"`) are exported as JSON. Lines are sorted by key. It returns an error for keys
+    /// that are not valid shell variable names (`[A-Za-z_][A-Za-z0-9_]*`, e.g. plugin names containing `-`).
+    #[cfg(feature = "env")]
+    pub fn export_env<P: AsRef<str>, S: AsRef<str>>(
         &self,
+        prefix: P,
+        separator: S,
         skip_soft_errors: bool,
-    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
-        load(
-            self.url_list.as_slice(),
-            self.loader_list.as_slice(),
-            self.maybe_whitelist.as_deref(),
-            skip_soft_errors,
-        )
-        .map_err(Error::from)
+    ) -> Result<String, Error> {
+        let separator = separator.as_ref();
+        let mut prefix = prefix.as_ref().to_uppercase();
+        if !separator.is_empty() && !prefix.is_empty() && !prefix.ends_with(separator) {
+            prefix += separator
+        }
+        let mut env_list = Vec::new();
+        for (plugin_name, input) in self.load_parse_merge(skip_soft_errors)? {
+            flatten_env(
+                format!("{prefix}{}", plugin_name.to_uppercase()),
+                &input,
+                separator,
+                &mut env_list,
+            )
+            .map_err(|error| {
+                Error::Other(anyhow!(
+                    "Could not export `{plugin_name}` configuration as environment-variables: {error}"
+                ))
+            })?;
+        }
+        env_list.sort();
+        env_list
+            .into_iter()
+            .map(|(key, value)| {
+                if !is_shell_variable_name(key.as_str()) {
+                    return Err(Error::Other(anyhow!(
+                        "Could not export `{key}` since it is not a valid shell variable name"
+                    )));
+                }
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('$', "\\$")
+                    .replace('`', "\\`");
+                Ok(format!("export {key}=\"{value}\"\n"))
+            })
+            .collect()
     }
-}
 
-impl Configuration {
-    pub fn has_parser<F: AsRef<str>>(&self, format: F) -> bool {
-        let format = format.as_ref().to_lowercase();
-        self.parser_list
-            .iter()
-            .any(|parser| parser.supported_format_list().contains(&format))
+    /// Same as [Configuration::load_parse_merge_validate] but validates against JSON Schemas (draft-07).
+    #[cfg(feature = "jsonschema")]
+    pub fn load_parse_merge_validate_jsonschema(
+        &self,
+        schema_list: &[(String, serde_json::Value)],
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, Input)>, Error> {
+        let merged = self.load_parse_merge_all(skip_soft_errors)?;
+        validate_jsonschema(merged.as_slice(), schema_list).map(|_| self.exclude_result(merged))
     }
+}
 
-    pub fn with_parser<P>(mut self, parser: P) -> Self
-    where
-        P: Parser + 'static,
-    {
-        self.add_parser(parser);
-        self
-    }
+fn append_loaded(
+    result: &mut Vec<(String, Vec<ConfigurationEntity>)>,
+    loaded: Vec<(String, Vec<ConfigurationEntity>)>,
+) {
+    loaded
+        .into_iter()
+        .for_each(|(plugin_name, mut configuration_list)| {
+            if let Some((_, inner_configuration_list)) = result
+                .iter_mut()
+                .find(|(loaded_plugin_name, _)| loaded_plugin_name == &plugin_name)
+            {
+                inner_configuration_list.append(&mut configuration_list)
+            } else {
+                result.push((plugin_name, configuration_list))
+            }
+        });
+}
 
-    pub fn add_parser<P>(&mut self, parser: P)
-    where
-        P: Parser + 'static,
-    {
-        self.add_boxed_parser(Box::new(parser));
-    }
+pub fn load(
+    url_list: &[Url],
+    loader_list: &[Box<dyn Loader>],
+    maybe_whitelist: Option<&[String]>,
+    skip_soft_errors: bool,
+) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, LoaderError> {
+    let loader_list: Vec<_> = loader_list.iter().map(AsRef::as_ref).collect();
+    load_with(
+        url_list,
+        loader_list.as_slice(),
+        maybe_whitelist,
+        skip_soft_errors,
+    )
+}
 
-    pub fn with_boxed_parser(mut self, parser: Box<dyn Parser>) -> Self {
-        self.add_boxed_parser(parser);
-        self
-    }
+/// Same as [load] but loads with clones of loaders (if they can be cloned), so loaders that keep state between
+/// loads (e.g. `tail` option of [crate::loader::fs::Fs]) are not advanced.
+fn load_detached(
+    url_list: &[Url],
+    loader_list: &[Box<dyn Loader>],
+    maybe_whitelist: Option<&[String]>,
+    skip_soft_errors: bool,
+) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, LoaderError> {
+    let cloned_loader_list: Vec<_> = loader_list
+        .iter()
+        .map(|loader| loader.boxed_clone())
+        .collect();
+    let loader_list: Vec<_> = loader_list
+        .iter()
+        .zip(cloned_loader_list.iter())
+        .map(|(loader, maybe_cloned_loader)| {
+            maybe_cloned_loader.as_deref().unwrap_or(loader.as_ref())
+        })
+        .collect();
+    load_with(
+        url_list,
+        loader_list.as_slice(),
+        maybe_whitelist,
+        skip_soft_errors,
+    )
+}
 
-    pub fn add_boxed_parser(&mut self, parser: Box<dyn Parser>) {
-        cfg_if! {
-            if #[cfg(feature = "tracing")] {
-                tracing::debug!(
-                    parser=%parser,
-                    format_list=?parser.supported_format_list(),
-                    "Added configuration parser"
-                );
-            } else if #[cfg(feature = "logging")] {
-                log::debug!(
-                    "msg=\"Added configuration parser\" parser=\"{parser}\" format_list={:?}",
-                    parser.supported_format_list()
-                );
+fn load_with(
+    url_list: &[Url],
+    loader_list: &[&dyn Loader],
+    maybe_whitelist: Option<&[String]>,
+    skip_soft_errors: bool,
+) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, LoaderError> {
+    let mut result: Vec<(String, Vec<_>)> = Vec::with_capacity(url_list.len());
+    url_list
+        .iter()
+        .try_for_each(|url| {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("load", url=%url).entered();
+            let scheme_string = url.scheme().to_string();
+            if let Some(loader) = loader_list
+                .iter()
+                .copied()
+                .find(|loader| loader.matches_scheme(&scheme_string))
+            {
+                warn_unsupported_soft_errors(loader, url, skip_soft_errors);
+                loader
+                    .load(url, maybe_whitelist, skip_soft_errors)
+                    .map(|mut loaded_list| {
+                        filter_unsupported_whitelist(&mut loaded_list, loader, maybe_whitelist);
+                        loaded_list
+                            .into_iter()
+                            .for_each(|(plugin_name, configuration)| {
+                                if let Some((_, configuration_list)) =
+                                    result.iter_mut().find(|(loaded_plugin_name, _)| {
+                                        loaded_plugin_name == &plugin_name
+                                    })
+                                {
+                                    configuration_list.push(configuration);
+                                } else {
+                                    result.push((plugin_name.clone(), [configuration].to_vec()))
+                                }
+                            });
+                    })
+            } else {
+                Err(LoaderError::LoaderNotFound {
+                    scheme: scheme_string,
+                    url: url.clone(),
+                })
+            }
+        })
+        .map(|_| result)
+}
+
+/// Warns only once per process so reloads do not repeat it.
+fn warn_unsupported_soft_errors(loader: &dyn Loader, _url: &Url, skip_soft_errors: bool) {
+    static WARN_ONCE: std::sync::Once = std::sync::Once::new();
+
+    if skip_soft_errors && !loader.supports_soft_errors() {
+        WARN_ONCE.call_once(|| {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::warn!(url=%_url, loader=loader.name(), "Loader ignores skipping soft errors");
+                } else if #[cfg(feature = "logging")] {
+                    log::warn!("msg=\"Loader ignores skipping soft errors\" url=\"{_url}\" loader={:?}", loader.name());
+                }
             }
+        });
+    }
+}
+
+/// Drops configurations of plugins that are not in the whitelist if `loader` does not support whitelists.
+fn filter_unsupported_whitelist(
+    loaded_list: &mut Vec<(String, ConfigurationEntity)>,
+    loader: &dyn Loader,
+    maybe_whitelist: Option<&[String]>,
+) {
+    if let Some(whitelist) = maybe_whitelist {
+        if !loader.supports_whitelist() {
+            loaded_list.retain(|(plugin_name, _)| whitelist.contains(plugin_name))
         }
-        self.parser_list.push(parser);
     }
+}
 
-    pub fn remove_parser<F: AsRef<str>>(&mut self, format: F) -> Vec<Box<dyn Parser>> {
-        let format = format.as_ref().to_lowercase();
-        let mut parser_list = Vec::new();
-        while let Some(index) = self
-            .parser_list
+/// URL scheme of documents added via [Configuration::add_default_document].
+const DEFAULT_DOCUMENT_SCHEME: &str = "default";
+
+/// Characters of environment-variable values that are percent-encoded in URL templates (see
+/// [Configuration::add_url_template]).
+const URL_TEMPLATE_VALUE_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>')
+    .add(b'?')
+    .add(b'@')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// MIME types that are mapped to formats by default (see [Configuration::map_mime]).
+const DEFAULT_MIME_FORMAT_LIST: &[(&str, &str)] = &[
+    ("application/json", "json"),
+    ("text/json", "json"),
+    ("application/yaml", "yaml"),
+    ("application/x-yaml", "yaml"),
+    ("text/yaml", "yaml"),
+    ("text/x-yaml", "yaml"),
+    ("application/toml", "toml"),
+    ("text/x-toml", "toml"),
+    ("application/hjson", "hjson"),
+];
+
+fn normalize_mime(mime: &str) -> String {
+    mime.split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+fn format_for_mime(mime: &str, mime_format_list: &[(String, String)]) -> Option<String> {
+    let mime = normalize_mime(mime);
+    let find = |mime: &str| {
+        mime_format_list
             .iter()
-            .position(|parser| parser.supported_format_list().contains(&format))
-        {
-            let parser = self.parser_list.swap_remove(index);
+            .find(|(inner_mime, _)| inner_mime == mime)
+            .map(|(_, format)| format.clone())
+    };
+    find(mime.as_str()).or_else(|| {
+        mime.rsplit_once('+')
+            .and_then(|(_, suffix)| find(format!("application/{suffix}").as_str()))
+    })
+}
+
+/// Sets format of configurations that have [CONTENT_TYPE_METADATA_KEY] metadata with a mapped MIME type (see
+/// [Configuration::map_mime]).
+pub fn apply_mime_format_list(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    mime_format_list: &[(String, String)],
+) {
+    plugin_configuration_list
+        .iter_mut()
+        .flat_map(|(_, configuration_list)| configuration_list.iter_mut())
+        .filter(|configuration| configuration.maybe_parsed_contents().is_none())
+        .for_each(|configuration| {
+            let Some(format) = configuration
+                .metadata()
+                .get(CONTENT_TYPE_METADATA_KEY)
+                .and_then(|mime| format_for_mime(mime, mime_format_list))
+            else {
+                return;
+            };
             cfg_if! {
                 if #[cfg(feature = "tracing")] {
-                    tracing::debug!(
-                        parser=%parser,
-                        format_list=?parser.supported_format_list(),
-                        "Removed configuration parser"
+                    tracing::trace!(
+                        url=%configuration.url(),
+                        old_format=configuration.maybe_format(),
+                        format=format,
+                        "Set format from MIME map"
                     );
                 } else if #[cfg(feature = "logging")] {
-                    log::debug!(
-                        "msg=\"Removed configuration parser\" parser=\"{parser}\" format_list={:?}",
-                        parser.supported_format_list()
+                    log::trace!(
+                        "msg=\"Set format from MIME map\" url=\"{}\" old_format={:?} format={format:?}",
+                        configuration.url(),
+                        configuration.maybe_format()
                     );
                 }
             }
-            parser_list.push(parser)
-        }
-        parser_list
+            configuration.set_format(format);
+        });
+}
+
+/// Sets formats of configurations of plugins in `plugin_format_list` (see [Configuration::add_plugin_format]).
+pub fn apply_plugin_format_list(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    plugin_format_list: &[(String, String)],
+) {
+    if plugin_format_list.is_empty() {
+        return;
+    }
+    plugin_configuration_list
+        .iter_mut()
+        .filter_map(|(plugin_name, configuration_list)| {
+            plugin_format_list
+                .iter()
+                .find(|(inner_plugin_name, _)| inner_plugin_name == plugin_name)
+                .map(|(_, format)| (format, configuration_list))
+        })
+        .for_each(|(format, configuration_list)| {
+            configuration_list
+                .iter_mut()
+                .filter(|configuration| configuration.maybe_parsed_contents().is_none())
+                .for_each(|configuration| {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::trace!(
+                                url=%configuration.url(),
+                                old_format=configuration.maybe_format(),
+                                format=format,
+                                "Set format of plugin"
+                            );
+                        } else if #[cfg(feature = "logging")] {
+                            log::trace!(
+                                "msg=\"Set format of plugin\" url=\"{}\" old_format={:?} format={format:?}",
+                                configuration.url(),
+                                configuration.maybe_format()
+                            );
+                        }
+                    }
+                    configuration.set_format(format);
+                })
+        });
+}
+
+pub fn apply_extension_format_list(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    parser_list: &[Box<dyn Parser>],
+    extension_format_list: &[(String, String)],
+) {
+    if extension_format_list.is_empty() {
+        return;
+    }
+    plugin_configuration_list
+        .iter_mut()
+        .flat_map(|(_, configuration_list)| configuration_list.iter_mut())
+        .filter(|configuration| configuration.maybe_parsed_contents().is_none())
+        .for_each(|configuration| {
+            let maybe_format = if let Some(format) = configuration.maybe_format() {
+                let extension = format.to_lowercase();
+                extension_format_list
+                    .iter()
+                    .find(|(inner_extension, _)| inner_extension == &extension)
+                    .map(|(_, format)| format.clone())
+            } else if let Some(contents) = configuration.maybe_contents() {
+                extension_format_list
+                    .iter()
+                    .map(|(_, format)| format)
+                    .find(|format| {
+                        parser_list.iter().any(|parser| {
+                            parser.supported_format_list().contains(format)
+                                && parser
+                                    .is_format_supported(contents.as_bytes())
+                                    .unwrap_or_default()
+                        })
+                    })
+                    .cloned()
+            } else {
+                None
+            };
+            if let Some(format) = maybe_format {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::trace!(
+                            url=%configuration.url(),
+                            old_format=configuration.maybe_format(),
+                            format=format,
+                            "Set format from extension map"
+                        );
+                    } else if #[cfg(feature = "logging")] {
+                        log::trace!(
+                            "msg=\"Set format from extension map\" url=\"{}\" old_format={:?} format={format:?}",
+                            configuration.url(),
+                            configuration.maybe_format()
+                        );
+                    }
+                }
+                configuration.set_format(format);
+            }
+        });
+}
+
+/// Guesses format of configurations without format by trying parsers in `probe_order` (see
+/// [Configuration::set_parser_probe_order]).
+pub fn apply_parser_probe_order(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    parser_list: &[Box<dyn Parser>],
+    probe_order: &[String],
+) {
+    if probe_order.is_empty() {
+        return;
+    }
+    let mut sorted_parser_list: Vec<_> = parser_list.iter().collect();
+    // Stable sort keeps order of adding for parsers with the same rank:
+    sorted_parser_list.sort_by_key(|parser| {
+        parser
+            .supported_format_list()
+            .iter()
+            .filter_map(|format| probe_order.iter().position(|probe| probe == format))
+            .min()
+            .unwrap_or(probe_order.len())
+    });
+    plugin_configuration_list
+        .iter_mut()
+        .flat_map(|(_, configuration_list)| configuration_list.iter_mut())
+        .filter(|configuration| {
+            configuration.maybe_format().is_none() && configuration.maybe_parsed_contents().is_none()
+        })
+        .for_each(|configuration| {
+            let Some(contents) = configuration.maybe_contents() else {
+                return;
+            };
+            let Some(format) = sorted_parser_list
+                .iter()
+                .find(|parser| {
+                    parser
+                        .is_format_supported(contents.as_bytes())
+                        .unwrap_or_default()
+                })
+                .and_then(|parser| parser.supported_format_list().last().cloned())
+            else {
+                return;
+            };
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::trace!(url=%configuration.url(), format=format, "Guessed format from probe order");
+                } else if #[cfg(feature = "logging")] {
+                    log::trace!(
+                        "msg=\"Guessed format from probe order\" url=\"{}\" format={format:?}",
+                        configuration.url()
+                    );
+                }
+            }
+            configuration.set_format(format);
+        });
+}
+
+/// Parses configurations whose format is unknown with parsers of `fallback_list` in order (see
+/// [Configuration::set_parser_fallback]).
+pub fn apply_parser_fallback(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    parser_list: &[Box<dyn Parser>],
+    fallback_list: &[String],
+) -> Result<(), Error> {
+    if fallback_list.is_empty() {
+        return Ok(());
+    }
+    let fallback_parser_list: Vec<_> = fallback_list
+        .iter()
+        .filter_map(|format| {
+            parser_list
+                .iter()
+                .find(|parser| parser.supported_format_list().contains(format))
+                .map(|parser| (format, parser))
+        })
+        .collect();
+    plugin_configuration_list
+        .iter_mut()
+        .try_for_each(|(plugin_name, configuration_list)| {
+            configuration_list
+                .iter_mut()
+                .filter(|configuration| {
+                    configuration.maybe_format().is_none()
+                        && configuration.maybe_parsed_contents().is_none()
+                        && configuration.maybe_contents().is_some()
+                        && configuration.guess_format(parser_list).is_none()
+                })
+                .try_for_each(|configuration| {
+                    let bytes = configuration
+                        .maybe_contents()
+                        .map(|contents| contents.as_bytes().to_vec())
+                        .unwrap_or_default();
+                    let mut maybe_last_error = None;
+                    for (format, parser) in fallback_parser_list.iter() {
+                        match parser.parse(bytes.as_slice()) {
+                            Ok(parsed) => {
+                                cfg_if! {
+                                    if #[cfg(feature = "tracing")] {
+                                        tracing::trace!(url=%configuration.url(), format=format.as_str(), "Parsed with fallback parser");
+                                    } else if #[cfg(feature = "logging")] {
+                                        log::trace!(
+                                            "msg=\"Parsed with fallback parser\" url=\"{}\" format={format:?}",
+                                            configuration.url()
+                                        );
+                                    }
+                                }
+                                configuration.set_format(*format);
+                                configuration.set_parsed_contents(parsed);
+                                return Ok(());
+                            }
+                            Err(error) => maybe_last_error = Some(error),
+                        }
+                    }
+                    match maybe_last_error {
+                        Some(error) => Err(Error::Parse {
+                            plugin_name: plugin_name.to_string(),
+                            url: configuration.url().clone(),
+                            item: configuration.item().clone().into(),
+                            source: Box::new(error),
+                        }),
+                        None => Ok(()),
+                    }
+                })
+        })
+}
+
+pub fn parse(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    parser_list: &[Box<dyn Parser>],
+    log_options: LogOptions,
+) -> Result<(), Error> {
+    plugin_configuration_list
+        .iter_mut()
+        .try_for_each(|(plugin_name, configuration_list)| {
+            configuration_list.iter_mut().try_for_each(|configuration| {
+                parse_entity(plugin_name, configuration, parser_list, log_options)
+            })
+        })
+}
+
+/// Same as [parse] but parses up to `concurrency` configurations at the same time in scoped threads.
+///
+/// If more than one configuration could not be parsed, the error of the first one (in order of the list) is
+/// returned.
+pub fn parse_concurrently(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    parser_list: &[Box<dyn Parser>],
+    concurrency: usize,
+    log_options: LogOptions,
+) -> Result<(), Error> {
+    let worker_count = concurrency.min(
+        plugin_configuration_list
+            .iter()
+            .flat_map(|(_, configuration_list)| configuration_list.iter())
+            .filter(|configuration| !configuration.is_parsed())
+            .count(),
+    );
+    if worker_count <= 1 {
+        return parse(plugin_configuration_list, parser_list, log_options);
     }
+    let mut queue: Vec<_> = plugin_configuration_list
+        .iter_mut()
+        .flat_map(|(plugin_name, configuration_list)| {
+            let plugin_name: &String = plugin_name;
+            configuration_list
+                .iter_mut()
+                .filter(|configuration| !configuration.is_parsed())
+                .map(move |configuration| (plugin_name, configuration))
+        })
+        .enumerate()
+        .collect();
+    // Items are popped from the end:
+    queue.reverse();
+    let queue = std::sync::Mutex::new(queue);
+    #[cfg(feature = "tracing")]
+    let span = tracing::Span::current();
+    let mut error_list: Vec<(usize, Error)> = std::thread::scope(|scope| {
+        (0..worker_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    // Workers log inside the span of the caller:
+                    #[cfg(feature = "tracing")]
+                    let _span = span.enter();
+                    let mut error_list = Vec::new();
+                    loop {
+                        let maybe_item = queue
+                            .lock()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)
+                            .pop();
+                        let Some((index, (plugin_name, configuration))) = maybe_item else {
+                            break;
+                        };
+                        if let Err(error) =
+                            parse_entity(plugin_name, configuration, parser_list, log_options)
+                        {
+                            error_list.push((index, error));
+                        }
+                    }
+                    error_list
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    error_list.sort_by_key(|(index, _)| *index);
+    error_list
+        .into_iter()
+        .next()
+        .map(|(_, error)| Err(error))
+        .unwrap_or(Ok(()))
+}
 
-    pub fn load_and_parse(
-        &self,
-        skip_soft_errors: bool,
-    ) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, Error> {
-        let mut load_result = self.load(skip_soft_errors)?;
-        parse(load_result.as_mut(), self.parser_list.as_slice())?;
-        Ok(load_result)
+fn parse_entity(
+    plugin_name: &str,
+    configuration: &mut ConfigurationEntity,
+    parser_list: &[Box<dyn Parser>],
+    log_options: LogOptions,
+) -> Result<(), Error> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!(
+        "parse",
+        plugin=plugin_name,
+        url=%configuration.url()
+    )
+    .entered();
+    if !configuration.is_parsed() {
+        #[cfg(feature = "env")]
+        let maybe_env_parser_list = env_parser_list(configuration, parser_list);
+        #[cfg(feature = "env")]
+        let parser_list = maybe_env_parser_list.as_deref().unwrap_or(parser_list);
+        let parsed = log_options
+            .scope(|| configuration.parse_contents(parser_list))
+            .map_err(|error| Error::Parse {
+                plugin_name: plugin_name.to_string(),
+                url: configuration.url().clone(),
+                item: configuration.item().clone().into(),
+                source: Box::new(error),
+            })?;
+        configuration.set_parsed_contents(parsed);
     }
+    Ok(())
 }
 
-impl Configuration {
-    pub fn is_in_whitelist<P: AsRef<str>>(&self, name: P) -> bool {
-        let name = name.as_ref().to_lowercase();
-        self.maybe_whitelist
-            .as_ref()
-            .map(|whitelist| whitelist.contains(&name))
-            .unwrap_or(false)
+/// Fails if a parsed configuration has values nested deeper than `max_depth` maps and lists.
+pub fn check_depth(
+    plugin_configuration_list: &[(String, Vec<ConfigurationEntity>)],
+    max_depth: usize,
+) -> Result<(), Error> {
+    plugin_configuration_list
+        .iter()
+        .try_for_each(|(plugin_name, configuration_list)| {
+            configuration_list
+                .iter()
+                .filter_map(|configuration| {
+                    configuration
+                        .maybe_parsed_contents()
+                        .map(|input| (configuration.url(), input))
+                })
+                .find(|(_, input)| is_too_deep(input, max_depth))
+                .map_or(Ok(()), |(url, input)| {
+                    let path = too_deep_path(input, max_depth).unwrap_or_default();
+                    Err(Error::Other(anyhow!(
+                        "`{plugin_name}` configuration from `{url}` is nested deeper than {max_depth} levels at `{path}`"
+                    )))
+                })
+        })
+}
+
+/// Whether `input` has values nested deeper than `max_depth` maps and lists.
+///
+/// Like [too_deep_path], it walks `input` with a stack instead of recursion but only keeps depths, so it's cheap for
+/// the common case.
+fn is_too_deep(input: &Input, max_depth: usize) -> bool {
+    let mut stack = vec![(input, 0)];
+    while let Some((input, depth)) = stack.pop() {
+        if depth > max_depth {
+            return true;
+        }
+        if input.is_map() {
+            stack.extend(input.as_map().values().map(|input| (input, depth + 1)));
+        } else if input.is_list() {
+            stack.extend(input.as_list().iter().map(|input| (input, depth + 1)));
+        }
     }
+    false
+}
 
-    pub fn load_whitelist_from_env<K: AsRef<str>>(&mut self, key: K) -> Result<(), Error> {
-        let whitelist = env::var(key.as_ref())
-            .map(|value| value.trim().to_lowercase())
-            .map(|value| {
-                if value.is_empty() {
-                    Vec::new()
+/// Returns path of the first value of `input` that is nested deeper than `max_depth` maps and lists.
+///
+/// It walks `input` with a stack instead of recursion, so it does not overflow on deep inputs itself.
+fn too_deep_path(input: &Input, max_depth: usize) -> Option<String> {
+    let mut stack = vec![(input, 0, String::new())];
+    while let Some((input, depth, path)) = stack.pop() {
+        if depth > max_depth {
+            return Some(path);
+        }
+        if input.is_map() {
+            input.as_map().iter().for_each(|(key, input)| {
+                let path = if path.is_empty() {
+                    key.clone()
                 } else {
-                    value.split([' ', ',', ';']).map(String::from).collect()
-                }
-            })
-            .map_err(|error| {
-                Error::Other(anyhow!("Invalid key or the value is not set: {}", error))
-            })?;
-        if whitelist.is_empty() {
-            cfg_if! {
-                if #[cfg(feature = "tracing")] {
-                    tracing::warn!(key=key.as_ref(), "Whitelist environment-variable is set to empty")
-                } else if #[cfg(feature = "logging")] {
-                    log::warn!("msg=\"Whitelist environment-variable is set to empty\" key={:?}", key.as_ref())
-                }
-            }
-        } else {
-            cfg_if! {
-                if #[cfg(feature = "tracing")] {
-                    tracing::info!(key=key.as_ref(), "Set whitelist from environment-variable")
-                } else if #[cfg(feature = "logging")] {
-                    log::info!("msg=\"Set whitelist from environment-variable\" key={:?}", key.as_ref())
-                }
-            }
+                    format!("{path}.{key}")
+                };
+                stack.push((input, depth + 1, path))
+            });
+        } else if input.is_list() {
+            input
+                .as_list()
+                .iter()
+                .enumerate()
+                .for_each(|(index, input)| {
+                    stack.push((input, depth + 1, format!("{path}[{index}]")))
+                });
         }
-        self.set_whitelist(whitelist.as_ref());
-        Ok(())
     }
+    None
+}
 
-    pub fn set_whitelist_from_env<K: AsRef<str>>(mut self, key: K) -> Result<Self, Error> {
-        self.load_whitelist_from_env(key)?;
-        Ok(self)
+/// Returns an env parser with the key separator that the env loader used for `configuration` (see
+/// [KEY_SEPARATOR_METADATA_KEY](crate::loader::env::KEY_SEPARATOR_METADATA_KEY)) if `parser_list` supports `env`
+/// format.
+#[cfg(feature = "env")]
+fn env_parser_list(
+    configuration: &ConfigurationEntity,
+    parser_list: &[Box<dyn Parser>],
+) -> Option<Vec<Box<dyn Parser>>> {
+    if configuration.maybe_format().map(String::as_str) != Some("env")
+        || !parser_list.iter().any(|parser| {
+            parser
+                .supported_format_list()
+                .iter()
+                .any(|format| format == "env")
+        })
+    {
+        return None;
     }
+    configuration
+        .metadata()
+        .get(crate::loader::env::KEY_SEPARATOR_METADATA_KEY)
+        .map(|separator| {
+            let parser: Box<dyn Parser> =
+                Box::new(crate::parser::env::Env::new().with_key_separator(separator));
+            vec![parser]
+        })
+}
 
-    pub fn set_whitelist<N: AsRef<str>>(&mut self, whitelist: &[N]) {
-        whitelist
-            .iter()
-            .for_each(|name| self.add_to_whitelist(name));
-    }
+pub fn alias(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    alias_list: &[(String, String, String)],
+    policy: AliasConflictPolicy,
+) -> Result<(), Error> {
+    plugin_configuration_list
+        .iter_mut()
+        .try_for_each(|(plugin_name, configuration_list)| {
+            alias_list
+                .iter()
+                .filter(|(plugin, _, _)| plugin == plugin_name)
+                .try_for_each(|(_, from_path, to_path)| {
+                    configuration_list.iter_mut().try_for_each(|configuration| {
+                        let url = configuration.url().clone();
+                        let Some(parsed) = configuration.maybe_parsed_contents_mut().as_mut() else {
+                            return Ok(());
+                        };
+                        let Some(value) = take_path(parsed, from_path) else {
+                            return Ok(());
+                        };
+                        if get_path(parsed, to_path).is_some() {
+                            match policy {
+                                AliasConflictPolicy::Error => {
+                                    return Err(Error::Other(anyhow!(
+                                        "Both `{from_path}` and its alias `{to_path}` are set in `{plugin_name}` configuration from `{url}`"
+                                    )))
+                                }
+                                AliasConflictPolicy::PreferTo => return Ok(()),
+                                AliasConflictPolicy::PreferFrom => (),
+                            }
+                        }
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::trace!(plugin=plugin_name, url=%url, from=from_path, to=to_path, "Applied alias")
+                            } else if #[cfg(feature = "logging")] {
+                                log::trace!("msg=\"Applied alias\" plugin={plugin_name:?} url=\"{url}\" from={from_path:?} to={to_path:?}")
+                            }
+                        }
+                        insert_path(parsed, to_path, value).map_err(|error| {
+                            Error::Other(anyhow!(
+                                "Could not apply alias `{from_path}` -> `{to_path}` to `{plugin_name}` configuration from `{url}`: {error}"
+                            ))
+                        })
+                    })
+                })
+        })
+}
 
-    pub fn with_whitelist<N: AsRef<str>>(mut self, whitelist: &[N]) -> Self {
-        self.set_whitelist(whitelist);
-        self
-    }
+/// Only files may have `#` comment lines.
+fn split_whitelist(text: &str, skip_comments: bool) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !(skip_comments && line.starts_with('#')))
+        .flat_map(|line| {
+            line.split(|character: char| {
+                character.is_whitespace() || character == ',' || character == ';'
+            })
+        })
+        .filter(|name| !name.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
 
-    pub fn add_to_whitelist<N: AsRef<str>>(&mut self, name: N) {
-        let name = name.as_ref().to_lowercase();
-        cfg_if! {
-            if #[cfg(feature = "tracing")] {
-                tracing::debug!(name=name, "Added to whitelist")
-            } else if #[cfg(feature = "logging")] {
-                log::debug!("msg=\"Added to whitelist\" name={name:?}")
-            }
+#[cfg(feature = "env")]
+fn flatten_env(
+    key: String,
+    input: &Input,
+    separator: &str,
+    env_list: &mut Vec<(String, String)>,
+) -> anyhow::Result<()> {
+    let value = if input.is_map() {
+        for (inner_key, inner_input) in input.as_map() {
+            flatten_env(
+                format!("{key}{separator}{}", inner_key.to_uppercase()),
+                inner_input,
+                separator,
+                env_list,
+            )?;
         }
-        if let Some(whitelist) = self.maybe_whitelist.as_mut() {
-            if !whitelist.contains(&name) {
-                whitelist.push(name);
-            }
+        return Ok(());
+    } else if input.is_str() {
+        let value = input.as_str();
+        // Environment-variable values are parsed as JSON if possible:
+        if serde_json::from_str::<Input>(value).is_ok() {
+            serde_json::to_string(value)?
         } else {
-            self.maybe_whitelist = Some(Vec::from([name]));
+            value.clone()
         }
-    }
+    } else {
+        serde_json::to_string(input)?
+    };
+    env_list.push((key, value));
+    Ok(())
 }
 
-impl Configuration {
-    pub fn load_parse_merge(&self, skip_soft_errors: bool) -> Result<Vec<(String, Input)>, Error> {
-        let mut parsed = self.load_and_parse(skip_soft_errors)?;
-        merge(parsed.as_mut())
+#[allow(unused_variables)]
+fn serialize(format: &str, input: &Input, sort_keys: bool) -> anyhow::Result<String> {
+    if sort_keys {
+        serialize_with(format, &SortedKeys(input))
+    } else {
+        serialize_with(format, input)
     }
+}
 
-    pub fn load_parse_merge_validate(
-        &self,
-        schema_list: &[(String, InputSchemaType)],
-        skip_soft_errors: bool,
-    ) -> Result<Vec<(String, Input)>, Error> {
-        let mut merged = self.load_parse_merge(skip_soft_errors)?;
-        validate(merged.as_mut(), schema_list)
+#[allow(unused_variables)]
+fn serialize_with<T: Serialize>(format: &str, input: &T) -> anyhow::Result<String> {
+    match format {
+        #[cfg(feature = "json")]
+        "json" => serde_json::to_string_pretty(input)
+            .map(|text| text + "\n")
+            .map_err(|error| anyhow!(error)),
+        #[cfg(feature = "yaml")]
+        "yml" | "yaml" => serde_yaml::to_string(input).map_err(|error| anyhow!(error)),
+        #[cfg(feature = "toml")]
+        "toml" => toml::to_string_pretty(input).map_err(|error| anyhow!(error)),
+        _ => Err(anyhow!("Serializing `{format}` format is not supported")),
     }
 }
 
-pub fn load(
-    url_list: &[Url],
-    loader_list: &[Box<dyn Loader>],
-    maybe_whitelist: Option<&[String]>,
-    skip_soft_errors: bool,
-) -> Result<Vec<(String, Vec<ConfigurationEntity>)>, LoaderError> {
-    let mut result: Vec<(String, Vec<_>)> = Vec::with_capacity(url_list.len());
-    url_list
+#[cfg(feature = "jsonschema")]
+pub fn validate_jsonschema(
+    plugin_configuration_list: &[(String, Input)],
+    schema_list: &[(String, serde_json::Value)],
+) -> Result<(), Error> {
+    plugin_configuration_list
         .iter()
-        .try_for_each(|url| {
-            let scheme_string = url.scheme().to_string();
-            if let Some(loader) = loader_list
+        .try_for_each(|(plugin_name, configuration)| {
+            let Some((_, schema)) = schema_list
                 .iter()
-                .find(|loader| loader.scheme_list().contains(&scheme_string))
-            {
-                loader
-                    .load(url, maybe_whitelist, skip_soft_errors)
-                    .map(|loaded_list| {
-                        loaded_list
-                            .into_iter()
-                            .for_each(|(plugin_name, configuration)| {
-                                if let Some((_, configuration_list)) =
-                                    result.iter_mut().find(|(loaded_plugin_name, _)| {
-                                        loaded_plugin_name == &plugin_name
-                                    })
-                                {
-                                    configuration_list.push(configuration);
-                                } else {
-                                    result.push((plugin_name.clone(), [configuration].to_vec()))
-                                }
-                            });
-                    })
-            } else {
-                Err(LoaderError::LoaderNotFound {
-                    scheme: scheme_string,
-                    url: url.clone(),
-                })
-            }
+                .find(|(schema_plugin_name, _)| schema_plugin_name == plugin_name)
+            else {
+                return Ok(());
+            };
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("validate", plugin = plugin_name.as_str()).entered();
+            let compiled = jsonschema::JSONSchema::options()
+                .with_draft(jsonschema::Draft::Draft7)
+                .compile(schema)
+                .map_err(|error| {
+                    Error::Other(anyhow!("Invalid JSON Schema for `{plugin_name}`: {error}"))
+                })?;
+            let instance = serde_json::to_value(configuration).map_err(|error| {
+                Error::Other(anyhow!(
+                    "Could not convert `{plugin_name}` configuration to JSON: {error}"
+                ))
+            })?;
+            compiled.validate(&instance).map_err(|error_iter| {
+                let error_list = error_iter
+                    .map(|error| format!("{}: {error}", error.instance_path))
+                    .collect();
+                Error::ValidateJsonSchema {
+                    plugin_name: plugin_name.to_string(),
+                    error_list,
+                }
+            })
         })
-        .map(|_| result)
 }
 
-pub fn parse(
-    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
-    parser_list: &[Box<dyn Parser>],
+/// Converts human-readable durations to integer milliseconds and byte sizes to integer bytes (see
+/// [Configuration::set_duration_keys] and [Configuration::set_bytesize_keys]).
+#[cfg(feature = "units")]
+pub fn convert_units(
+    plugin_configuration_list: &mut [(String, Input)],
+    duration_key_list: &[String],
+    bytesize_key_list: &[String],
 ) -> Result<(), Error> {
     plugin_configuration_list
         .iter_mut()
-        .try_for_each(|(plugin_name, configuration_list)| {
-            configuration_list
-                .iter_mut()
-                .try_for_each(|configuration| {
-                    if configuration.maybe_parsed_contents().is_none() {
-                        let parsed =
-                            configuration.parse_contents(parser_list).map_err(|error| {
-                                Error::Parse {
-                                    plugin_name: plugin_name.to_string(),
-                                    url: configuration.url().clone(),
-                                    item: configuration.item().clone().into(),
-                                    source: error,
-                                }
-                            })?;
-                        configuration.set_parsed_contents(parsed);
-                    }
-                    Ok::<_, Error>(())
+        .try_for_each(|(plugin_name, configuration)| {
+            for key in duration_key_list {
+                for_each_matching_str(plugin_name, configuration, key, &mut |path, value| {
+                    let duration = humantime::parse_duration(value.as_str()).map_err(|error| {
+                        anyhow!(
+                            "Could not parse duration `{}` of `{path}`: {error}",
+                            value.as_str()
+                        )
+                    })?;
+                    let milliseconds = isize::try_from(duration.as_millis()).map_err(|_| {
+                        anyhow!("Duration `{}` of `{path}` is too large", value.as_str())
+                    })?;
+                    *value = Input::Int(milliseconds);
+                    Ok(())
                 })?;
-            Ok::<_, Error>(())
+            }
+            for key in bytesize_key_list {
+                for_each_matching_str(plugin_name, configuration, key, &mut |path, value| {
+                    let size = value
+                        .as_str()
+                        .parse::<bytesize::ByteSize>()
+                        .map_err(|error| {
+                            anyhow!(
+                                "Could not parse byte size `{}` of `{path}`: {error}",
+                                value.as_str()
+                            )
+                        })?;
+                    let bytes = isize::try_from(size.as_u64()).map_err(|_| {
+                        anyhow!("Byte size `{}` of `{path}` is too large", value.as_str())
+                    })?;
+                    *value = Input::Int(bytes);
+                    Ok(())
+                })?;
+            }
+            Ok(())
         })
+        .map_err(Error::Other)
+}
+
+/// Calls `f` for every string value whose dotted path (starting with the plugin name) matches `key`.
+#[cfg(feature = "units")]
+fn for_each_matching_str(
+    plugin_name: &str,
+    input: &mut Input,
+    key: &str,
+    f: &mut dyn FnMut(&str, &mut Input) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    fn visit(
+        input: &mut Input,
+        key_list: &[&str],
+        path: String,
+        f: &mut dyn FnMut(&str, &mut Input) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let Some((key, key_list)) = key_list.split_first() else {
+            return if input.is_str() {
+                f(&path, input)
+            } else {
+                Ok(())
+            };
+        };
+        if !input.is_map() {
+            return Ok(());
+        }
+        for (name, input) in input.map_mut().iter_mut() {
+            if *key == "*" || key == name {
+                visit(input, key_list, format!("{path}.{name}"), f)?;
+            }
+        }
+        Ok(())
+    }
+
+    let key_list: Vec<_> = key.split('.').collect();
+    match key_list.split_first() {
+        Some((first, key_list))
+            if !key_list.is_empty() && (*first == "*" || *first == plugin_name) =>
+        {
+            visit(input, key_list, plugin_name.to_string(), f)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn is_shell_variable_name(name: &str) -> bool {
+    let mut character_list = name.chars();
+    character_list
+        .next()
+        .map(|first| first.is_ascii_alphabetic() || first == '_')
+        .unwrap_or(false)
+        && character_list.all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|index| glob_match(rest, &text[index..])),
+        Some(('?', rest)) => !text.is_empty() && glob_match(rest, &text[1..]),
+        Some((character, rest)) => text.first() == Some(character) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// Numeric keys of the dotted path are list indexes.
+fn get_path<'a>(input: &'a Input, path: &str) -> Option<&'a Input> {
+    path.split('.').try_fold(input, |input, key| {
+        if input.is_map() {
+            input.as_map().get(key)
+        } else if input.is_list() {
+            key.parse::<usize>()
+                .ok()
+                .and_then(|index| input.as_list().get(index))
+        } else {
+            None
+        }
+    })
+}
+
+fn take_path(input: &mut Input, path: &str) -> Option<Input> {
+    let (parent_path, key) = path.rsplit_once('.').unwrap_or(("", path));
+    let mut parent = input;
+    if !parent_path.is_empty() {
+        for key in parent_path.split('.') {
+            if !parent.is_map() {
+                return None;
+            }
+            parent = parent.map_mut().get_mut(key)?;
+        }
+    }
+    if parent.is_map() {
+        parent.map_mut().remove(key)
+    } else {
+        None
+    }
+}
+
+fn insert_path(input: &mut Input, path: &str, value: Input) -> anyhow::Result<()> {
+    let (parent_path, key) = path.rsplit_once('.').unwrap_or(("", path));
+    let mut parent = input;
+    if !parent_path.is_empty() {
+        for key in parent_path.split('.') {
+            if !parent.is_map() {
+                return Err(anyhow!("`{key}` is not inside a map"));
+            }
+            parent = parent
+                .map_mut()
+                .entry(key.to_string())
+                .or_insert_with(Input::new_map);
+        }
+    }
+    if !parent.is_map() {
+        return Err(anyhow!("`{key}` is not inside a map"));
+    }
+    parent.map_mut().insert(key.to_string(), value);
+    Ok(())
 }
 
+/// Merges parsed configurations of each plugin in order (later ones override earlier ones).
 pub fn merge(
     plugin_configuration_list: &[(String, Vec<ConfigurationEntity>)],
+) -> Result<Vec<(String, Input)>, Error> {
+    merge_with_precedence(plugin_configuration_list, &[])
+}
+
+/// Like [merge] but merges configurations of plugins in `precedence_list` according to their [SourcePrecedence].
+pub fn merge_with_precedence(
+    plugin_configuration_list: &[(String, Vec<ConfigurationEntity>)],
+    precedence_list: &[(String, SourcePrecedence)],
 ) -> Result<Vec<(String, Input)>, Error> {
     let mut result = Vec::with_capacity(plugin_configuration_list.len());
     plugin_configuration_list
         .iter()
-        .for_each(|(plugin_name, configuration_list)| {
+        .try_for_each(|(plugin_name, configuration_list)| {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("merge", plugin = plugin_name.as_str()).entered();
             let mut first = Input::new_map();
-            configuration_list
+            let precedence = precedence_list
                 .iter()
+                .find(|(inner_plugin_name, _)| inner_plugin_name == plugin_name)
+                .map(|(_, precedence)| *precedence)
+                .unwrap_or_default();
+            merge_index_list(configuration_list, precedence)
+                .into_iter()
+                .map(|index| &configuration_list[index])
                 .filter(|configuration| configuration.maybe_parsed_contents().is_some())
-                .for_each(|configuration| {
-                    plugx_input::merge::merge_with_positions(
-                        &mut first,
-                        plugx_input::position::new().new_with_key(plugin_name),
-                        configuration.maybe_parsed_contents().unwrap(),
-                        plugx_input::position::new().new_with_key(configuration.url().as_str()),
-                    )
-                });
+                .try_for_each(|configuration| {
+                    configuration
+                        .merge_into(&mut first, plugin_name.as_str(), &[])
+                        .map_err(|error| Error::Parse {
+                            plugin_name: plugin_name.to_string(),
+                            url: configuration.url().clone(),
+                            item: configuration.item().clone().into(),
+                            source: Box::new(error),
+                        })
+                })?;
             result.push((plugin_name.to_string(), first));
-        });
+            Ok::<_, Error>(())
+        })?;
+    Ok(result)
+}
+
+/// Same as [merge_with_precedence] but takes parsed contents of plugins that have only one parsed configuration instead
+/// of merging (cloning) them into an empty map, which yields the same result.
+fn merge_owned(
+    plugin_configuration_list: &mut [(String, Vec<ConfigurationEntity>)],
+    precedence_list: &[(String, SourcePrecedence)],
+) -> Result<Vec<(String, Input)>, Error> {
+    let mut result = Vec::with_capacity(plugin_configuration_list.len());
+    for plugin_configuration in plugin_configuration_list.iter_mut() {
+        let (plugin_name, configuration_list) = &mut *plugin_configuration;
+        let mut parsed_list = configuration_list
+            .iter_mut()
+            .map(|configuration| configuration.maybe_parsed_contents_mut())
+            .filter(|maybe_parsed_contents| maybe_parsed_contents.is_some());
+        if let (Some(single), None) = (parsed_list.next(), parsed_list.next()) {
+            result.extend(single.take().map(|input| (plugin_name.to_string(), input)));
+            continue;
+        }
+        result.append(&mut merge_with_precedence(
+            std::slice::from_ref(&*plugin_configuration),
+            precedence_list,
+        )?);
+    }
     Ok(result)
 }
 
+/// Replaces values of `input` with `<redacted>` and keeps keys of its maps (see [Configuration::set_dump_values]).
+fn redact(input: &Input) -> Input {
+    if input.is_map() {
+        Input::from(
+            input
+                .as_map()
+                .iter()
+                .map(|(key, input)| (key.clone(), redact(input)))
+                .collect::<HashMap<_, _>>(),
+        )
+    } else if input.is_list() {
+        Input::from(input.as_list().iter().map(redact).collect::<Vec<_>>())
+    } else {
+        Input::from("<redacted>")
+    }
+}
+
+/// Returns indexes of `configuration_list` in the order they are merged (later ones override earlier ones).
+fn merge_index_list(
+    configuration_list: &[ConfigurationEntity],
+    precedence: SourcePrecedence,
+) -> Vec<usize> {
+    let is_default =
+        |index: &usize| configuration_list[*index].url().scheme() == DEFAULT_DOCUMENT_SCHEME;
+    match precedence {
+        SourcePrecedence::LastWins => (0..configuration_list.len()).collect(),
+        // Default documents are still merged first:
+        SourcePrecedence::FirstWins => (0..configuration_list.len())
+            .filter(is_default)
+            .chain(
+                (0..configuration_list.len())
+                    .rev()
+                    .filter(|index| !is_default(index)),
+            )
+            .collect(),
+    }
+}
+
+/// Removes string values that are equal to one of `sentinel_list` from maps of `input` and returns their key paths.
+fn take_null_path_list(input: &mut Input, sentinel_list: &[String]) -> Vec<Vec<String>> {
+    fn visit(
+        input: &mut Input,
+        sentinel_list: &[String],
+        path: &mut Vec<String>,
+        path_list: &mut Vec<Vec<String>>,
+    ) {
+        if !input.is_map() {
+            return;
+        }
+        input.map_mut().retain(|key, value| {
+            path.push(key.clone());
+            let is_null = value.is_str()
+                && sentinel_list
+                    .iter()
+                    .any(|sentinel| sentinel.as_str() == value.as_str());
+            if is_null {
+                path_list.push(path.clone());
+            } else {
+                visit(value, sentinel_list, path, path_list);
+            }
+            path.pop();
+            !is_null
+        });
+    }
+
+    let mut path_list = Vec::new();
+    visit(input, sentinel_list, &mut Vec::new(), &mut path_list);
+    path_list
+}
+
+fn remove_path(input: &mut Input, path: &[String]) {
+    let Some((key, parent_path)) = path.split_last() else {
+        return;
+    };
+    let mut parent = input;
+    for key in parent_path {
+        if !parent.is_map() {
+            return;
+        }
+        let Some(child) = parent.map_mut().get_mut(key) else {
+            return;
+        };
+        parent = child;
+    }
+    if parent.is_map() {
+        parent.map_mut().remove(key);
+    }
+}
+
 pub fn validate(
     plugin_configuration_list: &[(String, Input)],
     schema_list: &[(String, InputSchemaType)],
@@ -521,6 +4484,8 @@ pub fn validate(
     plugin_configuration_list
         .iter()
         .try_for_each(|(plugin_name, configuration)| {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("validate", plugin = plugin_name.as_str()).entered();
             let mut configuration = configuration.clone();
             if let Some((_, schema_type)) = schema_list
                 .iter()
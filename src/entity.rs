@@ -18,14 +18,14 @@
 //! let loaded = loader.load(&url, None, false).unwrap();
 //! let (_, foo_entity) = loaded.iter().find(|(plugin_name, _)| plugin_name == "foo").expect("`foo` plugin config");
 //! // Above `loader` actually does this:
-//! let loader_name = format!("{loader}");
+//! let loader_name = loader.name();
 //! let mut foo_entity2 = ConfigurationEntity::new("MY_APP_NAME__*", url.clone(), plugin_name, loader_name)
 //!     .with_format("env")
 //!     .with_contents("BAR__BAZ=\"3.14\"\nQUX=\"false\"");
 //!
 //! assert_eq!(&foo_entity2, foo_entity);
 //!
-//! // We can pass a list of `ConfigurationParser` to an entity to parse its contents.
+//! // We can pass a list of `Parser` to an entity to parse its contents.
 //! let parser = EnvParser::new().with_key_separator("__");
 //! let parser_list: Vec<Box<dyn Parser>> = vec![Box::new(parser)];
 //! let input = foo_entity2.parse_contents_mut(&parser_list).unwrap();
@@ -33,11 +33,22 @@
 //! ```
 use crate::parser::{Error, Parser};
 use plugx_input::Input;
-use std::fmt::{Display, Formatter};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::{self, Read};
+use std::sync::Arc;
 use url::Url;
 
+/// Metadata key of the MIME type (e.g. `Content-Type` of a response) of contents which is mapped to a format before
+/// parsing (see [crate::Configuration::map_mime]).
+pub const CONTENT_TYPE_METADATA_KEY: &str = "content-type";
+
+/// A `|| -> Result<Box<dyn Read>, io::Error>` [Fn] that opens contents of an entity which are not read into memory
+/// (see [ConfigurationEntity::set_contents_reader]).
+pub type ContentsReaderFn = Arc<dyn Fn() -> Result<Box<dyn Read + Send>, io::Error> + Send + Sync>;
+
 /// A configuration entity for each plugin.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone)]
 pub struct ConfigurationEntity {
     item: String,
     loader_name: String,
@@ -45,7 +56,43 @@ pub struct ConfigurationEntity {
     plugin_name: String,
     maybe_format: Option<String>,
     maybe_contents: Option<String>,
+    maybe_contents_reader: Option<ContentsReaderFn>,
     maybe_parsed: Option<Input>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl Debug for ConfigurationEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigurationEntity")
+            .field("item", &self.item)
+            .field("loader_name", &self.loader_name)
+            .field("url", &self.url)
+            .field("plugin_name", &self.plugin_name)
+            .field("maybe_format", &self.maybe_format)
+            .field("maybe_contents", &self.maybe_contents)
+            .field(
+                "maybe_contents_reader",
+                &self.maybe_contents_reader.as_ref().map(|_| "<reader>"),
+            )
+            .field("maybe_parsed", &self.maybe_parsed)
+            .field("metadata", &self.metadata)
+            .finish()
+    }
+}
+
+impl PartialEq for ConfigurationEntity {
+    /// Contents readers are only compared by their existence.
+    fn eq(&self, other: &Self) -> bool {
+        self.item == other.item
+            && self.loader_name == other.loader_name
+            && self.url == other.url
+            && self.plugin_name == other.plugin_name
+            && self.maybe_format == other.maybe_format
+            && self.maybe_contents == other.maybe_contents
+            && self.maybe_contents_reader.is_some() == other.maybe_contents_reader.is_some()
+            && self.maybe_parsed == other.maybe_parsed
+            && self.metadata == other.metadata
+    }
 }
 
 impl ConfigurationEntity {
@@ -68,7 +115,9 @@ impl ConfigurationEntity {
             loader_name: loader_name.as_ref().to_string(),
             maybe_format: Default::default(),
             maybe_contents: Default::default(),
+            maybe_contents_reader: Default::default(),
             maybe_parsed: Default::default(),
+            metadata: Default::default(),
         }
     }
 
@@ -90,6 +139,20 @@ impl ConfigurationEntity {
         self
     }
 
+    /// Sets a reader for contents that are too large to be read into memory first (e.g. big files).
+    ///
+    /// If no contents are set, the parser of the entity format parses them directly from the reader (see
+    /// [Parser::parse_stream]). Formats of such entities can not be guessed, so it's better to set the format too.
+    pub fn set_contents_reader(&mut self, contents_reader: ContentsReaderFn) {
+        self.maybe_contents_reader = Some(contents_reader);
+    }
+
+    /// Sets a reader for contents that are too large to be read into memory first (see [Self::set_contents_reader]).
+    pub fn with_contents_reader(mut self, contents_reader: ContentsReaderFn) -> Self {
+        self.set_contents_reader(contents_reader);
+        self
+    }
+
     pub fn set_parsed_contents<I: Into<Input>>(&mut self, contents: I) {
         self.maybe_parsed = Some(contents.into());
     }
@@ -99,6 +162,34 @@ impl ConfigurationEntity {
         self
     }
 
+    /// Sets extra information (e.g. a commit hash or a secret version) which is not used in parsing or merging.
+    pub fn set_metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) {
+        self.metadata
+            .insert(key.as_ref().to_string(), value.as_ref().to_string());
+    }
+
+    /// Sets extra information (e.g. a commit hash or a secret version) which is not used in parsing or merging.
+    pub fn with_metadata<K: AsRef<str>, V: AsRef<str>>(mut self, key: K, value: V) -> Self {
+        self.set_metadata(key, value);
+        self
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.metadata
+    }
+
+    pub fn loader_name(&self) -> &String {
+        &self.loader_name
+    }
+
+    pub fn loader_name_mut(&mut self) -> &mut String {
+        &mut self.loader_name
+    }
+
     pub fn item(&self) -> &String {
         &self.item
     }
@@ -139,6 +230,14 @@ impl ConfigurationEntity {
         &mut self.maybe_contents
     }
 
+    pub fn maybe_contents_reader(&self) -> Option<&ContentsReaderFn> {
+        self.maybe_contents_reader.as_ref()
+    }
+
+    pub fn maybe_contents_reader_mut(&mut self) -> &mut Option<ContentsReaderFn> {
+        &mut self.maybe_contents_reader
+    }
+
     pub fn maybe_parsed_contents(&self) -> Option<&Input> {
         self.maybe_parsed.as_ref()
     }
@@ -147,6 +246,30 @@ impl ConfigurationEntity {
         &mut self.maybe_parsed
     }
 
+    /// Whether contents are parsed (so [crate::Configuration] does not parse them again).
+    pub fn is_parsed(&self) -> bool {
+        self.maybe_parsed.is_some()
+    }
+
+    /// Drops parsed contents (e.g. after changing contents via [Self::maybe_contents_mut]) so they are parsed again.
+    pub fn clear_parsed(&mut self) {
+        self.maybe_parsed = None;
+    }
+
+    /// A stable (FNV-1a) hash of contents which is the same across process runs for identical contents.
+    ///
+    /// Entities without raw contents (e.g. documents that loaders parse themselves, or streamed files after they are
+    /// parsed) are hashed by their parsed contents in sorted key order. It yields [None] if there are neither (e.g.
+    /// streamed files before they are parsed), since such entities can not be compared.
+    pub fn checksum(&self) -> Option<u64> {
+        if let Some(contents) = self.maybe_contents.as_ref() {
+            return Some(fnv1a(FNV_OFFSET_BASIS, contents.as_bytes()));
+        }
+        self.maybe_parsed
+            .as_ref()
+            .map(|input| fnv1a_input(FNV_OFFSET_BASIS, input))
+    }
+
     /// We have to call it after calling [Self::set_contents] or [Self::with_contents] and If no
     /// contents is set, It yields [None] too.
     pub fn guess_format(&self, parser_list: &[Box<dyn Parser>]) -> Option<String> {
@@ -162,19 +285,30 @@ impl ConfigurationEntity {
         }
     }
 
+    /// Returns the explicit format if it is set, otherwise the guessed one (see [Self::guess_format]).
+    pub fn format_or_guess(&self, parser_list: &[Box<dyn Parser>]) -> Option<String> {
+        self.maybe_format()
+            .cloned()
+            .or_else(|| self.guess_format(parser_list))
+    }
+
+    /// Parses contents, or contents of the reader if there are no contents (see [Self::set_contents_reader]).
+    ///
+    /// Errors of opening or reading the reader are yielded as [Error::Parse] too.
     pub fn parse_contents(&self, parser_list: &[Box<dyn Parser>]) -> Result<Input, Error> {
         let contents = if let Some(contents) = self.maybe_contents() {
             contents
+        } else if let Some(contents_reader) = self.maybe_contents_reader() {
+            return self.parse_contents_reader(contents_reader, parser_list);
         } else {
             return Ok(Input::new_map());
         };
-        let format = if let Some(format) = self.maybe_format() {
-            format.clone()
-        } else if let Some(format) = self.guess_format(parser_list) {
+        let format = if let Some(format) = self.format_or_guess(parser_list) {
             format
         } else {
             return Err(Error::ParserNotFound {
                 format: "<unknown>".into(),
+                available_format_list: available_format_list(parser_list),
             });
         };
         if let Some(parser) = parser_list
@@ -183,10 +317,44 @@ impl ConfigurationEntity {
         {
             parser.parse(contents.as_bytes())
         } else {
-            Err(Error::ParserNotFound { format })
+            Err(Error::ParserNotFound {
+                format,
+                available_format_list: available_format_list(parser_list),
+            })
         }
     }
 
+    fn parse_contents_reader(
+        &self,
+        contents_reader: &ContentsReaderFn,
+        parser_list: &[Box<dyn Parser>],
+    ) -> Result<Input, Error> {
+        let format = if let Some(format) = self.maybe_format() {
+            format.clone()
+        } else {
+            return Err(Error::ParserNotFound {
+                format: "<unknown>".into(),
+                available_format_list: available_format_list(parser_list),
+            });
+        };
+        let Some(parser) = parser_list
+            .iter()
+            .find(|parser| parser.supported_format_list().contains(&format))
+        else {
+            return Err(Error::ParserNotFound {
+                format,
+                available_format_list: available_format_list(parser_list),
+            });
+        };
+        let mut reader = contents_reader().map_err(|error| Error::Parse {
+            data: format!("<reader of {}>", self.url),
+            parser: parser.to_string(),
+            supported_format_list: parser.supported_format_list(),
+            source: error.into(),
+        })?;
+        parser.parse_stream(&mut reader)
+    }
+
     pub fn parse_contents_mut(
         &mut self,
         parser_list: &[Box<dyn Parser>],
@@ -198,6 +366,54 @@ impl ConfigurationEntity {
             .as_mut()
             .expect("input has been set!"))
     }
+
+    /// Parses contents even if they are already parsed and replaces parsed contents.
+    ///
+    /// Parsed contents are cleared if parsing fails.
+    pub fn reparse(&mut self, parser_list: &[Box<dyn Parser>]) -> Result<&mut Input, Error> {
+        self.clear_parsed();
+        self.parse_contents_mut(parser_list)
+    }
+
+    /// Merges (already parsed or freshly parsed) contents into `target` like [crate::Configuration] does for each
+    /// configuration of `plugin`.
+    ///
+    /// Positions of values (e.g. in validation errors) start with `plugin` for `target` and with URL of this entity for
+    /// its own values. `parser_list` is only used if contents are not parsed yet.
+    pub fn merge_into<P: AsRef<str>>(
+        &self,
+        target: &mut Input,
+        plugin: P,
+        parser_list: &[Box<dyn Parser>],
+    ) -> Result<(), Error> {
+        let parsed;
+        let input = if let Some(input) = self.maybe_parsed_contents() {
+            input
+        } else {
+            parsed = self.parse_contents(parser_list)?;
+            &parsed
+        };
+        plugx_input::merge::merge_with_positions(
+            target,
+            plugx_input::position::new().new_with_key(plugin.as_ref()),
+            input,
+            plugx_input::position::new().new_with_key(self.url.as_str()),
+        );
+        Ok(())
+    }
+}
+
+fn available_format_list(parser_list: &[Box<dyn Parser>]) -> Vec<String> {
+    let mut format_list = Vec::new();
+    parser_list
+        .iter()
+        .flat_map(|parser| parser.supported_format_list())
+        .for_each(|format| {
+            if !format_list.contains(&format) {
+                format_list.push(format)
+            }
+        });
+    format_list
 }
 
 impl Display for ConfigurationEntity {
@@ -205,3 +421,40 @@ impl Display for ConfigurationEntity {
         f.write_str(format!("Configuration entity for {}", self.plugin_name).as_str())
     }
 }
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Hashes maps in sorted key order and prefixes each value with its kind (and strings with their lengths), so
+/// different structures do not collide.
+fn fnv1a_input(hash: u64, input: &Input) -> u64 {
+    let with_str = |hash: u64, text: &str| {
+        fnv1a(
+            fnv1a(hash, (text.len() as u64).to_le_bytes().as_slice()),
+            text.as_bytes(),
+        )
+    };
+    if input.is_map() {
+        let mut item_list: Vec<_> = input.as_map().iter().collect();
+        item_list.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+        item_list
+            .into_iter()
+            .fold(fnv1a(hash, b"m"), |hash, (key, input)| {
+                fnv1a_input(with_str(hash, key), input)
+            })
+    } else if input.is_list() {
+        input
+            .as_list()
+            .iter()
+            .fold(fnv1a(hash, b"l"), |hash, input| fnv1a_input(hash, input))
+    } else if input.is_str() {
+        with_str(fnv1a(hash, b"s"), input.as_str())
+    } else {
+        with_str(fnv1a(hash, b"v"), input.to_string().as_str())
+    }
+}
@@ -3,7 +3,10 @@
 use url::Url;
 
 /// Main error wrapper.
+///
+/// It's `#[non_exhaustive]`, so new variants can be added in minor releases. Add a wildcard arm when matching it.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Errors from [crate::loader::Error].
     #[error(transparent)]
@@ -11,13 +14,26 @@ pub enum Error {
         #[from]
         source: crate::loader::Error,
     },
+    /// Errors of all URLs that could not be loaded (see [crate::Configuration::set_fail_fast]).
+    #[error(
+        "Could not load {} configuration URL(s): {}",
+        error_list.len(),
+        error_list
+            .iter()
+            .map(|(url, error)| format!("`{url}`: {error}"))
+            .collect::<Vec<_>>()
+            .join("; ")
+    )]
+    MultipleLoad {
+        error_list: Vec<(Url, crate::loader::Error)>,
+    },
     /// Errors from [crate::parser::Error].
     #[error("Error in parsing `{plugin_name}` configuration from `{url}` for `{item}`")]
     Parse {
         plugin_name: String,
         url: Url,
         item: Box<String>,
-        source: crate::parser::Error,
+        source: Box<crate::parser::Error>,
     },
     /// Errors from [plugx_input::schema::InputSchemaError]
     #[error(transparent)]
@@ -25,6 +41,16 @@ pub enum Error {
         #[from]
         source: plugx_input::schema::InputSchemaError,
     },
+    /// Errors from validating against a JSON Schema (`jsonschema` feature).
+    #[cfg(feature = "jsonschema")]
+    #[error("Invalid `{plugin_name}` configuration: {}", error_list.join("; "))]
+    ValidateJsonSchema {
+        plugin_name: String,
+        error_list: Vec<String>,
+    },
+    /// Required keys that are not set (see [crate::Configuration::assert_complete]).
+    #[error("Missing required configuration(s): {}", missing_list.join(", "))]
+    Incomplete { missing_list: Vec<String> },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -43,7 +69,7 @@ impl From<(String, Url, String, crate::parser::Error)> for Error {
             plugin_name,
             url,
             item: Box::new(item),
-            source: parser_error,
+            source: Box::new(parser_error),
         }
     }
 }
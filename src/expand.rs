@@ -0,0 +1,85 @@
+use anyhow::{anyhow, bail};
+use std::env;
+
+/// Expands `$NAME`, `${NAME}`, and `${NAME:-default}` occurrences from environment-variables.
+///
+/// `$$` is an escaped `$` and a `$` which is not followed by a variable name is kept as is.
+pub(crate) fn expand_env_vars<T: AsRef<str>>(text: T) -> anyhow::Result<String> {
+    expand_env_vars_with(text, |_, value| value)
+}
+
+/// Same as [expand_env_vars] but passes the text expanded so far and every value to `map_value` (e.g. to escape it).
+pub(crate) fn expand_env_vars_with<T, F>(text: T, map_value: F) -> anyhow::Result<String>
+where
+    T: AsRef<str>,
+    F: Fn(&str, String) -> String,
+{
+    let text = text.as_ref();
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character != '$' {
+            result.push(character);
+            continue;
+        }
+        match chars.peek() {
+            Some('$') => {
+                chars.next();
+                result.push('$');
+            }
+            Some('{') => {
+                chars.next();
+                let mut expression = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(character) => expression.push(character),
+                        None => bail!("Unclosed `${{` in {text:?}"),
+                    }
+                }
+                let (name, maybe_default) = expression
+                    .split_once(":-")
+                    .map(|(name, default)| (name, Some(default)))
+                    .unwrap_or((expression.as_str(), None));
+                if !is_valid_name(name) {
+                    bail!("Invalid environment-variable name {name:?} in {text:?}");
+                }
+                let value = get_var(name, maybe_default, text)?;
+                result += map_value(result.as_str(), value).as_str();
+            }
+            Some(character) if character.is_ascii_alphabetic() || *character == '_' => {
+                let mut name = String::new();
+                while let Some(character) = chars.peek() {
+                    if character.is_ascii_alphanumeric() || *character == '_' {
+                        name.push(*character);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let value = get_var(name.as_str(), None, text)?;
+                result += map_value(result.as_str(), value).as_str();
+            }
+            _ => result.push('$'),
+        }
+    }
+    Ok(result)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|character: char| character.is_ascii_digit())
+        && name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+fn get_var(name: &str, maybe_default: Option<&str>, text: &str) -> anyhow::Result<String> {
+    match (env::var(name), maybe_default) {
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(default)) => Ok(default.to_string()),
+        (Err(error), None) => Err(anyhow!(
+            "Could not expand environment-variable `{name}` in {text:?} ({error})"
+        )),
+    }
+}
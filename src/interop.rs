@@ -0,0 +1,65 @@
+//! Conversions between [Input] and `Value` types of other serde crates.
+//!
+//! * [input_to_json] and [json_to_input] with `json` feature.
+//! * [input_to_toml] and [toml_to_input] with `toml` feature.
+//! * [input_to_yaml] and [yaml_to_input] with `yaml` feature.
+//!
+//! [Input] has no null, so converting null values (JSON `null` and YAML `~`) to it yields an error. This module needs
+//! `configuration` feature for [crate::Error].
+//!
+//! ### Example
+//! ```rust
+//! # #[cfg(feature = "json")] {
+//! use plugx_config::{interop::{input_to_json, json_to_input}, Input};
+//! use serde_json::json;
+//!
+//! let input = json_to_input(&json!({"server": {"port": 8080, "hosts": ["a", "b"]}})).unwrap();
+//! let server = input.as_map().get("server").unwrap().as_map();
+//! assert_eq!(server.get("port").unwrap(), &Input::from(8080));
+//! assert_eq!(input_to_json(&input).unwrap(), json!({"server": {"port": 8080, "hosts": ["a", "b"]}}));
+//! assert!(json_to_input(&json!({"port": null})).is_err());
+//! # }
+//! ```
+
+use crate::Error;
+use anyhow::anyhow;
+use plugx_input::Input;
+
+/// Converts [Input] to a JSON value.
+#[cfg(feature = "json")]
+pub fn input_to_json(input: &Input) -> Result<serde_json::Value, Error> {
+    serde_json::to_value(input).map_err(|error| Error::Other(anyhow!(error)))
+}
+
+/// Converts a JSON value to [Input]. It returns an error for `null` values.
+#[cfg(feature = "json")]
+pub fn json_to_input(value: &serde_json::Value) -> Result<Input, Error> {
+    serde_json::from_value(value.clone()).map_err(|error| Error::Other(anyhow!(error)))
+}
+
+/// Converts [Input] to a TOML value.
+#[cfg(feature = "toml")]
+pub fn input_to_toml(input: &Input) -> Result<toml::Value, Error> {
+    toml::Value::try_from(input).map_err(|error| Error::Other(anyhow!(error)))
+}
+
+/// Converts a TOML value to [Input].
+#[cfg(feature = "toml")]
+pub fn toml_to_input(value: &toml::Value) -> Result<Input, Error> {
+    value
+        .clone()
+        .try_into()
+        .map_err(|error| Error::Other(anyhow!(error)))
+}
+
+/// Converts [Input] to a YAML value.
+#[cfg(feature = "yaml")]
+pub fn input_to_yaml(input: &Input) -> Result<serde_yaml::Value, Error> {
+    serde_yaml::to_value(input).map_err(|error| Error::Other(anyhow!(error)))
+}
+
+/// Converts a YAML value to [Input]. It returns an error for null (`~`) values.
+#[cfg(feature = "yaml")]
+pub fn yaml_to_input(value: &serde_yaml::Value) -> Result<Input, Error> {
+    serde_yaml::from_value(value.clone()).map_err(|error| Error::Other(anyhow!(error)))
+}
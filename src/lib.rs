@@ -1,18 +1,35 @@
 #![doc = include_str!("../README.md")]
 #![doc(test(no_crate_inject))]
 
+#[cfg(feature = "configuration")]
 pub mod entity;
+#[cfg(feature = "configuration")]
 pub mod error;
+#[cfg(all(
+    feature = "configuration",
+    any(feature = "json", feature = "toml", feature = "yaml")
+))]
+pub mod interop;
+#[cfg(feature = "configuration")]
 pub mod loader;
 pub mod parser;
 
+#[cfg(feature = "configuration")]
 #[doc(inline)]
-pub use configuration::Configuration;
+pub use configuration::{
+    merge, merge_with_precedence, AliasConflictPolicy, BoxedInputPreprocessorFn,
+    BoxedMaterializeFn, Configuration, ConfigurationBuilder, ConfigurationState, LoadPlanItem,
+    ParsedSourceList, PhaseTimings, PluginRegistry, SortedKeys, SourcePrecedence,
+};
+#[cfg(feature = "configuration")]
 #[doc(inline)]
 pub use error::Error;
 #[doc(inline)]
+pub use logging::LogOptions;
+#[doc(inline)]
 /// Final configuration data type
 pub use plugx_input::Input;
+#[cfg(feature = "configuration")]
 #[doc(inline)]
 /// URL type to load configuration from
 pub use url::Url;
@@ -23,8 +40,12 @@ pub mod ext {
     pub extern crate anyhow;
     pub extern crate plugx_input;
     pub extern crate serde;
+    #[cfg(feature = "configuration")]
     pub extern crate url;
 }
 
+#[cfg(feature = "configuration")]
 mod configuration;
+#[cfg(feature = "configuration")]
+mod expand;
 mod logging;
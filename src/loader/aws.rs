@@ -0,0 +1,556 @@
+//! AWS Secrets Manager and SSM Parameter Store configuration loaders (`aws` feature).
+//!
+//! Both loaders use the default AWS credential chain (environment-variables, shared config files, IMDS, etc.)
+//! and accept `region` and `soft-errors` (`not-found` and `no-access`) options.
+//! Outside an async context they run requests on one shared single-threaded Tokio runtime. Inside a multi-threaded
+//! Tokio runtime they block in place on it, and inside a current-thread runtime they return a [Error::Load] (use
+//! `tokio::task::spawn_blocking` for that).
+//!
+//! ### Secrets Manager
+//! * Supported schema: `secretsmanager`
+//! * The secret ID is the URL host and path (e.g. `secretsmanager://prod/my-app/foo`) or the `secret-id` option
+//!   (e.g. for ARNs).
+//! * Contents of the secret string is the configuration of one plugin. Plugin name is the `plugin` option
+//!   (defaults to last part of the secret ID) and the format is the `format` option (defaults to `json`).
+//!
+//! ### SSM Parameter Store
+//! * Supported schema: `ssm`
+//! * The URL path is used as the parameter path and all parameters under it are read recursively (with
+//!   decryption). For example with `ssm:///my-app/prod` the parameter `/my-app/prod/foo/server/port` is the
+//!   `server.port` key of plugin `foo`.
+//! * Values are parsed as JSON if possible (just like environment-variables), otherwise they are strings.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use plugx_config::{Configuration, Url};
+//!
+//! let configuration = Configuration::new()
+//!     .with_url("ssm:///my-app/prod?region=eu-west-1".parse().unwrap())
+//!     .unwrap()
+//!     .with_url("secretsmanager://prod/my-app/database?plugin=db&soft-errors=not-found".parse().unwrap())
+//!     .unwrap();
+//! let merged = configuration.load_parse_merge(true).unwrap();
+//! ```
+//!
+//! See [loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{self, Error, Loader, SoftErrors},
+};
+use anyhow::anyhow;
+use aws_sdk_ssm::error::ProvideErrorMetadata;
+use cfg_if::cfg_if;
+use plugx_input::Input;
+use serde::Deserialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::{future::Future, sync::OnceLock};
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use url::Url;
+
+pub const SECRETS_MANAGER_NAME: &str = "AWS-Secrets-Manager";
+pub const SECRETS_MANAGER_SCHEME_LIST: &[&str] = &["secretsmanager"];
+pub const SSM_NAME: &str = "AWS-SSM-Parameter-Store";
+pub const SSM_SCHEME_LIST: &[&str] = &["ssm"];
+
+/// Loads a plugin configuration from an AWS Secrets Manager secret.
+#[derive(Debug, Default, Clone)]
+pub struct SecretsManager {
+    options: SecretsManagerOptions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SecretsManagerOptions {
+    secret_id: Option<String>,
+    plugin: Option<String>,
+    format: Option<String>,
+    region: Option<String>,
+    soft_errors: SoftErrors<SoftErrorsAws>,
+}
+
+/// Loads configurations from AWS SSM Parameter Store.
+#[derive(Debug, Default, Clone)]
+pub struct Ssm {
+    options: SsmOptions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SsmOptions {
+    region: Option<String>,
+    soft_errors: SoftErrors<SoftErrorsAws>,
+}
+
+/// Supported soft errors when loading from AWS.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoftErrorsAws {
+    NotFound,
+    NoAccess,
+}
+
+impl SecretsManager {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn set_region<R: AsRef<str>>(&mut self, region: R) {
+        self.options.region = Some(region.as_ref().to_string());
+    }
+
+    pub fn with_region<R: AsRef<str>>(mut self, region: R) -> Self {
+        self.set_region(region);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsAws) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsAws) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<SecretsManagerOptions, Error> {
+        loader::deserialize_query_string::<SecretsManagerOptions>(SECRETS_MANAGER_NAME, url).map(
+            |mut options| {
+                if self.options.region.is_some() {
+                    options.region = self.options.region.clone();
+                }
+                options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+                options
+            },
+        )
+    }
+}
+
+impl Ssm {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn set_region<R: AsRef<str>>(&mut self, region: R) {
+        self.options.region = Some(region.as_ref().to_string());
+    }
+
+    pub fn with_region<R: AsRef<str>>(mut self, region: R) -> Self {
+        self.set_region(region);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsAws) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsAws) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<SsmOptions, Error> {
+        loader::deserialize_query_string::<SsmOptions>(SSM_NAME, url).map(|mut options| {
+            if self.options.region.is_some() {
+                options.region = self.options.region.clone();
+            }
+            options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+            options
+        })
+    }
+}
+
+impl Display for SecretsManager {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(SECRETS_MANAGER_NAME)
+    }
+}
+
+impl Display for Ssm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(SSM_NAME)
+    }
+}
+
+impl Loader for SecretsManager {
+    /// In this case `["secretsmanager"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SECRETS_MANAGER_SCHEME_LIST
+            .iter()
+            .cloned()
+            .map(String::from)
+            .collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        let secret_id = options.secret_id.clone().unwrap_or_else(|| {
+            url.host_str().unwrap_or_default().to_string() + url.path().trim_end_matches('/')
+        });
+        let secret_id = secret_id.trim_start_matches('/').to_string();
+        let plugin_name = options
+            .plugin
+            .clone()
+            .unwrap_or_else(|| {
+                secret_id
+                    .rsplit(['/', ':'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .to_lowercase();
+        if secret_id.is_empty() || plugin_name.is_empty() {
+            return Err(Error::InvalidUrl {
+                loader: SECRETS_MANAGER_NAME.to_string(),
+                url: url.to_string(),
+                source: anyhow!("Could not detect secret ID and plugin name"),
+            });
+        }
+        if !maybe_whitelist
+            .map(|whitelist| whitelist.contains(&plugin_name))
+            .unwrap_or(true)
+        {
+            return Ok(Vec::new());
+        }
+        let result = block_on(SECRETS_MANAGER_NAME, url, async {
+            let client = aws_sdk_secretsmanager::Client::new(
+                &load_sdk_config(options.region.as_deref()).await,
+            );
+            client.get_secret_value().secret_id(&secret_id).send().await
+        })?;
+        let output = match result {
+            Ok(output) => output,
+            Err(error) => {
+                return handle_sdk_error(
+                    SECRETS_MANAGER_NAME,
+                    url,
+                    format!("secret `{secret_id}`"),
+                    error,
+                    &options.soft_errors,
+                    skip_soft_errors,
+                )
+                .map(|_| Vec::new())
+            }
+        };
+        let contents = output.secret_string().ok_or_else(|| Error::Load {
+            loader: SECRETS_MANAGER_NAME.to_string(),
+            url: url.clone(),
+            description: format!("read string value of secret `{secret_id}` from").into(),
+            source: anyhow!("Secret has no string value"),
+        })?;
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::trace!(
+                    url=%url,
+                    secret_id=secret_id,
+                    plugin=plugin_name,
+                    "Read secret"
+                );
+            } else if #[cfg(feature = "logging")] {
+                log::trace!(
+                    "msg=\"Read secret\" url={:?} secret_id={secret_id:?} plugin={plugin_name:?}",
+                    url.to_string()
+                );
+            }
+        }
+        let entity =
+            ConfigurationEntity::new(&secret_id, url.clone(), &plugin_name, SECRETS_MANAGER_NAME)
+                .with_format(options.format.unwrap_or("json".into()).to_lowercase())
+                .with_contents(contents);
+        Ok([(plugin_name, entity)].into())
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl Loader for Ssm {
+    /// In this case `["ssm"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SSM_SCHEME_LIST.iter().cloned().map(String::from).collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        let mut path = url
+            .host_str()
+            .map(|host| format!("/{host}"))
+            .unwrap_or_default()
+            + url.path().trim_end_matches('/');
+        if path.is_empty() {
+            path = "/".into();
+        }
+        let result = block_on(SSM_NAME, url, async {
+            let client =
+                aws_sdk_ssm::Client::new(&load_sdk_config(options.region.as_deref()).await);
+            let mut stream = client
+                .get_parameters_by_path()
+                .path(&path)
+                .recursive(true)
+                .with_decryption(true)
+                .into_paginator()
+                .send();
+            let mut parameter_list = Vec::new();
+            while let Some(page) = stream.next().await {
+                match page {
+                    Ok(page) => page.parameters().iter().for_each(|parameter| {
+                        if let (Some(name), Some(value)) = (parameter.name(), parameter.value()) {
+                            parameter_list.push((name.to_string(), value.to_string()))
+                        }
+                    }),
+                    Err(error) => return Err(error),
+                }
+            }
+            Ok(parameter_list)
+        })?;
+        let parameter_list = match result {
+            Ok(parameter_list) => parameter_list,
+            Err(error) => {
+                return handle_sdk_error(
+                    SSM_NAME,
+                    url,
+                    format!("parameter path `{path}`"),
+                    error,
+                    &options.soft_errors,
+                    skip_soft_errors,
+                )
+                .map(|_| Vec::new())
+            }
+        };
+        if parameter_list.is_empty() {
+            return handle_not_found(
+                SSM_NAME,
+                url,
+                format!("parameter path `{path}`"),
+                &options.soft_errors,
+                skip_soft_errors,
+            )
+            .map(|_| Vec::new());
+        }
+        let mut result: Vec<(String, Input)> = Vec::new();
+        for (name, value) in parameter_list {
+            let mut key_list: Vec<String> = name
+                .strip_prefix(path.as_str())
+                .unwrap_or(name.as_str())
+                .split('/')
+                .filter(|key| !key.is_empty())
+                .map(|key| key.to_lowercase())
+                .collect();
+            if key_list.len() < 2 {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(url=%url, parameter=name, "Could not detect plugin name and key of parameter");
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!("msg=\"Could not detect plugin name and key of parameter\" url={:?} parameter={name:?}", url.to_string());
+                    }
+                }
+                continue;
+            }
+            let plugin_name = key_list.remove(0);
+            if !maybe_whitelist
+                .map(|whitelist| whitelist.contains(&plugin_name))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::trace!(plugin=plugin_name, parameter=name, "Detected parameter");
+                } else if #[cfg(feature = "logging")] {
+                    log::trace!("msg=\"Detected parameter\" plugin={plugin_name:?} parameter={name:?}");
+                }
+            }
+            let value = serde_json::from_str::<Input>(value.as_str()).unwrap_or(value.into());
+            let input = if let Some((_, input)) = result
+                .iter_mut()
+                .find(|(loaded_plugin_name, _)| loaded_plugin_name == &plugin_name)
+            {
+                input
+            } else {
+                result.push((plugin_name, Input::new_map()));
+                &mut result.last_mut().unwrap().1
+            };
+            insert_key_list(input, key_list.as_slice(), value).map_err(|source| Error::Load {
+                loader: SSM_NAME.to_string(),
+                url: url.clone(),
+                description: format!("set parameter `{name}` from").into(),
+                source,
+            })?;
+        }
+        Ok(result
+            .into_iter()
+            .map(|(plugin_name, input)| {
+                let entity = ConfigurationEntity::new(
+                    format!("{}/{plugin_name}", path.trim_end_matches('/')),
+                    url.clone(),
+                    &plugin_name,
+                    SSM_NAME,
+                )
+                .with_format("json")
+                .with_contents(serde_json::to_string(&input).unwrap_or_default())
+                .with_parsed_contents(input);
+                (plugin_name, entity)
+            })
+            .collect())
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+async fn load_sdk_config(maybe_region: Option<&str>) -> aws_config::SdkConfig {
+    let mut config_loader = aws_config::from_env();
+    if let Some(region) = maybe_region {
+        config_loader = config_loader.region(aws_config::Region::new(region.to_string()));
+    }
+    config_loader.load().await
+}
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fn runtime() -> std::io::Result<&'static Runtime> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+fn block_on<F: Future>(loader: &str, url: &Url, future: F) -> Result<F::Output, Error> {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+        }
+        Ok(_) => Err(Error::Load {
+            loader: loader.to_string(),
+            url: url.clone(),
+            description: "block current-thread async runtime to load"
+                .to_string()
+                .into(),
+            source: anyhow!(
+                "Use `tokio::task::spawn_blocking` to load inside a current-thread runtime"
+            ),
+        }),
+        Err(_) => runtime()
+            .map(|runtime| runtime.block_on(future))
+            .map_err(|error| Error::Load {
+                loader: loader.to_string(),
+                url: url.clone(),
+                description: "create async runtime to load".to_string().into(),
+                source: error.into(),
+            }),
+    }
+}
+
+fn handle_sdk_error<E>(
+    loader: &str,
+    url: &Url,
+    item: String,
+    error: E,
+    soft_errors: &SoftErrors<SoftErrorsAws>,
+    skip_soft_errors: bool,
+) -> Result<(), Error>
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    match error.code() {
+        Some("ResourceNotFoundException" | "ParameterNotFound") => {
+            handle_not_found(loader, url, item, soft_errors, skip_soft_errors)
+        }
+        Some("AccessDeniedException" | "AccessDenied") => {
+            if skip_soft_errors
+                && (soft_errors.skip_all() || soft_errors.contains(&SoftErrorsAws::NoAccess))
+            {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::info!(url=%url, item=item, skip_error=true, "Access denied");
+                    } else if #[cfg(feature = "logging")] {
+                        log::info!("msg=\"Access denied\" url={:?} item={item:?} skip_error=true", url.to_string());
+                    }
+                }
+                Ok(())
+            } else {
+                Err(Error::NoAccess {
+                    loader: loader.to_string(),
+                    url: url.clone(),
+                })
+            }
+        }
+        _ => Err(Error::Load {
+            loader: loader.to_string(),
+            url: url.clone(),
+            description: format!("load {item} from").into(),
+            source: anyhow!(aws_sdk_ssm::error::DisplayErrorContext(error)),
+        }),
+    }
+}
+
+fn handle_not_found(
+    loader: &str,
+    url: &Url,
+    item: String,
+    soft_errors: &SoftErrors<SoftErrorsAws>,
+    skip_soft_errors: bool,
+) -> Result<(), Error> {
+    if skip_soft_errors
+        && (soft_errors.skip_all() || soft_errors.contains(&SoftErrorsAws::NotFound))
+    {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::info!(url=%url, item=item, skip_error=true, "Could not find item");
+            } else if #[cfg(feature = "logging")] {
+                log::info!("msg=\"Could not find item\" url={:?} item={item:?} skip_error=true", url.to_string());
+            }
+        }
+        Ok(())
+    } else {
+        Err(Error::NotFound {
+            loader: loader.to_string(),
+            url: url.clone(),
+            item: item.into(),
+        })
+    }
+}
+
+fn insert_key_list(input: &mut Input, key_list: &[String], value: Input) -> anyhow::Result<()> {
+    let (key, key_list) = key_list.split_first().expect("Non-empty key list");
+    if key_list.is_empty() {
+        input.map_mut().insert(key.clone(), value);
+        return Ok(());
+    }
+    if !input.as_map().contains_key(key) {
+        input.map_mut().insert(key.clone(), Input::new_map());
+    }
+    let inner_input = input.map_mut().get_mut(key).unwrap();
+    if inner_input.is_map() {
+        insert_key_list(inner_input, key_list, value)
+    } else {
+        Err(anyhow!(
+            "`{key}` already exists with type {}, but we needed {}",
+            inner_input.type_name(),
+            Input::map_type_name()
+        ))
+    }
+}
@@ -0,0 +1,432 @@
+//! A loader wrapper that caches results of another loader on disk (`cache` feature).
+//!
+//! Results are stored as JSON files inside a cache directory (one file per URL and whitelist). On each load:
+//!
+//! * If the cached copy is younger than the TTL (see [DiskCachedLoader::set_ttl]), it's returned without calling the
+//!   inner loader.
+//! * Otherwise the inner loader is called and on success the cache is updated (failing to write the cache is only
+//!   logged).
+//! * If the inner loader fails with [Error::Load] or [Error::Timeout] and `stale_on_error` is `true` (which is the
+//!   default), the cached copy (regardless of its age) is returned with a warning log. Otherwise, or if there is no
+//!   cached copy, the error is returned.
+//!
+//! Cache files contain configurations, so on Unix the cache directory is created with mode `0700` and cache files
+//! with mode `0600`. Usernames and passwords of URLs are not stored in cache files (URLs of cached entities do not
+//! have them either).
+//!
+//! ### Example
+//! ```rust
+//! use plugx_config::{
+//!     entity::ConfigurationEntity,
+//!     loader::{Loader, Error, closure::Closure, cached::DiskCachedLoader},
+//!     ext::url::Url,
+//! };
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use tempdir::TempDir;
+//!
+//! static AVAILABLE: AtomicBool = AtomicBool::new(true);
+//! let loader_fn = |url: &Url, _: Option<&[String]>, _: bool| {
+//!     if !AVAILABLE.load(Ordering::SeqCst) {
+//!         return Err(Error::Load {
+//!             loader: "remote".to_string(),
+//!             url: url.clone(),
+//!             description: "connect to backend".to_string().into(),
+//!             source: anyhow::anyhow!("Backend is down"),
+//!         });
+//!     }
+//!     let entity = ConfigurationEntity::new("foo", url.clone(), "foo", "remote")
+//!         .with_format("yml")
+//!         .with_contents("hello: world");
+//!     Ok([("foo".to_string(), entity)].to_vec())
+//! };
+//! let cache_dir = TempDir::new("cached-example").unwrap();
+//! let loader = DiskCachedLoader::new(Closure::new("remote", Box::new(loader_fn), "remote"), cache_dir.path());
+//!
+//! let url = "remote://".parse().unwrap();
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! AVAILABLE.store(false, Ordering::SeqCst);
+//! // Falls back to the cached copy:
+//! assert_eq!(loader.load(&url, None, false).unwrap(), loaded);
+//! // Or not:
+//! assert!(loader.with_stale_on_error(false).load(&url, None, false).is_err());
+//! ```
+//!
+//! See [crate::loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{without_userinfo, Error, Loader},
+    LogOptions,
+};
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use plugx_input::Input;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display, Formatter},
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use url::Url;
+
+/// Caches results of the inner loader on disk.
+#[derive(Debug, Clone)]
+pub struct DiskCachedLoader<L: Loader> {
+    loader: L,
+    cache_dir: PathBuf,
+    maybe_ttl: Option<Duration>,
+    stale_on_error: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedLoad {
+    url: String,
+    maybe_whitelist: Option<Vec<String>>,
+    entity_list: Vec<CachedEntity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedEntity {
+    item: String,
+    loader_name: String,
+    url: String,
+    plugin_name: String,
+    maybe_format: Option<String>,
+    maybe_contents: Option<String>,
+    maybe_parsed: Option<Input>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl<L: Loader> Display for DiskCachedLoader<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.loader.name().as_str())
+    }
+}
+
+impl<L: Loader> DiskCachedLoader<L> {
+    pub fn new<P: AsRef<Path>>(loader: L, cache_dir: P) -> Self {
+        Self {
+            loader,
+            cache_dir: cache_dir.as_ref().to_path_buf(),
+            maybe_ttl: None,
+            stale_on_error: true,
+        }
+    }
+
+    /// Cached copies younger than `ttl` are returned without calling the inner loader.
+    ///
+    /// By default there is no TTL and the inner loader is called on each load.
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.maybe_ttl = Some(ttl)
+    }
+
+    /// Cached copies younger than `ttl` are returned without calling the inner loader.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.set_ttl(ttl);
+        self
+    }
+
+    /// Whether to return the cached copy (regardless of its age) if the inner loader fails. Defaults to `true`.
+    pub fn set_stale_on_error(&mut self, stale_on_error: bool) {
+        self.stale_on_error = stale_on_error
+    }
+
+    /// Whether to return the cached copy (regardless of its age) if the inner loader fails. Defaults to `true`.
+    pub fn with_stale_on_error(mut self, stale_on_error: bool) -> Self {
+        self.set_stale_on_error(stale_on_error);
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        self.cache_dir.as_path()
+    }
+
+    pub fn maybe_ttl(&self) -> Option<Duration> {
+        self.maybe_ttl
+    }
+
+    pub fn stale_on_error(&self) -> bool {
+        self.stale_on_error
+    }
+
+    pub fn loader(&self) -> &L {
+        &self.loader
+    }
+
+    pub fn loader_mut(&mut self) -> &mut L {
+        &mut self.loader
+    }
+
+    pub fn into_loader(self) -> L {
+        self.loader
+    }
+
+    /// Returns path of the cache file of `url` and `maybe_whitelist`.
+    pub fn cache_path(&self, url: &Url, maybe_whitelist: Option<&[String]>) -> PathBuf {
+        // FNV-1a, since hashes of `std` are not guaranteed to be stable between releases:
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut key = url.to_string();
+        if let Some(whitelist) = maybe_whitelist {
+            let mut whitelist = whitelist.to_vec();
+            whitelist.sort();
+            key.push('\n');
+            key.push_str(whitelist.join(",").as_str());
+        }
+        key.bytes().for_each(|byte| {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        });
+        self.cache_dir.join(format!("{hash:016x}.json"))
+    }
+
+    /// Removes the cached copy of `url` and `maybe_whitelist` and returns whether it existed.
+    pub fn invalidate(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+    ) -> Result<bool, io::Error> {
+        match fs::remove_file(self.cache_path(url, maybe_whitelist)) {
+            Ok(_) => Ok(true),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn read_cache(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+    ) -> Option<(Vec<(String, ConfigurationEntity)>, Duration)> {
+        let path = self.cache_path(url, maybe_whitelist);
+        let age = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?
+            .elapsed()
+            .unwrap_or_default();
+        let result = fs::read(&path)
+            .map_err(|error| anyhow!(error))
+            .and_then(|bytes| {
+                serde_json::from_slice::<CachedLoad>(bytes.as_slice())
+                    .map_err(|error| anyhow!(error))
+            })
+            .and_then(|cached| {
+                if cached.url != without_userinfo(url).as_str() {
+                    return Err(anyhow!("Cache file belongs to URL `{}`", cached.url));
+                }
+                cached
+                    .entity_list
+                    .into_iter()
+                    .map(|cached_entity| {
+                        let mut entity = ConfigurationEntity::new(
+                            cached_entity.item,
+                            Url::parse(cached_entity.url.as_str())?,
+                            cached_entity.plugin_name.clone(),
+                            cached_entity.loader_name,
+                        );
+                        *entity.maybe_format_mut() = cached_entity.maybe_format;
+                        *entity.maybe_contents_mut() = cached_entity.maybe_contents;
+                        *entity.maybe_parsed_contents_mut() = cached_entity.maybe_parsed;
+                        *entity.metadata_mut() = cached_entity.metadata;
+                        Ok((cached_entity.plugin_name, entity))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+            });
+        match result {
+            Ok(loaded) => Some((loaded, age)),
+            Err(_error) => {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(url=%url, path=?path, error=%_error, "Could not read cache file");
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!(
+                            "msg=\"Could not read cache file\" url={:?} path={path:?} error={:?}",
+                            url.to_string(),
+                            _error.to_string()
+                        );
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    fn write_cache(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        loaded: &[(String, ConfigurationEntity)],
+    ) -> anyhow::Result<()> {
+        let cached = CachedLoad {
+            url: without_userinfo(url).to_string(),
+            maybe_whitelist: maybe_whitelist.map(|whitelist| whitelist.to_vec()),
+            entity_list: loaded
+                .iter()
+                .map(|(_, entity)| {
+                    // Contents that are not read into memory are cached as ordinary contents:
+                    let maybe_contents =
+                        match (entity.maybe_contents(), entity.maybe_contents_reader()) {
+                            (None, Some(contents_reader)) => {
+                                let mut contents = String::new();
+                                contents_reader()?.read_to_string(&mut contents)?;
+                                Some(contents)
+                            }
+                            (maybe_contents, _) => maybe_contents.cloned(),
+                        };
+                    Ok(CachedEntity {
+                        item: entity.item().clone(),
+                        loader_name: entity.loader_name().clone(),
+                        url: without_userinfo(entity.url()).to_string(),
+                        plugin_name: entity.plugin_name().clone(),
+                        maybe_format: entity.maybe_format().cloned(),
+                        maybe_contents,
+                        maybe_parsed: entity.maybe_parsed_contents().cloned(),
+                        metadata: entity.metadata().clone(),
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+        };
+        let mut dir_builder = fs::DirBuilder::new();
+        dir_builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut dir_builder, 0o700);
+        dir_builder.create(&self.cache_dir)?;
+        let path = self.cache_path(url, maybe_whitelist);
+        // Write to a temporary file first, so readers never see a partially written cache file:
+        let tmp_path = path.with_extension(format!(
+            "{}.tmp",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        let mut open_options = fs::OpenOptions::new();
+        open_options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut open_options, 0o600);
+        open_options
+            .open(&tmp_path)?
+            .write_all(serde_json::to_vec(&cached)?.as_slice())?;
+        fs::rename(&tmp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })?;
+        Ok(())
+    }
+}
+
+impl<L: Loader> Loader for DiskCachedLoader<L> {
+    fn name(&self) -> String {
+        self.loader.name()
+    }
+
+    fn scheme_list(&self) -> Vec<String> {
+        self.loader.scheme_list()
+    }
+
+    fn matches_scheme(&self, scheme: &str) -> bool {
+        self.loader.matches_scheme(scheme)
+    }
+
+    fn supports_whitelist(&self) -> bool {
+        self.loader.supports_whitelist()
+    }
+
+    fn supports_soft_errors(&self) -> bool {
+        self.loader.supports_soft_errors()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.loader.validate_url(url)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.loader.set_log_options(log_options)
+    }
+
+    /// Wraps a clone of the inner loader (sharing the cache directory) if it can be cloned.
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        self.loader.boxed_clone().map(|loader| {
+            Box::new(DiskCachedLoader {
+                loader,
+                cache_dir: self.cache_dir.clone(),
+                maybe_ttl: self.maybe_ttl,
+                stale_on_error: self.stale_on_error,
+            }) as Box<dyn Loader>
+        })
+    }
+
+    /// Plans of the inner loader, since cached copies have the same configurations.
+    fn plan(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        self.loader.plan(url, maybe_whitelist, skip_soft_errors)
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let mut maybe_cached = None;
+        if let Some(ttl) = self.maybe_ttl {
+            if let Some((loaded, age)) = self.read_cache(url, maybe_whitelist) {
+                if age < ttl {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::debug!(url=%url, age=?age, "Loaded from cache");
+                        } else if #[cfg(feature = "logging")] {
+                            log::debug!("msg=\"Loaded from cache\" url={:?} age={age:?}", url.to_string());
+                        }
+                    }
+                    return Ok(loaded);
+                }
+                maybe_cached = Some((loaded, age));
+            }
+        }
+        match self.loader.load(url, maybe_whitelist, skip_soft_errors) {
+            Ok(loaded) => {
+                if let Err(_error) = self.write_cache(url, maybe_whitelist, loaded.as_slice()) {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::warn!(url=%url, error=%_error, "Could not write cache file");
+                        } else if #[cfg(feature = "logging")] {
+                            log::warn!(
+                                "msg=\"Could not write cache file\" url={:?} error={:?}",
+                                url.to_string(),
+                                _error.to_string()
+                            );
+                        }
+                    }
+                }
+                Ok(loaded)
+            }
+            // Only failures of reaching the source fall back to the cached copy (e.g. not invalid URLs):
+            Err(error @ (Error::Load { .. } | Error::Timeout { .. })) if self.stale_on_error => {
+                let Some((loaded, _age)) =
+                    maybe_cached.or_else(|| self.read_cache(url, maybe_whitelist))
+                else {
+                    return Err(error);
+                };
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(url=%url, age=?_age, error=%error, "Could not load, used stale cache");
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!(
+                            "msg=\"Could not load, used stale cache\" url={:?} age={_age:?} error={:?}",
+                            url.to_string(),
+                            error.to_string()
+                        );
+                    }
+                }
+                Ok(loaded)
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
@@ -41,7 +41,7 @@ use crate::{
 use std::fmt::{Debug, Display, Formatter};
 use url::Url;
 
-/// A `|&Url, Option<&[String]>, bool| -> Result<Vec<String, ConfigurationEntity>, ConfigurationLoadError>` [Fn]
+/// A `|&Url, Option<&[String]>, bool| -> Result<Vec<String, ConfigurationEntity>, Error>` [Fn]
 pub type BoxedLoaderFn = Box<
     dyn Fn(&Url, Option<&[String]>, bool) -> Result<Vec<(String, ConfigurationEntity)>, Error>
         + Send
@@ -53,23 +53,36 @@ pub struct Closure {
     name: String,
     loader: BoxedLoaderFn,
     scheme_list: Vec<String>,
+    supports_whitelist: bool,
+    supports_soft_errors: bool,
 }
 
 impl Debug for Closure {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ConfigurationLoaderFn")
+        f.debug_struct("Closure")
             .field("name", &self.name)
             .field("scheme_list", &self.scheme_list)
+            .field("supports_whitelist", &self.supports_whitelist)
+            .field("supports_soft_errors", &self.supports_soft_errors)
             .finish()
     }
 }
 
+/// Former name of [Closure].
+#[deprecated(
+    since = "0.3.1",
+    note = "Use `plugx_config::loader::closure::Closure` instead"
+)]
+pub type ConfigurationLoaderFn = Closure;
+
 impl Closure {
     pub fn new<S: AsRef<str>, N: AsRef<str>>(name: N, loader: BoxedLoaderFn, scheme: S) -> Self {
         Self {
             name: name.as_ref().to_string(),
             loader,
             scheme_list: [scheme.as_ref().into()].into(),
+            supports_whitelist: true,
+            supports_soft_errors: true,
         }
     }
 
@@ -102,6 +115,26 @@ impl Closure {
         self.set_scheme_list(scheme_list);
         self
     }
+
+    /// Set it to `false` if the [Fn] ignores the whitelist, so other plugins are dropped after loading.
+    pub fn set_supports_whitelist(&mut self, flag: bool) {
+        self.supports_whitelist = flag
+    }
+
+    pub fn with_supports_whitelist(mut self, flag: bool) -> Self {
+        self.set_supports_whitelist(flag);
+        self
+    }
+
+    /// Set it to `false` if the [Fn] ignores `skip_soft_errors`.
+    pub fn set_supports_soft_errors(&mut self, flag: bool) {
+        self.supports_soft_errors = flag
+    }
+
+    pub fn with_supports_soft_errors(mut self, flag: bool) -> Self {
+        self.set_supports_soft_errors(flag);
+        self
+    }
 }
 
 impl Display for Closure {
@@ -115,6 +148,14 @@ impl Loader for Closure {
         self.scheme_list.clone()
     }
 
+    fn supports_whitelist(&self) -> bool {
+        self.supports_whitelist
+    }
+
+    fn supports_soft_errors(&self) -> bool {
+        self.supports_soft_errors
+    }
+
     fn load(
         &self,
         url: &Url,
@@ -0,0 +1,303 @@
+//! Configuration loader for directories embedded in the binary with [include_dir](https://docs.rs/include_dir)
+//! (`embedded` feature).
+//!
+//! * Supported schema: `embedded`
+//! * It works like the `fs` loader over the embedded directory: the URL path is a file or directory inside it
+//!   (e.g. `embedded:///` for the whole directory or `embedded:///defaults/foo.yml`), plugin name is the file stem,
+//!   and the format is the file extension.
+//! * Options: `recursive` (also loads files of sub-directories) and `soft-errors`.
+//!
+//! ### Example
+//! ```rust
+//! use include_dir::{include_dir, Dir};
+//! use plugx_config::loader::{Loader, embedded::Embedded};
+//! use url::Url;
+//!
+//! static CONFIG_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/tests/etc");
+//!
+//! let loader = Embedded::new(&CONFIG_DIR);
+//! let url: Url = "embedded:///".parse().unwrap();
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! assert_eq!(loaded.len(), 4);
+//! let (_, bar) = loaded.iter().find(|(plugin_name, _)| plugin_name == "bar").expect("`bar` plugin config");
+//! assert_eq!(bar.maybe_format(), Some(&"json".to_string()));
+//!
+//! // Only load `foo`:
+//! let whitelist = ["foo".into()].to_vec();
+//! let loaded = loader.load(&url, Some(&whitelist), false).unwrap();
+//! assert_eq!(loaded.len(), 1);
+//!
+//! // Load just one file:
+//! let url: Url = "embedded:///baz.toml".parse().unwrap();
+//! assert_eq!(loader.load(&url, None, false).unwrap().len(), 1);
+//! ```
+//!
+//! See [loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{self, Error, Loader, SoftErrors},
+    LogOptions,
+};
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use include_dir::{Dir, DirEntry, File};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use url::Url;
+
+pub const NAME: &str = "Embedded";
+pub const SCHEME_LIST: &[&str] = &["embedded"];
+
+/// Loads configurations from an embedded directory.
+#[derive(Debug, Clone)]
+pub struct Embedded {
+    dir: &'static Dir<'static>,
+    options: EmbeddedOptions,
+    log_options: LogOptions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct EmbeddedOptions {
+    recursive: Option<bool>,
+    soft_errors: SoftErrors<SoftErrorsEmbedded>,
+}
+
+/// Supported soft errors when loading embedded contents.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoftErrorsEmbedded {
+    NotFound,
+}
+
+impl Embedded {
+    pub fn new(dir: &'static Dir<'static>) -> Self {
+        Self {
+            dir,
+            options: Default::default(),
+            log_options: Default::default(),
+        }
+    }
+
+    pub fn dir(&self) -> &'static Dir<'static> {
+        self.dir
+    }
+
+    /// Also loads files of sub-directories.
+    pub fn set_recursive(&mut self, recursive: bool) {
+        self.options.recursive = Some(recursive);
+    }
+
+    /// Also loads files of sub-directories.
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.set_recursive(recursive);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsEmbedded) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsEmbedded) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<EmbeddedOptions, Error> {
+        loader::deserialize_query_string::<EmbeddedOptions>(NAME, url).map(|mut options| {
+            if self.options.recursive.is_some() {
+                options.recursive = self.options.recursive;
+            }
+            options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+            options
+        })
+    }
+
+    fn get_plugin_name_and_format(file: &File) -> Option<(String, String)> {
+        let path = file.path();
+        let plugin_name = path.file_stem()?.to_str()?.to_lowercase();
+        let format = path.extension()?.to_str()?.to_lowercase();
+        if plugin_name.is_empty() || format.is_empty() {
+            None
+        } else {
+            Some((plugin_name, format))
+        }
+    }
+
+    fn collect_file_list(
+        dir: &'static Dir<'static>,
+        recursive: bool,
+    ) -> Vec<&'static File<'static>> {
+        let mut file_list: Vec<_> = dir.files().collect();
+        if recursive {
+            dir.dirs()
+                .for_each(|dir| file_list.append(&mut Self::collect_file_list(dir, recursive)));
+        }
+        file_list
+    }
+
+    fn file_to_entity(
+        &self,
+        url: &Url,
+        file: &File,
+        plugin_name: String,
+        format: String,
+    ) -> Result<ConfigurationEntity, Error> {
+        let contents = file.contents_utf8().ok_or_else(|| Error::Load {
+            loader: NAME.to_string(),
+            url: url.clone(),
+            description: format!("decode contents of `{}` from", file.path().display()).into(),
+            source: anyhow!("Contents is not UTF-8"),
+        })?;
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::trace!(
+                    url=%url,
+                    path=?file.path(),
+                    contents=self.log_options.contents(contents),
+                    "Read embedded configuration file"
+                );
+            } else if #[cfg(feature = "logging")] {
+                log::trace!(
+                    "msg=\"Read embedded configuration file\" url={:?} path={:?} contents={:?}",
+                    url.to_string(),
+                    file.path(),
+                    self.log_options.contents(contents)
+                );
+            }
+        }
+        Ok(ConfigurationEntity::new(
+            file.path().to_string_lossy(),
+            url.clone(),
+            plugin_name,
+            NAME,
+        )
+        .with_format(format)
+        .with_contents(contents))
+    }
+}
+
+impl Display for Embedded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(NAME)
+    }
+}
+
+impl Loader for Embedded {
+    /// In this case `["embedded"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SCHEME_LIST.iter().cloned().map(String::from).collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        let is_whitelisted = |plugin_name: &String| {
+            maybe_whitelist
+                .map(|whitelist| whitelist.contains(plugin_name))
+                .unwrap_or(true)
+        };
+        let path = url.path().trim_matches('/');
+        let file_list = if path.is_empty() {
+            Self::collect_file_list(self.dir, options.recursive.unwrap_or(false))
+        } else {
+            match self.dir.get_entry(path) {
+                Some(DirEntry::Dir(dir)) => {
+                    Self::collect_file_list(dir, options.recursive.unwrap_or(false))
+                }
+                Some(DirEntry::File(file)) => {
+                    return if let Some((plugin_name, format)) =
+                        Self::get_plugin_name_and_format(file)
+                    {
+                        if is_whitelisted(&plugin_name) {
+                            self.file_to_entity(url, file, plugin_name.clone(), format)
+                                .map(|entity| [(plugin_name, entity)].into())
+                        } else {
+                            Ok(Vec::new())
+                        }
+                    } else {
+                        Err(Error::InvalidUrl {
+                            loader: NAME.to_string(),
+                            url: url.to_string(),
+                            source: anyhow!("Could not parse plugin name/format"),
+                        })
+                    };
+                }
+                None => {
+                    return if skip_soft_errors
+                        && (options.soft_errors.skip_all()
+                            || options.soft_errors.contains(&SoftErrorsEmbedded::NotFound))
+                    {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::info!(url=%url, skip_error=true, "Could not find embedded path");
+                            } else if #[cfg(feature = "logging")] {
+                                log::info!(
+                                    "msg=\"Could not find embedded path\" url={:?} skip_error=true",
+                                    url.to_string()
+                                );
+                            }
+                        }
+                        Ok(Vec::new())
+                    } else {
+                        Err(Error::NotFound {
+                            loader: NAME.to_string(),
+                            url: url.clone(),
+                            item: format!("embedded path `{path}`").into(),
+                        })
+                    };
+                }
+            }
+        };
+        let mut plugins: HashMap<String, String> = HashMap::with_capacity(file_list.len());
+        let mut result = Vec::with_capacity(file_list.len());
+        for file in file_list {
+            let Some((plugin_name, format)) = Self::get_plugin_name_and_format(file) else {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(path=?file.path(), "Could not parse plugin name/format");
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!("msg=\"Could not parse plugin name/format\" path={:?}", file.path());
+                    }
+                }
+                continue;
+            };
+            if !is_whitelisted(&plugin_name) {
+                continue;
+            }
+            if let Some(other_format) = plugins.get(&plugin_name) {
+                let mut url = url.clone();
+                url.set_query(None);
+                return Err(Error::Duplicate {
+                    loader: NAME.to_string().into(),
+                    url,
+                    plugin: plugin_name.into(),
+                    format_1: other_format.to_string().into(),
+                    format_2: format.into(),
+                });
+            }
+            plugins.insert(plugin_name.clone(), format.clone());
+            let entity = self.file_to_entity(url, file, plugin_name.clone(), format)?;
+            result.push((plugin_name, entity));
+        }
+        Ok(result)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.log_options = log_options;
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -43,25 +43,86 @@
 //! assert!(result.iter().find(|(plugin_name, _)| plugin_name == "qux").is_none());
 //! ```
 //!
+//! ### Variable source
+//! By default, the whole environment is read on every load. If you already know which variables are relevant (e.g.
+//! you keep a snapshot of them and update it when they change), you can pass them via [Env::set_var_source] so each
+//! load (and reload via calling [crate::Configuration::load_parse_merge] again) only goes over them:
+//! ```rust
+//! use plugx_config::{loader::{Loader, env::Env}, ext::url::Url};
+//!
+//! let loader = Env::new().with_prefix("MY_APP").with_var_source(|| {
+//!     // For example only variables that changed since the last load:
+//!     [("MY_APP__FOO__BAR".to_string(), "baz".to_string())].to_vec()
+//! });
+//! let url = Url::try_from("env://").expect("A valid URL!");
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! assert_eq!(loaded.len(), 1);
+//! assert_eq!(loaded[0].1.maybe_contents(), Some(&"BAR=\"baz\"".to_string()));
+//! ```
+//! Note that every load only contains variables that the source returns, so to keep unchanged values, merge the
+//! loaded configurations with the previous ones (e.g. add them as default documents).
+//!
+//! ### Empty values
+//! By default, variables with empty values are loaded as empty strings. The `empty_value_policy` option (or
+//! [Env::set_empty_value_policy]) can skip them (e.g. if an empty variable means unset) or fail loading:
+//! ```rust
+//! use plugx_config::{loader::{Loader, env::{EmptyValuePolicy, Env}}, ext::url::Url};
+//!
+//! let loader = Env::new().with_prefix("MY_APP").with_var_source(|| {
+//!     [
+//!         ("MY_APP__FOO__BAR".to_string(), "baz".to_string()),
+//!         ("MY_APP__FOO__QUX".to_string(), "".to_string()),
+//!     ].to_vec()
+//! });
+//! let url = Url::try_from("env://?empty_value_policy=skip").expect("A valid URL!");
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! assert_eq!(loaded[0].1.maybe_contents(), Some(&"BAR=\"baz\"".to_string()));
+//!
+//! let loader = loader.with_empty_value_policy(EmptyValuePolicy::Error);
+//! let url = Url::try_from("env://").expect("A valid URL!");
+//! assert!(loader.load(&url, None, false).is_err());
+//! ```
+//!
 //! See [mod@loader] documentation to known how loaders work.
 
 use crate::{
     entity::ConfigurationEntity,
     loader::{self, Error, Loader},
+    LogOptions,
 };
+use anyhow::anyhow;
 use cfg_if::cfg_if;
 use serde::Deserialize;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Display, Formatter, Write};
+use std::sync::Arc;
 use std::{env, fmt::Debug};
 use url::Url;
 
 pub const NAME: &str = "Environment-Variables";
 pub const SCHEME_LIST: &[&str] = &["env"];
+/// Metadata key of the key separator that is used to load the entity, so [crate::Configuration] parses nested keys of
+/// it with the same separator (see [crate::parser::env::Env::set_key_separator]).
+pub const KEY_SEPARATOR_METADATA_KEY: &str = "env-key-separator";
+
+/// A `|| -> Vec<(String, String)>` [Fn] that returns environment-variable keys and values.
+pub type VarSourceFn = Arc<dyn Fn() -> Vec<(String, String)> + Send + Sync>;
 
 /// Loads configurations from Environment-Variables.
-#[derive(Debug, Default, Clone)]
+#[derive(Default, Clone)]
 pub struct Env {
     options: EnvOptions,
+    maybe_var_source: Option<VarSourceFn>,
+    log_options: LogOptions,
+}
+
+impl Debug for Env {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Env")
+            .field("options", &self.options)
+            .field("has_var_source", &self.maybe_var_source.is_some())
+            .field("log_options", &self.log_options)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -70,6 +131,21 @@ struct EnvOptions {
     prefix: String,
     separator: String,
     strip_prefix: bool,
+    empty_value_policy: EmptyValuePolicy,
+}
+
+/// What to do with environment-variables that have empty values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmptyValuePolicy {
+    /// Loads them as empty strings.
+    #[default]
+    Keep,
+    /// Ignores them as if they were not set.
+    Skip,
+    /// Fails loading if a variable that would be loaded (i.e. it has a plugin name and a key, and its plugin is
+    /// whitelisted) is empty.
+    Error,
 }
 
 impl Default for EnvOptions {
@@ -78,6 +154,7 @@ impl Default for EnvOptions {
             prefix: default::prefix(),
             separator: default::separator(),
             strip_prefix: default::strip_prefix(),
+            empty_value_policy: Default::default(),
         }
     }
 }
@@ -107,6 +184,37 @@ pub mod default {
     }
 }
 
+/// Returns an `env://?prefix=<CRATE_NAME>` [Url](crate::Url) for the crate that calls this macro.
+///
+/// [default::prefix](crate::loader::env::default::prefix) is taken from `CARGO_BIN_NAME`/`CARGO_CRATE_NAME` when
+/// `plugx-config` itself is compiled, so it never contains your application's name. Macros are expanded inside the
+/// calling crate, so `option_env!` in here reads those variables at *your* compile time instead. The name is
+/// uppercased and `-` is replaced with `_`.
+///
+/// ### Example
+/// ```rust
+/// let url = plugx_config::env_url_for_current_crate!();
+/// assert_eq!(url.scheme(), "env");
+/// ```
+#[macro_export]
+macro_rules! env_url_for_current_crate {
+    () => {{
+        let name = option_env!("CARGO_BIN_NAME")
+            .filter(|name| !name.is_empty())
+            .or(option_env!("CARGO_CRATE_NAME"))
+            .unwrap_or_default()
+            .to_uppercase()
+            .replace('-', "_");
+        let url = if name.is_empty() {
+            "env://".to_string()
+        } else {
+            format!("env://?prefix={name}")
+        };
+        <$crate::Url as ::std::str::FromStr>::from_str(url.as_str())
+            .expect("Crate names are valid URL query values")
+    }};
+}
+
 impl Env {
     /// Same as `default()` method.
     pub fn new() -> Self {
@@ -135,16 +243,46 @@ impl Env {
         self
     }
 
-    /// Used is separating plugin names.
+    /// Removes the prefix (and the separator after it) from keys before detecting plugin names.
+    ///
+    /// It has no effect if the prefix is empty.
     pub fn set_strip_prefix(&mut self, strip_prefix: bool) {
         self.options.strip_prefix = strip_prefix;
     }
 
-    /// Used is separating plugin names.
+    /// See [Env::set_strip_prefix].
     pub fn with_strip_prefix(mut self, strip_prefix: bool) -> Self {
         self.set_strip_prefix(strip_prefix);
         self
     }
+
+    /// What to do with environment-variables that have empty values (defaults to [EmptyValuePolicy::Keep]).
+    pub fn set_empty_value_policy(&mut self, empty_value_policy: EmptyValuePolicy) {
+        self.options.empty_value_policy = empty_value_policy;
+    }
+
+    /// What to do with environment-variables that have empty values (defaults to [EmptyValuePolicy::Keep]).
+    pub fn with_empty_value_policy(mut self, empty_value_policy: EmptyValuePolicy) -> Self {
+        self.set_empty_value_policy(empty_value_policy);
+        self
+    }
+
+    /// Reads environment-variables from `var_source` instead of the process environment.
+    pub fn set_var_source<F>(&mut self, var_source: F)
+    where
+        F: Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.maybe_var_source = Some(Arc::new(var_source));
+    }
+
+    /// Reads environment-variables from `var_source` instead of the process environment.
+    pub fn with_var_source<F>(mut self, var_source: F) -> Self
+    where
+        F: Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.set_var_source(var_source);
+        self
+    }
 }
 
 impl Display for Env {
@@ -159,6 +297,35 @@ impl Loader for Env {
         SCHEME_LIST.iter().cloned().map(String::from).collect()
     }
 
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        loader::deserialize_query_string::<EnvOptions>(NAME, url).map(|_| ())
+    }
+
+    /// Reading environment-variables has no soft errors, so `skip_soft_errors` is ignored.
+    fn supports_soft_errors(&self) -> bool {
+        false
+    }
+
+    /// Environment-variables are already in memory, so it's the result of [Self::load] without contents.
+    fn plan(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        self.load(url, maybe_whitelist, skip_soft_errors)
+            .map(|loaded| {
+                loaded
+                    .into_iter()
+                    .map(|(_, mut entity)| {
+                        *entity.maybe_contents_mut() = None;
+                        entity
+                    })
+                    .collect()
+            })
+            .map(Some)
+    }
+
     /// This loader does not support `skip_soft_errors`.  
     fn load(
         &self,
@@ -170,6 +337,7 @@ impl Loader for Env {
             mut prefix,
             mut separator,
             mut strip_prefix,
+            mut empty_value_policy,
         } = loader::deserialize_query_string(NAME, url)?;
         if self.options.prefix != default::prefix() {
             prefix = self.options.prefix.clone()
@@ -180,82 +348,122 @@ impl Loader for Env {
         if self.options.strip_prefix != default::strip_prefix() {
             strip_prefix = self.options.strip_prefix
         }
+        if self.options.empty_value_policy != EmptyValuePolicy::default() {
+            empty_value_policy = self.options.empty_value_policy
+        }
         if !separator.is_empty() && !prefix.is_empty() && !prefix.ends_with(separator.as_str()) {
             prefix += separator.as_str()
         }
-        let mut result = Vec::new();
-        env::vars()
-            .filter(|(key, _)| prefix.is_empty() || key.starts_with(prefix.as_str()))
-            .map(|(mut key, value)| {
-                if !prefix.is_empty() && strip_prefix {
-                    key = key.chars().skip(prefix.chars().count()).collect::<String>()
-                }
-                (key, value)
-            })
-            .filter(|(key, _)| !key.is_empty())
-            .map(|(key, value)| {
-                let key_list = if separator.is_empty() {
-                    [key].to_vec()
+        let mut result: Vec<(String, String)> = Vec::new();
+        let mut maybe_empty_key = None;
+        let var_iter: Box<dyn Iterator<Item = (String, String)>> =
+            if let Some(var_source) = self.maybe_var_source.as_ref() {
+                Box::new(var_source().into_iter())
+            } else {
+                Box::new(env::vars())
+            };
+        // A single pass which skips variables without the prefix before allocating anything:
+        for (key, value) in var_iter {
+            // There is nothing to strip with an empty prefix:
+            let key = if prefix.is_empty() {
+                key.as_str()
+            } else if let Some(stripped_key) = key.strip_prefix(prefix.as_str()) {
+                if strip_prefix {
+                    stripped_key
                 } else {
-                    key.splitn(2, separator.as_str())
-                        .map(|key| key.to_string())
-                        .collect()
-                };
-                (key_list, value)
-            })
-            .filter(|(key_list, _)| !key_list[0].is_empty())
-            .map(|(mut key_list, value)| {
-                let plugin_name = key_list.remove(0).to_lowercase();
-                let key = if key_list.len() == 1 {
-                    key_list.remove(0)
-                } else {
-                    String::new()
-                };
-                (plugin_name, key, value)
-            })
-            .filter(|(_, key, _)| !key.is_empty())
-            .map(|(_plugin_name, _key, _value)| {
-                cfg_if! {
-                    if #[cfg(feature = "tracing")] {
-                        tracing::trace!(
-                            plugin=_plugin_name,
-                            key=_key,
-                            value=_value,
-                            "Detected environment-variable"
-                        );
-                    } else if #[cfg(feature = "logging")] {
-                        log::trace!(
-                            "msg=\"Detected environment-variable\" plugin={_plugin_name:?} key={_key:?} value={_value:?}"
-                        );
+                    key.as_str()
+                }
+            } else {
+                continue;
+            };
+            if key.is_empty() {
+                continue;
+            }
+            // Without a separator there is no key after the plugin name:
+            if separator.is_empty() {
+                continue;
+            }
+            let variable_name = key;
+            let Some((plugin_name, key)) = key.split_once(separator.as_str()) else {
+                continue;
+            };
+            if plugin_name.is_empty() || key.is_empty() {
+                continue;
+            }
+            let plugin_name = plugin_name.to_lowercase();
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::trace!(
+                        plugin=plugin_name,
+                        key=key,
+                        value=self.log_options.contents(value.as_str()),
+                        "Detected environment-variable"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::trace!(
+                        "msg=\"Detected environment-variable\" plugin={plugin_name:?} key={key:?} value={:?}",
+                        self.log_options.contents(value.as_str())
+                    );
+                }
+            }
+            if !maybe_whitelist
+                .map(|whitelist| whitelist.contains(&plugin_name))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            // Only variables that would be loaded are checked:
+            if value.is_empty() {
+                match empty_value_policy {
+                    EmptyValuePolicy::Keep => (),
+                    EmptyValuePolicy::Skip => {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::trace!(key=variable_name, "Skipped environment-variable with empty value");
+                            } else if #[cfg(feature = "logging")] {
+                                log::trace!("msg=\"Skipped environment-variable with empty value\" key={variable_name:?}");
+                            }
+                        }
+                        continue;
+                    }
+                    EmptyValuePolicy::Error => {
+                        maybe_empty_key.get_or_insert_with(|| variable_name.to_string());
+                        continue;
                     }
                 }
-                (_plugin_name, _key, _value)
-            })
-            .filter(|(plugin_name, _, _)| {
-                maybe_whitelist
-                    .as_ref()
-                    .map(|whitelist| whitelist.contains(plugin_name))
-                    .unwrap_or(true)
-            })
-            .for_each(|(plugin_name, key, value)| {
-                let key_value = format!("{key}={value:?}");
-                if let Some((_, _, configuration)) =
-                    result.iter_mut().find(|(name, _, _)| *name == plugin_name)
-                {
-                    *configuration += "\n";
-                    *configuration += key_value.as_str();
+            }
+            let contents =
+                if let Some(index) = result.iter().position(|(name, _)| *name == plugin_name) {
+                    let contents = &mut result[index].1;
+                    contents.push('\n');
+                    contents
                 } else {
-                    result.push((plugin_name, format!("{prefix}*"), key_value));
-                }
+                    result.push((plugin_name, String::new()));
+                    &mut result.last_mut().expect("pushed in above").1
+                };
+            write!(contents, "{key}={value:?}").expect("writing to a string does not fail");
+        }
+        if let Some(mut key) = maybe_empty_key {
+            if strip_prefix {
+                key = format!("{prefix}{key}");
+            }
+            return Err(Error::Load {
+                loader: NAME.to_string(),
+                url: url.clone(),
+                description: format!("load empty environment-variable `{key}` from").into(),
+                source: anyhow!("Empty values are not allowed (see `empty_value_policy` option)"),
             });
+        }
+        let item = format!("{prefix}*");
         Ok(result
             .into_iter()
-            .map(|(plugin_name, key, contents)| {
+            .map(|(plugin_name, contents)| {
                 (
                     plugin_name.clone(),
-                    ConfigurationEntity::new(key, url.clone(), plugin_name, NAME)
+                    ConfigurationEntity::new(item.as_str(), url.clone(), plugin_name, NAME)
                         .with_format("env")
-                        .with_contents(contents),
+                        .with_contents(contents)
+                        .with_metadata(KEY_SEPARATOR_METADATA_KEY, separator.as_str()),
                 )
             })
             .map(|(_plugin_name, _configuration)| {
@@ -264,14 +472,14 @@ impl Loader for Env {
                         tracing::trace!(
                             plugin=_plugin_name,
                             format=_configuration.maybe_format().unwrap_or(&"<unknown>".to_string()),
-                            contents=_configuration.maybe_contents().unwrap(),
+                            contents=self.log_options.contents(_configuration.maybe_contents().unwrap()),
                             "Detected configuration from environment-variable"
                         );
                     } else if #[cfg(feature = "logging")] {
                         log::trace!(
                             "msg=\"Detected configuration from environment-variable\" plugin={_plugin_name:?} format={:?} contents={:?}",
                             _configuration.maybe_format().unwrap_or(&"<unknown>".to_string()),
-                            _configuration.maybe_contents().unwrap(),
+                            self.log_options.contents(_configuration.maybe_contents().unwrap()),
                         );
                     }
                 }
@@ -279,4 +487,12 @@ impl Loader for Env {
             })
             .collect())
     }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.log_options = log_options;
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
 }
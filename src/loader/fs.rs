@@ -1,7 +1,37 @@
 //! File system configuration loader (`fs` feature).
 //!
-//! * Supported schema: `fs` and `file`  
-//! *
+//! * Supported schema: `fs` and `file`
+//! * Options: `strip-slash`, `expand-env` (expands `$NAME`, `${NAME}`, and `${NAME:-default}` inside the path),
+//!   `stream-threshold` (files larger than this number of bytes are not read into memory first and the parser of their
+//!   format parses them directly from the file, e.g. the `json` parser), `max-file-size` (files larger than this number of bytes are not
+//!   read and yield an error which is skippable via `too-large` soft error), `encoding` (`utf-8` which is the default,
+//!   `utf-16le`, `utf-16be`, or `latin-1` with `encoding` feature; a byte order mark takes precedence), and
+//!   `soft-errors`.
+//! * Option `recursive` also loads files of sub-directories. Files of a plugin in different directories are merged
+//!   in path order (only files of a plugin inside the same directory are duplicates). Directories that are already
+//!   walked (e.g. via a symbolic link loop) are skipped and so are unreadable sub-directories if their error is a
+//!   skipped soft error. Option `follow-symlinks` (defaults to `true`) set to `false` skips symbolic links inside
+//!   directories.
+//! * Option `plugin-per-dir` inverts the file-per-plugin model: each sub-directory of the URL directory becomes one
+//!   plugin named after the sub-directory (or [Fs::set_plugin_name_fn] of it) and its files (in sorted order) are
+//!   merged. Files directly inside the URL directory are skipped.
+//! * Option `tail` (or [Fs::set_tail]) makes the loader remember how many bytes of each file it has read, so next
+//!   loads only return newly appended complete lines of UTF-8 files (files that are replaced, truncated, or rewritten
+//!   are read from the beginning). `max-file-size` applies to the appended bytes. Merge the result on top of the
+//!   previous one. This only makes sense for append-only, line-delimited formats like `env`. Each clone of the loader
+//!   keeps its own offsets and [crate::Configuration] only advances them when it actually loads (e.g. not in
+//!   [crate::Configuration::dump_debug]).
+//! * Files with non-UTF-8 names are skipped with a warning. Option `lossy-file-names` (or [Fs::set_lossy_file_names])
+//!   loads them and replaces invalid bytes of their plugin names with `U+FFFD` (on Unix, the exact path is kept in
+//!   [RAW_PATH_METADATA_KEY] metadata of the entity to read the file).
+//! * On Unix, a URL can point to a FIFO (named pipe) which is read like a regular file until its writer closes it.
+//!   Option `fifo-timeout` (or [Fs::set_fifo_timeout]) is the number of seconds to wait for the writer to write and
+//!   close it, otherwise loading blocks until that happens. `max-file-size` applies to the read bytes. Every load of
+//!   the URL reads the FIFO again, including reloads, [crate::Configuration::normalize_source], and
+//!   [crate::Configuration::dump_debug], so each one consumes a write (or waits for it). [crate::Configuration::plan]
+//!   does not read it.
+//! * Plugin names are lowercased file stems (e.g. `foo` for `Foo.json`). Use [Fs::set_plugin_name_fn] to change them
+//!   (e.g. strip ordering prefixes like `10-` in `10-database.yaml`) or skip files.
 //!
 //! ### Example
 //! ```rust
@@ -50,37 +80,167 @@
 
 use crate::{
     entity::ConfigurationEntity,
+    expand::expand_env_vars,
     loader::{self, Error, Loader, SoftErrors},
+    LogOptions,
 };
 use anyhow::anyhow;
 use cfg_if::cfg_if;
+use percent_encoding::percent_decode_str;
 use serde::Deserialize;
 use std::fmt::{Display, Formatter};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     env::current_dir,
+    ffi::OsStr,
     fmt::Debug,
-    fs, io,
+    fs,
+    io::{self, Read, Seek, SeekFrom},
     path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
 };
 use url::Url;
 
 pub const NAME: &str = "File";
 pub const SCHEME_LIST: &[&str] = &["fs", "file"];
+/// Metadata key of percent-encoded bytes of non-UTF-8 file paths (see `lossy-file-names` option).
+pub const RAW_PATH_METADATA_KEY: &str = "fs-raw-path";
+
+/// A `|&Path| -> Option<String>` [Fn] that detects plugin name of a file (see [Fs::set_plugin_name_fn]).
+pub type PluginNameFn = Arc<dyn Fn(&Path) -> Option<String> + Send + Sync>;
 
 /// Loads configurations from filesystem.
-#[derive(Default, Clone, Debug)]
+#[derive(Default)]
 pub struct Fs {
     options: FsOptions,
+    maybe_plugin_name_fn: Option<PluginNameFn>,
+    // Read offset of each file for `tail` option which is copied (not shared) to clones:
+    tail_offset_list: Mutex<HashMap<PathBuf, TailOffset>>,
+    log_options: LogOptions,
+}
+
+/// Read offset of a file for `tail` option.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TailOffset {
+    offset: u64,
+    maybe_inode: Option<u64>,
+    // Last bytes before `offset` to detect files that are truncated and rewritten past `offset`:
+    last_byte_list: Vec<u8>,
+}
+
+impl Clone for Fs {
+    fn clone(&self) -> Self {
+        Self {
+            options: self.options.clone(),
+            maybe_plugin_name_fn: self.maybe_plugin_name_fn.clone(),
+            tail_offset_list: Mutex::new(
+                self.tail_offset_list
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone(),
+            ),
+            log_options: self.log_options,
+        }
+    }
+}
+
+impl Debug for Fs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fs")
+            .field("options", &self.options)
+            .field("has_plugin_name_fn", &self.maybe_plugin_name_fn.is_some())
+            .field(
+                "tail_offset_list",
+                &self
+                    .tail_offset_list
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            )
+            .field("log_options", &self.log_options)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default, rename_all = "kebab-case")]
 pub struct FsOptions {
     strip_slash: Option<bool>,
+    expand_env: Option<bool>,
+    stream_threshold: Option<u64>,
+    max_file_size: Option<u64>,
+    #[cfg(feature = "encoding")]
+    encoding: Option<Encoding>,
+    // Only to reject the option without `encoding` feature:
+    #[cfg(not(feature = "encoding"))]
+    encoding: Option<String>,
+    tail: Option<bool>,
+    recursive: Option<bool>,
+    follow_symlinks: Option<bool>,
+    #[serde(alias = "plugin_per_dir")]
+    plugin_per_dir: Option<bool>,
+    lossy_file_names: Option<bool>,
+    fifo_timeout: Option<u64>,
     soft_errors: SoftErrors<SoftErrorsFs>,
 }
 
+/// Text encoding of configuration files (`encoding` feature).
+#[cfg(feature = "encoding")]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+pub enum Encoding {
+    #[serde(rename = "utf-8", alias = "utf8")]
+    Utf8,
+    #[serde(rename = "utf-16le", alias = "utf16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be", alias = "utf16be")]
+    Utf16Be,
+    #[serde(rename = "latin-1", alias = "latin1", alias = "iso-8859-1")]
+    Latin1,
+}
+
+#[cfg(feature = "encoding")]
+impl Encoding {
+    /// Decodes `bytes` to a [String].
+    ///
+    /// A byte order mark (UTF-8, UTF-16LE, or UTF-16BE) takes precedence over `maybe_encoding` and is removed. Without
+    /// both of them, `bytes` should be UTF-8.
+    pub fn decode(bytes: Vec<u8>, maybe_encoding: Option<Encoding>) -> Result<String, io::Error> {
+        let (encoding, bytes) = if let Some(bytes) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+            (Self::Utf8, bytes)
+        } else if let Some(bytes) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            (Self::Utf16Le, bytes)
+        } else if let Some(bytes) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+            (Self::Utf16Be, bytes)
+        } else {
+            (maybe_encoding.unwrap_or(Self::Utf8), bytes.as_slice())
+        };
+        let invalid_data = |error: String| io::Error::new(io::ErrorKind::InvalidData, error);
+        match encoding {
+            Self::Utf8 => {
+                String::from_utf8(bytes.to_vec()).map_err(|error| invalid_data(error.to_string()))
+            }
+            Self::Utf16Le | Self::Utf16Be => {
+                if bytes.len() % 2 != 0 {
+                    return Err(invalid_data(
+                        "UTF-16 contents have an odd number of bytes".into(),
+                    ));
+                }
+                let unit_list: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        if encoding == Self::Utf16Le {
+                            u16::from_le_bytes([pair[0], pair[1]])
+                        } else {
+                            u16::from_be_bytes([pair[0], pair[1]])
+                        }
+                    })
+                    .collect();
+                String::from_utf16(&unit_list).map_err(|error| invalid_data(error.to_string()))
+            }
+            Self::Latin1 => Ok(bytes.iter().map(|byte| *byte as char).collect()),
+        }
+    }
+}
+
 impl FsOptions {
     pub fn contains(&self, error: io::ErrorKind) -> bool {
         SoftErrorsFs::try_from(error)
@@ -95,6 +255,8 @@ impl FsOptions {
 pub enum SoftErrorsFs {
     NotFound,
     PermissionDenied,
+    /// The file is larger than the `max-file-size` option.
+    TooLarge,
 }
 
 impl TryFrom<io::ErrorKind> for SoftErrorsFs {
@@ -104,6 +266,7 @@ impl TryFrom<io::ErrorKind> for SoftErrorsFs {
         match value {
             io::ErrorKind::NotFound => Ok(Self::NotFound),
             io::ErrorKind::PermissionDenied => Ok(Self::PermissionDenied),
+            io::ErrorKind::FileTooLarge => Ok(Self::TooLarge),
             _ => Err("Unhandled IO error".into()),
         }
     }
@@ -117,6 +280,78 @@ impl Fs {
             .and_then(|name| Self::get_format(&path).map(|format| (name, format)))
     }
 
+    #[inline]
+    fn get_plugin_name_and_format_with<P: AsRef<Path>>(
+        path: P,
+        maybe_plugin_name_fn: Option<&PluginNameFn>,
+        lossy_file_names: bool,
+    ) -> Option<(String, String)> {
+        let path = path.as_ref();
+        let os_str_to_str = |os_str: &OsStr| -> Option<String> {
+            if lossy_file_names {
+                Some(os_str.to_string_lossy().into_owned())
+            } else {
+                os_str.to_str().map(String::from)
+            }
+        };
+        let maybe_plugin_name = if let Some(plugin_name_fn) = maybe_plugin_name_fn {
+            plugin_name_fn(path)
+        } else {
+            path.file_stem().and_then(os_str_to_str)
+        };
+        maybe_plugin_name
+            .map(|name| name.to_lowercase())
+            .filter(|name| !name.is_empty())
+            .and_then(|name| {
+                path.extension()
+                    .and_then(os_str_to_str)
+                    .map(|format| format.to_lowercase())
+                    .filter(|format| !format.is_empty())
+                    .map(|format| (name, format))
+            })
+    }
+
+    /// Creates an entity for file `path` which keeps the exact path of non-UTF-8 paths in its metadata on Unix.
+    ///
+    /// The entity keeps `url` as is (including its query string) for both directory and single-file URLs, and its
+    /// path is never re-derived from the URL (see [Fs::entity_path]).
+    fn new_entity(
+        path: &Path,
+        url: &Url,
+        plugin_name: String,
+        format: String,
+    ) -> ConfigurationEntity {
+        let entity =
+            ConfigurationEntity::new(path.to_string_lossy(), url.clone(), plugin_name, NAME)
+                .with_format(format);
+        #[cfg(unix)]
+        if path.to_str().is_none() {
+            use std::os::unix::ffi::OsStrExt;
+
+            return entity.with_metadata(
+                RAW_PATH_METADATA_KEY,
+                percent_encoding::percent_encode(
+                    path.as_os_str().as_bytes(),
+                    percent_encoding::NON_ALPHANUMERIC,
+                )
+                .to_string(),
+            );
+        }
+        entity
+    }
+
+    /// Returns path of the entity file (see [RAW_PATH_METADATA_KEY]).
+    pub fn entity_path(entity: &ConfigurationEntity) -> PathBuf {
+        #[cfg(unix)]
+        if let Some(raw_path) = entity.metadata().get(RAW_PATH_METADATA_KEY) {
+            use std::os::unix::ffi::OsStrExt;
+
+            let bytes: Vec<u8> = percent_decode_str(raw_path).collect();
+            return PathBuf::from(OsStr::from_bytes(bytes.as_slice()));
+        }
+        PathBuf::from(entity.item())
+    }
+
     #[inline]
     pub fn get_plugin_name<P: AsRef<Path>>(path: P) -> Option<String> {
         path.as_ref()
@@ -145,39 +380,84 @@ impl Fs {
     pub(super) fn get_entity_list(
         url: &Url,
         options: &FsOptions,
+        maybe_plugin_name_fn: Option<&PluginNameFn>,
         maybe_whitelist: Option<&[String]>,
         skip_soft_errors: bool,
     ) -> Result<Vec<ConfigurationEntity>, Error> {
-        let path = Self::url_to_path(url, options)
-            .map_err(|_| Error::Other(anyhow!("Could not detect current working directory")))?;
-        if path.is_dir() {
-            let list = match Self::get_directory_file_list(&path, maybe_whitelist) {
+        let path = Self::url_to_path(url, options).map_err(|error| {
+            if error.kind() == io::ErrorKind::InvalidInput {
+                Error::InvalidUrl {
+                    loader: NAME.to_string(),
+                    url: url.to_string(),
+                    source: error.into(),
+                }
+            } else {
+                Error::Other(anyhow!("Could not detect current working directory"))
+            }
+        })?;
+        // Unreadable sub-directories are skipped on soft errors:
+        let skip_error = |error: &io::Error| {
+            skip_soft_errors && (options.soft_errors.skip_all() || options.contains(error.kind()))
+        };
+        if path.is_dir() && options.plugin_per_dir.unwrap_or(false) {
+            let list = match Self::get_plugin_directory_file_list_with(
+                &path,
+                maybe_plugin_name_fn,
+                maybe_whitelist,
+                options.recursive.unwrap_or(false),
+                options.follow_symlinks.unwrap_or(true),
+                options.lossy_file_names.unwrap_or(false),
+                &skip_error,
+            ) {
                 Ok(list) => list,
                 Err(error) => {
-                    return if skip_soft_errors
-                        && (options.soft_errors.skip_all() || options.contains(error.kind()))
-                    {
-                        cfg_if! {
-                            if #[cfg(feature = "tracing")] {
-                                tracing::info!(path=?path, skip_error=true, "Could not load directory contents");
-                            } else if #[cfg(feature = "logging")] {
-                                log::info!("msg=\"Could not load directory contents\" path={path:?} skip_error=true");
-                            }
-                        }
-                        Ok(Vec::new())
-                    } else {
-                        Err(Error::Load {
-                            loader: NAME.to_string(),
-                            url: url.clone(),
-                            description: "load directory file list".to_string().into(),
-                            source: error.into(),
-                        })
-                    }
+                    return Error::from_io(
+                        NAME,
+                        url,
+                        "load directory file list",
+                        error,
+                        &options.soft_errors,
+                        skip_soft_errors,
+                    )
+                    .map(|_| Vec::new())
                 }
             };
-            let mut plugins: HashMap<&String, &String> = HashMap::with_capacity(list.len());
-            for (plugin_name, format, _) in list.iter() {
-                if let Some(other_format) = plugins.get(plugin_name) {
+            Ok(list
+                .into_iter()
+                .map(|(plugin_name, format, path)| {
+                    Self::new_entity(&path, url, plugin_name, format)
+                })
+                .collect())
+        } else if path.is_dir() {
+            let list = match Self::get_directory_file_list_with(
+                &path,
+                maybe_plugin_name_fn,
+                maybe_whitelist,
+                options.recursive.unwrap_or(false),
+                options.follow_symlinks.unwrap_or(true),
+                options.lossy_file_names.unwrap_or(false),
+                &skip_error,
+            ) {
+                Ok(list) => list,
+                Err(error) => {
+                    return Error::from_io(
+                        NAME,
+                        url,
+                        "load directory file list",
+                        error,
+                        &options.soft_errors,
+                        skip_soft_errors,
+                    )
+                    .map(|_| Vec::new())
+                }
+            };
+            // Files of a plugin in different sub-directories are merged, so only files of a plugin inside the same
+            // directory are duplicates:
+            let mut plugins: HashMap<(Option<&Path>, &String), &String> =
+                HashMap::with_capacity(list.len());
+            for (plugin_name, format, file_path) in list.iter() {
+                let key = (file_path.parent(), plugin_name);
+                if let Some(other_format) = plugins.get(&key) {
                     let mut url = url.clone();
                     url.set_query(None);
                     return Err(Error::Duplicate {
@@ -188,30 +468,26 @@ impl Fs {
                         format_2: format.to_string().into(),
                     });
                 } else {
-                    plugins.insert(plugin_name, format);
+                    plugins.insert(key, format);
                 }
             }
             Ok(list
                 .into_iter()
                 .map(|(plugin_name, format, path)| {
-                    ConfigurationEntity::new(path.to_str().unwrap(), url.clone(), plugin_name, NAME)
-                        .with_format(format)
+                    Self::new_entity(&path, url, plugin_name, format)
                 })
                 .collect())
-        } else if path.is_file() {
-            if let Some((plugin_name, format)) = Self::get_plugin_name_and_format(&path) {
+        } else if path.is_file() || Self::is_fifo(&path) {
+            if let Some((plugin_name, format)) = Self::get_plugin_name_and_format_with(
+                &path,
+                maybe_plugin_name_fn,
+                options.lossy_file_names.unwrap_or(false),
+            ) {
                 if maybe_whitelist
                     .map(|whitelist| whitelist.contains(&plugin_name))
                     .unwrap_or(true)
                 {
-                    let entity = ConfigurationEntity::new(
-                        path.to_str().unwrap(),
-                        url.clone(),
-                        plugin_name,
-                        NAME,
-                    )
-                    .with_format(format);
-                    Ok([entity].into())
+                    Ok([Self::new_entity(&path, url, plugin_name, format)].into())
                 } else {
                     Ok(Vec::new())
                 }
@@ -280,11 +556,232 @@ impl Fs {
         path: P,
         maybe_whitelist: Option<&[String]>,
     ) -> Result<Vec<(String, String, PathBuf)>, io::Error> {
-        Ok(fs::read_dir(path)?
-            .filter_map(|maybe_entry| maybe_entry.ok())
-            .map(|entry| entry.path())
+        Self::get_directory_file_list_with(path, None, maybe_whitelist, false, true, false, &|_| {
+            false
+        })
+    }
+
+    /// Lists paths inside directory `path` (and its sub-directories if `recursive` is `true`).
+    ///
+    /// Directories in `visited_list` (canonical paths) are skipped to not walk symbolic link loops forever.
+    /// Sub-directories that can not be read are skipped if `skip_error` returns `true` for their error.
+    fn get_directory_path_list<P: AsRef<Path>>(
+        path: P,
+        recursive: bool,
+        follow_symlinks: bool,
+        visited_list: &mut HashSet<PathBuf>,
+        skip_error: &dyn Fn(&io::Error) -> bool,
+    ) -> Result<Vec<PathBuf>, io::Error> {
+        let path = path.as_ref();
+        visited_list.insert(fs::canonicalize(path)?);
+        let mut path_list = Vec::new();
+        for entry in fs::read_dir(path)?.filter_map(|maybe_entry| maybe_entry.ok()) {
+            let path = entry.path();
+            if !follow_symlinks
+                && entry
+                    .file_type()
+                    .map(|file_type| file_type.is_symlink())
+                    .unwrap_or_default()
+            {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::trace!(path=?path, "Skipped symbolic link");
+                    } else if #[cfg(feature = "logging")] {
+                        log::trace!("msg=\"Skipped symbolic link\" path={path:?}");
+                    }
+                }
+                continue;
+            }
+            if recursive && path.is_dir() {
+                let result = fs::canonicalize(&path).and_then(|canonical_path| {
+                    if visited_list.contains(&canonical_path) {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::trace!(path=?path, "Skipped already visited directory (symbolic link loop)");
+                            } else if #[cfg(feature = "logging")] {
+                                log::trace!("msg=\"Skipped already visited directory (symbolic link loop)\" path={path:?}");
+                            }
+                        }
+                        Ok(Vec::new())
+                    } else {
+                        Self::get_directory_path_list(
+                            &path,
+                            recursive,
+                            follow_symlinks,
+                            visited_list,
+                            skip_error,
+                        )
+                    }
+                });
+                match result {
+                    Ok(mut sub_path_list) => path_list.append(&mut sub_path_list),
+                    Err(error) if skip_error(&error) => {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::info!(path=?path, error=%error, skip_error=true, "Could not read directory");
+                            } else if #[cfg(feature = "logging")] {
+                                log::info!(
+                                    "msg=\"Could not read directory\" path={path:?} error={:?} skip_error=true",
+                                    error.to_string()
+                                );
+                            }
+                        }
+                    }
+                    Err(error) => return Err(error),
+                }
+            } else {
+                path_list.push(path);
+            }
+        }
+        Ok(path_list)
+    }
+
+    /// Lists files of each sub-directory of `path` as configuration files of a plugin named after the sub-directory.
+    ///
+    /// Sub-directories and their files are sorted by path, so files of each plugin are merged in that order.
+    fn get_plugin_directory_file_list_with<P: AsRef<Path>>(
+        path: P,
+        maybe_plugin_name_fn: Option<&PluginNameFn>,
+        maybe_whitelist: Option<&[String]>,
+        recursive: bool,
+        follow_symlinks: bool,
+        lossy_file_names: bool,
+        skip_error: &dyn Fn(&io::Error) -> bool,
+    ) -> Result<Vec<(String, String, PathBuf)>, io::Error> {
+        let os_str_to_str = |os_str: &OsStr| -> Option<String> {
+            if lossy_file_names {
+                Some(os_str.to_string_lossy().into_owned())
+            } else {
+                os_str.to_str().map(String::from)
+            }
+        };
+        let mut directory_list = Self::get_directory_path_list(
+            path,
+            false,
+            follow_symlinks,
+            &mut HashSet::new(),
+            skip_error,
+        )?;
+        directory_list.sort();
+        let mut file_list = Vec::new();
+        for directory in directory_list {
+            if !directory.is_dir() {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::trace!(path=?directory, "Skipped non-directory path");
+                    } else if #[cfg(feature = "logging")] {
+                        log::trace!("msg=\"Skipped non-directory path\" path={directory:?}");
+                    }
+                }
+                continue;
+            }
+            let maybe_plugin_name = if let Some(plugin_name_fn) = maybe_plugin_name_fn {
+                plugin_name_fn(&directory).map(|name| name.to_lowercase())
+            } else {
+                directory
+                    .file_name()
+                    .and_then(os_str_to_str)
+                    .map(|name| name.to_lowercase())
+            }
+            .filter(|name| !name.is_empty());
+            let Some(plugin_name) = maybe_plugin_name else {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::warn!(path=?directory, "Could not parse plugin name");
+                    } else if #[cfg(feature = "logging")] {
+                        log::warn!("msg=\"Could not parse plugin name\" path={directory:?}");
+                    }
+                }
+                continue;
+            };
+            if !maybe_whitelist
+                .map(|whitelist| whitelist.contains(&plugin_name))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            let mut path_list = match Self::get_directory_path_list(
+                &directory,
+                recursive,
+                follow_symlinks,
+                &mut HashSet::new(),
+                skip_error,
+            ) {
+                Ok(path_list) => path_list,
+                Err(error) if skip_error(&error) => {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::info!(path=?directory, error=%error, skip_error=true, "Could not read directory");
+                        } else if #[cfg(feature = "logging")] {
+                            log::info!(
+                                "msg=\"Could not read directory\" path={directory:?} error={:?} skip_error=true",
+                                error.to_string()
+                            );
+                        }
+                    }
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+            path_list.sort();
+            path_list
+                .into_iter()
+                .filter(|path| path.is_file())
+                .for_each(|path| {
+                    let maybe_format = path
+                        .extension()
+                        .and_then(os_str_to_str)
+                        .map(|format| format.to_lowercase())
+                        .filter(|format| !format.is_empty());
+                    if let Some(format) = maybe_format {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::trace!(plugin=plugin_name, path=?path, "Detected configuration file");
+                            } else if #[cfg(feature = "logging")] {
+                                log::trace!("msg=\"Detected configuration file\" plugin={plugin_name:?} path={path:?}");
+                            }
+                        }
+                        file_list.push((plugin_name.clone(), format, path))
+                    } else {
+                        cfg_if! {
+                            if #[cfg(feature = "tracing")] {
+                                tracing::warn!(path=?path, "Could not parse format");
+                            } else if #[cfg(feature = "logging")] {
+                                log::warn!("msg=\"Could not parse format\" path={path:?}");
+                            }
+                        }
+                    }
+                });
+        }
+        Ok(file_list)
+    }
+
+    #[inline]
+    fn get_directory_file_list_with<P: AsRef<Path>>(
+        path: P,
+        maybe_plugin_name_fn: Option<&PluginNameFn>,
+        maybe_whitelist: Option<&[String]>,
+        recursive: bool,
+        follow_symlinks: bool,
+        lossy_file_names: bool,
+        skip_error: &dyn Fn(&io::Error) -> bool,
+    ) -> Result<Vec<(String, String, PathBuf)>, io::Error> {
+        let mut path_list = Self::get_directory_path_list(
+            path,
+            recursive,
+            follow_symlinks,
+            &mut HashSet::new(),
+            skip_error,
+        )?;
+        // Files of a plugin in different sub-directories are merged in this order:
+        path_list.sort();
+        Ok(
+            path_list
+                .into_iter()
             .filter_map(|path| {
-                if let Some((plugin_name, format)) = Self::get_plugin_name_and_format(&path) {
+                if let Some((plugin_name, format)) =
+                    Self::get_plugin_name_and_format_with(&path, maybe_plugin_name_fn, lossy_file_names)
+                {
                     cfg_if! {
                         if #[cfg(feature = "tracing")] {
                             tracing::trace!(plugin=plugin_name, path=?path, "Detected configuration file");
@@ -293,6 +790,25 @@ impl Fs {
                         }
                     }
                     Some((plugin_name, format, path))
+                } else if let Some(_file_name) = path
+                    .file_name()
+                    .filter(|file_name| file_name.to_str().is_none())
+                {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::warn!(
+                                name=?_file_name.to_os_string(),
+                                path=?path,
+                                "Skipped file with non-UTF-8 name (see `lossy-file-names` option)"
+                            );
+                        } else if #[cfg(feature = "logging")] {
+                            log::warn!(
+                                "msg=\"Skipped file with non-UTF-8 name (see `lossy-file-names` option)\" name={:?} path={path:?}",
+                                _file_name.to_os_string()
+                            );
+                        }
+                    }
+                    None
                 } else {
                     cfg_if! {
                         if #[cfg(feature = "tracing")] {
@@ -328,11 +844,331 @@ impl Fs {
 
     #[inline]
     pub fn read_entity_contents(entity: &mut ConfigurationEntity) -> Result<(), io::Error> {
-        fs::read_to_string(entity.item()).map(|contents| {
+        fs::read_to_string(Self::entity_path(entity)).map(|contents| {
+            entity.set_contents(contents);
+        })
+    }
+
+    /// Same as [Fs::read_entity_contents] but decodes contents based on byte order mark or `encoding` option.
+    #[cfg(feature = "encoding")]
+    #[inline]
+    pub fn read_and_decode_entity_contents(
+        entity: &mut ConfigurationEntity,
+        maybe_encoding: Option<Encoding>,
+    ) -> Result<(), io::Error> {
+        Encoding::decode(fs::read(Self::entity_path(entity))?, maybe_encoding).map(|contents| {
             entity.set_contents(contents);
         })
     }
 
+    /// Same as [Fs::read_entity_contents] but leaves files larger than `stream-threshold` bytes unread and sets a
+    /// contents reader for them instead (see [ConfigurationEntity::set_contents_reader]), and decodes other encodings
+    /// (needs `encoding` feature).
+    ///
+    /// Files that need decoding (a non-UTF-8 `encoding` option or a byte order mark) are always read and decoded first (with
+    /// `encoding` feature), since parsers only accept UTF-8.
+    pub fn read_or_stream_entity_contents(
+        entity: &mut ConfigurationEntity,
+        options: &FsOptions,
+    ) -> Result<(), io::Error> {
+        if let Some(stream_threshold) = options.stream_threshold {
+            let path = Self::entity_path(entity);
+            if fs::metadata(&path)?.len() > stream_threshold
+                && !Self::needs_decoding(&path, options)?
+            {
+                entity.set_contents_reader(Arc::new(move || {
+                    Ok(Box::new(io::BufReader::new(fs::File::open(&path)?))
+                        as Box<dyn Read + Send>)
+                }));
+                return Ok(());
+            }
+        }
+        cfg_if! {
+            if #[cfg(feature = "encoding")] {
+                Self::read_and_decode_entity_contents(entity, options.encoding)
+            } else {
+                Self::read_entity_contents(entity)
+            }
+        }
+    }
+
+    /// Whether the file has a byte order mark or the `encoding` option is not UTF-8.
+    fn needs_decoding(path: &Path, options: &FsOptions) -> Result<bool, io::Error> {
+        #[cfg(feature = "encoding")]
+        if !matches!(options.encoding, None | Some(Encoding::Utf8)) {
+            return Ok(true);
+        }
+        #[cfg(not(feature = "encoding"))]
+        let _ = options;
+        let mut prefix = Vec::with_capacity(3);
+        fs::File::open(path)?.take(3).read_to_end(&mut prefix)?;
+        Ok(prefix.starts_with(&[0xEF, 0xBB, 0xBF])
+            || prefix.starts_with(&[0xFF, 0xFE])
+            || prefix.starts_with(&[0xFE, 0xFF]))
+    }
+
+    /// Whether `path` is a FIFO (named pipe) which is always `false` on non-Unix platforms.
+    pub fn is_fifo<P: AsRef<Path>>(path: P) -> bool {
+        cfg_if! {
+            if #[cfg(unix)] {
+                use std::os::unix::fs::FileTypeExt;
+
+                fs::metadata(path)
+                    .map(|metadata| metadata.file_type().is_fifo())
+                    .unwrap_or(false)
+            } else {
+                let _ = path;
+                false
+            }
+        }
+    }
+
+    /// Reads the entity FIFO (named pipe) until its writer closes it.
+    ///
+    /// Reading more than `max-file-size` bytes yields [io::ErrorKind::FileTooLarge]. With `fifo-timeout` option, not
+    /// being written and closed in time yields [io::ErrorKind::TimedOut].
+    pub fn read_entity_fifo(
+        entity: &mut ConfigurationEntity,
+        options: &FsOptions,
+    ) -> Result<(), io::Error> {
+        let path = Self::entity_path(entity);
+        // One more byte to detect larger contents:
+        let limit = options
+            .max_file_size
+            .map(|max_file_size| max_file_size.saturating_add(1))
+            .unwrap_or(u64::MAX);
+        let bytes = match options.fifo_timeout {
+            #[cfg(unix)]
+            Some(timeout) => Self::read_fifo_with_timeout(&path, limit, timeout)?,
+            _ => {
+                let mut bytes = Vec::new();
+                fs::File::open(path)?.take(limit).read_to_end(&mut bytes)?;
+                bytes
+            }
+        };
+        if let Some(max_file_size) = options.max_file_size {
+            if bytes.len() as u64 > max_file_size {
+                return Err(io::Error::new(
+                    io::ErrorKind::FileTooLarge,
+                    format!("FIFO has more than `max-file-size` ({max_file_size}) bytes"),
+                ));
+            }
+        }
+        cfg_if! {
+            if #[cfg(feature = "encoding")] {
+                let contents = Encoding::decode(bytes, options.encoding)?;
+            } else {
+                let contents = String::from_utf8(bytes)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            }
+        }
+        entity.set_contents(contents);
+        Ok(())
+    }
+
+    /// Opening a FIFO blocks until a writer opens it too, so it's opened without blocking and polled until its writer
+    /// closes it, `limit` bytes are read, or `timeout` (in seconds) elapses. No thread is left blocked on timeout.
+    ///
+    /// Reading a FIFO that no writer has opened yet also returns zero bytes, so the end of contents is detected by
+    /// [libc::POLLHUP] which is only reported after a writer closes it (e.g. `: > fifo` writes empty contents).
+    #[cfg(unix)]
+    fn read_fifo_with_timeout(path: &Path, limit: u64, timeout: u64) -> Result<Vec<u8>, io::Error> {
+        use std::{
+            os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+            time::{Duration, Instant},
+        };
+
+        let deadline = Instant::now() + Duration::from_secs(timeout);
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+        let mut bytes = Vec::new();
+        let mut buffer = [0; 8192];
+        while (bytes.len() as u64) < limit {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("FIFO is not written and closed in {timeout}s"),
+                ));
+            }
+            let mut poll_fd = libc::pollfd {
+                fd: file.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // Rounded up, so it does not wake up right before the deadline:
+            let poll_timeout = remaining
+                .as_millis()
+                .saturating_add(1)
+                .min(libc::c_int::MAX as u128) as libc::c_int;
+            // SAFETY: `poll_fd` is one valid `pollfd` of an open file descriptor which outlives the call.
+            match unsafe { libc::poll(&mut poll_fd, 1, poll_timeout) } {
+                -1 => {
+                    let error = io::Error::last_os_error();
+                    if error.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(error);
+                }
+                // Timed out:
+                0 => continue,
+                _ => (),
+            }
+            if poll_fd.revents & libc::POLLIN == 0 {
+                // The writer closed it without writing (more):
+                if poll_fd.revents & libc::POLLHUP != 0 {
+                    break;
+                }
+                return Err(io::Error::other(format!(
+                    "Could not poll FIFO (events {:#x})",
+                    poll_fd.revents
+                )));
+            }
+            match file.read(&mut buffer) {
+                // The writer closed it:
+                Ok(0) => break,
+                Ok(size) => bytes.extend_from_slice(&buffer[..size]),
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+                    ) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Returns `Ok(false)` if the entity file after `offset` (of `tail` option) is larger than `max_file_size` and
+    /// it's a skipped soft error.
+    pub fn check_entity_size(
+        entity: &ConfigurationEntity,
+        offset: u64,
+        options: &FsOptions,
+        skip_soft_errors: bool,
+    ) -> Result<bool, Error> {
+        let Some(max_file_size) = options.max_file_size else {
+            return Ok(true);
+        };
+        let size = match fs::metadata(Self::entity_path(entity)) {
+            Ok(metadata) => metadata.len().saturating_sub(offset),
+            Err(error) => {
+                return Error::from_io(
+                    NAME,
+                    entity.url(),
+                    "read metadata of file",
+                    error,
+                    &options.soft_errors,
+                    skip_soft_errors,
+                )
+                .map(|_| false)
+            }
+        };
+        if size <= max_file_size {
+            return Ok(true);
+        }
+        if skip_soft_errors
+            && (options.soft_errors.skip_all()
+                || options.soft_errors.contains(&SoftErrorsFs::TooLarge))
+        {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::info!(
+                        url=%entity.url(),
+                        size=size,
+                        max_file_size=max_file_size,
+                        skip_error=true,
+                        "Configuration file is too large"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::info!(
+                        "msg=\"Configuration file is too large\" url=\"{}\" size={size} max_file_size={max_file_size} skip_error=true",
+                        entity.url()
+                    );
+                }
+            }
+            Ok(false)
+        } else {
+            Err(Error::Load {
+                loader: NAME.to_string(),
+                url: entity.url().clone(),
+                description: "read too large file".to_string().into(),
+                source: anyhow!(
+                    "File size is {size} bytes but `max-file-size` is {max_file_size} bytes"
+                ),
+            })
+        }
+    }
+
+    /// Returns `tail_offset` if the file is still the one that is read until it, otherwise the beginning of the file
+    /// (e.g. it's replaced, truncated, or rewritten).
+    fn validate_tail_offset(path: &Path, tail_offset: TailOffset) -> Result<TailOffset, io::Error> {
+        let mut file = fs::File::open(path)?;
+        let metadata = file.metadata()?;
+        if metadata.len() < tail_offset.offset || Self::inode(&metadata) != tail_offset.maybe_inode
+        {
+            return Ok(TailOffset::default());
+        }
+        let mut last_byte_list = vec![0; tail_offset.last_byte_list.len()];
+        file.seek(SeekFrom::Start(
+            tail_offset.offset - last_byte_list.len() as u64,
+        ))?;
+        file.read_exact(&mut last_byte_list)?;
+        if last_byte_list == tail_offset.last_byte_list {
+            Ok(tail_offset)
+        } else {
+            Ok(TailOffset::default())
+        }
+    }
+
+    fn inode(_metadata: &fs::Metadata) -> Option<u64> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                use std::os::unix::fs::MetadataExt;
+
+                Some(_metadata.ino())
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Reads complete lines of the entity file which are appended after `tail_offset` and returns the new offset.
+    fn read_entity_tail(
+        entity: &mut ConfigurationEntity,
+        tail_offset: TailOffset,
+    ) -> Result<TailOffset, io::Error> {
+        let mut file = fs::File::open(Self::entity_path(entity))?;
+        let maybe_inode = Self::inode(&file.metadata()?);
+        file.seek(SeekFrom::Start(tail_offset.offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        // An incomplete last line is read next time:
+        let length = bytes
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        bytes.truncate(length);
+        if bytes.is_empty() {
+            return Ok(TailOffset {
+                maybe_inode,
+                ..tail_offset
+            });
+        }
+        let last_byte_list = bytes[bytes.len().saturating_sub(16)..].to_vec();
+        let contents = String::from_utf8(bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        entity.set_contents(contents);
+        Ok(TailOffset {
+            offset: tail_offset.offset + length as u64,
+            maybe_inode,
+            last_byte_list,
+        })
+    }
+
     #[inline]
     pub fn url_to_path(url: &Url, options: &FsOptions) -> Result<PathBuf, io::Error> {
         cfg_if! {
@@ -342,7 +1178,17 @@ impl Fs {
                 let is_windows = false;
             }
         }
-        let url_path = if url.path() == "/" || url.path().is_empty() {
+        // URL paths are percent-encoded (e.g. `/my%20app` for `/my app`):
+        let url_path = percent_decode_str(url.path())
+            .decode_utf8()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let url_path = if options.expand_env.unwrap_or(false) {
+            expand_env_vars(url_path)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?
+        } else {
+            url_path.to_string()
+        };
+        let url_path = if url_path == "/" || url_path.is_empty() {
             let cwd = current_dir()?;
             cfg_if! {
                 if #[cfg(feature = "tracing")] {
@@ -352,16 +1198,15 @@ impl Fs {
                 }
             }
             cwd
-        } else if (options.strip_slash.unwrap_or(false) || is_windows)
-            && url.path().starts_with('/')
+        } else if (options.strip_slash.unwrap_or(false) || is_windows) && url_path.starts_with('/')
         {
             PathBuf::from(
-                url.path()
+                url_path
                     .strip_prefix('/')
                     .expect("URL path with length > 1"),
             )
         } else {
-            PathBuf::from(url.path())
+            PathBuf::from(url_path)
         };
         let mut path = PathBuf::new();
         url_path.components().for_each(|component| {
@@ -392,15 +1237,231 @@ impl Fs {
         self
     }
 
+    /// Expands environment-variables inside URL paths (e.g. `file:$HOME/.config/app`).
+    pub fn set_expand_env(&mut self, expand_env: bool) {
+        self.options.expand_env = Some(expand_env);
+    }
+
+    /// Expands environment-variables inside URL paths (e.g. `file:$HOME/.config/app`).
+    pub fn with_expand_env(mut self, expand_env: bool) -> Self {
+        self.set_expand_env(expand_env);
+        self
+    }
+
+    /// Files larger than `stream_threshold` bytes are not read into memory and are parsed directly from the file.
+    pub fn set_stream_threshold(&mut self, stream_threshold: u64) {
+        self.options.stream_threshold = Some(stream_threshold);
+    }
+
+    /// Files larger than `stream_threshold` bytes are not read into memory and are parsed directly from the file.
+    pub fn with_stream_threshold(mut self, stream_threshold: u64) -> Self {
+        self.set_stream_threshold(stream_threshold);
+        self
+    }
+
+    /// Does not read files larger than `max_file_size` bytes.
+    pub fn set_max_file_size(&mut self, max_file_size: u64) {
+        self.options.max_file_size = Some(max_file_size);
+    }
+
+    /// Does not read files larger than `max_file_size` bytes.
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.set_max_file_size(max_file_size);
+        self
+    }
+
+    /// Detects plugin names of files via `plugin_name_fn` instead of their lowercased file stem.
+    ///
+    /// Files that it returns `None` for are skipped. The result is lowercased.
+    pub fn set_plugin_name_fn<F>(&mut self, plugin_name_fn: F)
+    where
+        F: Fn(&Path) -> Option<String> + Send + Sync + 'static,
+    {
+        self.maybe_plugin_name_fn = Some(Arc::new(plugin_name_fn));
+    }
+
+    /// Detects plugin names of files via `plugin_name_fn` instead of their lowercased file stem.
+    pub fn with_plugin_name_fn<F>(mut self, plugin_name_fn: F) -> Self
+    where
+        F: Fn(&Path) -> Option<String> + Send + Sync + 'static,
+    {
+        self.set_plugin_name_fn(plugin_name_fn);
+        self
+    }
+
+    /// Decodes files without a byte order mark with `encoding` (`encoding` feature).
+    #[cfg(feature = "encoding")]
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.options.encoding = Some(encoding);
+    }
+
+    /// Decodes files without a byte order mark with `encoding` (`encoding` feature).
+    #[cfg(feature = "encoding")]
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.set_encoding(encoding);
+        self
+    }
+
+    /// Only reads newly appended lines of files in next loads (see module documentation).
+    pub fn set_tail(&mut self, tail: bool) {
+        self.options.tail = Some(tail);
+    }
+
+    /// Only reads newly appended lines of files in next loads (see module documentation).
+    pub fn with_tail(mut self, tail: bool) -> Self {
+        self.set_tail(tail);
+        self
+    }
+
+    /// Also loads files of sub-directories.
+    pub fn set_recursive(&mut self, recursive: bool) {
+        self.options.recursive = Some(recursive);
+    }
+
+    /// Also loads files of sub-directories.
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.set_recursive(recursive);
+        self
+    }
+
+    /// Set it to `false` to skip symbolic links inside directories.
+    pub fn set_follow_symlinks(&mut self, follow_symlinks: bool) {
+        self.options.follow_symlinks = Some(follow_symlinks);
+    }
+
+    /// Set it to `false` to skip symbolic links inside directories.
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.set_follow_symlinks(follow_symlinks);
+        self
+    }
+
+    /// Loads files with non-UTF-8 names (see module documentation).
+    pub fn set_lossy_file_names(&mut self, lossy_file_names: bool) {
+        self.options.lossy_file_names = Some(lossy_file_names);
+    }
+
+    /// Loads files with non-UTF-8 names (see module documentation).
+    pub fn with_lossy_file_names(mut self, lossy_file_names: bool) -> Self {
+        self.set_lossy_file_names(lossy_file_names);
+        self
+    }
+
+    /// Loads each sub-directory as one plugin (see module documentation).
+    pub fn set_plugin_per_dir(&mut self, plugin_per_dir: bool) {
+        self.options.plugin_per_dir = Some(plugin_per_dir);
+    }
+
+    /// Loads each sub-directory as one plugin (see module documentation).
+    pub fn with_plugin_per_dir(mut self, plugin_per_dir: bool) -> Self {
+        self.set_plugin_per_dir(plugin_per_dir);
+        self
+    }
+
+    /// Seconds to wait for the writer of a FIFO (see module documentation).
+    pub fn set_fifo_timeout(&mut self, timeout_in_seconds: u64) {
+        self.options.fifo_timeout = Some(timeout_in_seconds);
+    }
+
+    /// Seconds to wait for the writer of a FIFO (see module documentation).
+    pub fn with_fifo_timeout(mut self, timeout_in_seconds: u64) -> Self {
+        self.set_fifo_timeout(timeout_in_seconds);
+        self
+    }
+
+    /// Forgets read offsets of `tail` option (of this loader and not its clones), so next load reads files from the
+    /// beginning.
+    pub fn reset_tail(&self) {
+        self.tail_offset_list
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear()
+    }
+
+    /// Where reading the entity file starts (it's the beginning if `tail` option is not set).
+    fn tail_offset(
+        &self,
+        entity: &ConfigurationEntity,
+        options: &FsOptions,
+    ) -> Result<TailOffset, io::Error> {
+        let path = Self::entity_path(entity);
+        if !options.tail.unwrap_or(false) || Self::is_fifo(&path) {
+            return Ok(TailOffset::default());
+        }
+        let maybe_tail_offset = self
+            .tail_offset_list
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&path)
+            .cloned();
+        maybe_tail_offset
+            .map(|tail_offset| Self::validate_tail_offset(&path, tail_offset))
+            .unwrap_or_else(|| Ok(TailOffset::default()))
+    }
+
+    fn read_entity_contents_with(
+        &self,
+        entity: &mut ConfigurationEntity,
+        tail_offset: TailOffset,
+        options: &FsOptions,
+    ) -> Result<(), io::Error> {
+        // FIFOs can not be streamed from or seeked:
+        if Self::is_fifo(Self::entity_path(entity)) {
+            return Self::read_entity_fifo(entity, options);
+        }
+        if !options.tail.unwrap_or(false) {
+            return Self::read_or_stream_entity_contents(entity, options);
+        }
+        let tail_offset = Self::read_entity_tail(entity, tail_offset)?;
+        self.tail_offset_list
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(Self::entity_path(entity), tail_offset);
+        Ok(())
+    }
+
     fn get_options(&self, url: &Url) -> Result<FsOptions, Error> {
-        loader::deserialize_query_string::<FsOptions>(NAME, url).map(|mut options| {
-            if let Some(soft_errors) = self.options.soft_errors.maybe_soft_error_list() {
-                soft_errors
-                    .iter()
-                    .for_each(|soft_error| options.soft_errors.add_soft_error(*soft_error))
-            }
-            options
-        })
+        let mut options = loader::deserialize_query_string::<FsOptions>(NAME, url)?;
+        #[cfg(not(feature = "encoding"))]
+        if let Some(encoding) = options.encoding.as_ref() {
+            return Err(Error::InvalidUrl {
+                loader: NAME.to_string(),
+                url: url.to_string(),
+                source: anyhow!("Option `encoding={encoding}` needs `encoding` feature"),
+            });
+        }
+        if self.options.expand_env.is_some() {
+            options.expand_env = self.options.expand_env;
+        }
+        if self.options.stream_threshold.is_some() {
+            options.stream_threshold = self.options.stream_threshold;
+        }
+        if self.options.max_file_size.is_some() {
+            options.max_file_size = self.options.max_file_size;
+        }
+        #[cfg(feature = "encoding")]
+        if self.options.encoding.is_some() {
+            options.encoding = self.options.encoding;
+        }
+        if self.options.tail.is_some() {
+            options.tail = self.options.tail;
+        }
+        if self.options.recursive.is_some() {
+            options.recursive = self.options.recursive;
+        }
+        if self.options.follow_symlinks.is_some() {
+            options.follow_symlinks = self.options.follow_symlinks;
+        }
+        if self.options.plugin_per_dir.is_some() {
+            options.plugin_per_dir = self.options.plugin_per_dir;
+        }
+        if self.options.lossy_file_names.is_some() {
+            options.lossy_file_names = self.options.lossy_file_names;
+        }
+        if self.options.fifo_timeout.is_some() {
+            options.fifo_timeout = self.options.fifo_timeout;
+        }
+        options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+        Ok(options)
     }
 }
 
@@ -416,6 +1477,28 @@ impl Loader for Fs {
         SCHEME_LIST.iter().cloned().map(String::from).collect()
     }
 
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    /// Lists files without reading them.
+    fn plan(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        let options = self.get_options(url)?;
+        Self::get_entity_list(
+            url,
+            &options,
+            self.maybe_plugin_name_fn.as_ref(),
+            maybe_whitelist,
+            skip_soft_errors,
+        )
+        .map(Some)
+    }
+
     fn load(
         &self,
         url: &Url,
@@ -423,10 +1506,38 @@ impl Loader for Fs {
         skip_soft_errors: bool,
     ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
         let options = self.get_options(url)?;
-        let mut entity_list =
-            Self::get_entity_list(url, &options, maybe_whitelist, skip_soft_errors)?;
+        let mut entity_list = Self::get_entity_list(
+            url,
+            &options,
+            self.maybe_plugin_name_fn.as_ref(),
+            maybe_whitelist,
+            skip_soft_errors,
+        )?;
         entity_list.iter_mut().try_for_each(|entity| {
-            match Self::read_entity_contents(entity) {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!(
+                "read",
+                plugin = entity.plugin_name().as_str(),
+                path = entity.url().path()
+            )
+            .entered();
+            let tail_offset = match self.tail_offset(entity, &options) {
+                Ok(tail_offset) => tail_offset,
+                Err(error) => {
+                    return Error::from_io(
+                        NAME,
+                        entity.url(),
+                        "read offset of file",
+                        error,
+                        &options.soft_errors,
+                        skip_soft_errors,
+                    )
+                }
+            };
+            if !Self::check_entity_size(entity, tail_offset.offset, &options, skip_soft_errors)? {
+                return Ok(());
+            }
+            match self.read_entity_contents_with(entity, tail_offset, &options) {
                 Ok(_) => {
                     cfg_if! {
                         if #[cfg(feature = "tracing")] {
@@ -434,53 +1545,54 @@ impl Loader for Fs {
                                 url=%entity.url(),
                                 contents=entity
                                     .maybe_contents()
-                                    .expect("Contents is set inside `utils::read_entity_contents`"),
+                                    .map(|contents| self.log_options.contents(contents)),
+                                streamed=entity.maybe_contents().is_none(),
                                 "Read configuration file"
                             );
                         } else if #[cfg(feature = "logging")] {
                             log::trace!(
-                                "msg=\"Read configuration file\" url={:?} contents={:?}",
+                                "msg=\"Read configuration file\" url={:?} contents={:?} streamed={}",
                                 entity.url().to_string(),
-                                entity.maybe_contents().expect("Contents is set inside `utils::read_entity_contents`")
+                                entity
+                                    .maybe_contents()
+                                    .map(|contents| self.log_options.contents(contents)),
+                                entity.maybe_contents().is_none()
                             );
                         }
                     }
                     Ok(())
                 },
-                Err(error) => {
-                    if skip_soft_errors && (options.soft_errors.skip_all() || options.contains(error.kind())) {
-                        cfg_if! {
-                            if #[cfg(feature = "tracing")] {
-                                tracing::info!(
-                                    path=entity.url().path(),
-                                    skip_error=true,
-                                    "Could not read contents of file"
-                                );
-                            } else if #[cfg(feature = "logging")] {
-                                log::info!(
-                                    "msg=\"Could not read contents of file\" path={:?} skip_error=true",
-                                    entity.url().path()
-                                );
-                            }
-                        }
-                        Ok(())
-                    } else {
-                        Err(Error::Load {
-                            loader: NAME.to_string(),
-                            url: entity.url().clone(),
-                            description: "read contents of file".to_string().into(),
-                            source: error.into(),
-                        })
-                    }
-                }
+                Err(error) if error.kind() == io::ErrorKind::TimedOut => Err(Error::Timeout {
+                    loader: NAME.to_string(),
+                    url: entity.url().clone(),
+                    timeout_in_seconds: options.fifo_timeout.unwrap_or_default() as usize,
+                }),
+                Err(error) => Error::from_io(
+                    NAME,
+                    entity.url(),
+                    "read contents of file",
+                    error,
+                    &options.soft_errors,
+                    skip_soft_errors,
+                ),
             }
         })?;
         let result = entity_list
             .into_iter()
             // Maybe we have skipped soft errors in above:
-            .filter(|entity| entity.maybe_contents().is_some())
+            .filter(|entity| {
+                entity.maybe_contents().is_some() || entity.maybe_contents_reader().is_some()
+            })
             .map(|entity| (entity.plugin_name().clone(), entity))
             .collect();
         Ok(result)
     }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.log_options = log_options;
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
 }
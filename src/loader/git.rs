@@ -0,0 +1,454 @@
+//! Git repository configuration loader (`git` feature).
+//!
+//! * Supported schema: `git`, `git+https`, `git+ssh`, and `git+file`
+//! * The URL without `git+` prefix and query string is the remote repository (e.g.
+//!   `git+https://github.com/my-org/config.git?ref=v1.2.0&path=my-app`).
+//! * The reference is shallow-fetched (via libgit2) into a cache directory and checked out, and then the [Fs] loader
+//!   loads configurations from the checked out files. An existing clone is reused and updated on every load (e.g.
+//!   reload).
+//! * Options: `ref` (a branch, tag, or full commit hash, default `HEAD`), `path` (a directory or file inside the
+//!   repository, default is the repository root; paths that point outside of the repository are rejected),
+//!   `cache-dir` (default `plugx-config-git` inside the per-user cache directory, e.g. `$XDG_CACHE_HOME`,
+//!   `~/.cache`, or `%LOCALAPPDATA%`), and `soft-errors` (`not-found` for a missing repository, reference, or path
+//!   and `no-access` for authentication failures).
+//! * On Unix, directories of clones are only accessible by the current user (mode `0700`).
+//! * Credentials are taken from the SSH agent, git credential helpers, or the default credentials of the platform
+//!   and it never prompts for them.
+//! * Do not load the same repository from more than one process or thread at the same time since they share the
+//!   same clone.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use plugx_config::{Configuration, Url};
+//!
+//! let configuration = Configuration::new()
+//!     .with_url("git+https://github.com/my-org/config.git?ref=main&path=my-app".parse().unwrap())
+//!     .unwrap();
+//! let merged = configuration.load_parse_merge(false).unwrap();
+//! ```
+//!
+//! See [loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{
+        self,
+        fs::{Fs, SoftErrorsFs},
+        Error, Loader, SoftErrors,
+    },
+};
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use git2::{
+    build::CheckoutBuilder, Cred, CredentialType, Direction, ErrorClass, ErrorCode, FetchOptions,
+    RemoteCallbacks, Repository,
+};
+use serde::Deserialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+pub const NAME: &str = "Git";
+pub const SCHEME_LIST: &[&str] = &["git", "git+https", "git+ssh", "git+file"];
+
+/// Loads configurations from a git repository.
+#[derive(Debug, Default, Clone)]
+pub struct Git {
+    options: GitOptions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct GitOptions {
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+    path: Option<String>,
+    cache_dir: Option<PathBuf>,
+    soft_errors: SoftErrors<SoftErrorsGit>,
+}
+
+/// Supported soft errors when loading from a git repository.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoftErrorsGit {
+    NotFound,
+    NoAccess,
+}
+
+impl Git {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Branch, tag, or commit hash to check out.
+    pub fn set_reference<R: AsRef<str>>(&mut self, reference: R) {
+        self.options.reference = Some(reference.as_ref().to_string());
+    }
+
+    /// Branch, tag, or commit hash to check out.
+    pub fn with_reference<R: AsRef<str>>(mut self, reference: R) -> Self {
+        self.set_reference(reference);
+        self
+    }
+
+    /// Directory or file inside the repository to load configurations from.
+    pub fn set_path<P: AsRef<str>>(&mut self, path: P) {
+        self.options.path = Some(path.as_ref().to_string());
+    }
+
+    /// Directory or file inside the repository to load configurations from.
+    pub fn with_path<P: AsRef<str>>(mut self, path: P) -> Self {
+        self.set_path(path);
+        self
+    }
+
+    /// Directory to keep clones of repositories in.
+    pub fn set_cache_dir<P: AsRef<Path>>(&mut self, cache_dir: P) {
+        self.options.cache_dir = Some(cache_dir.as_ref().to_path_buf());
+    }
+
+    /// Directory to keep clones of repositories in.
+    pub fn with_cache_dir<P: AsRef<Path>>(mut self, cache_dir: P) -> Self {
+        self.set_cache_dir(cache_dir);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsGit) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsGit) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<GitOptions, Error> {
+        loader::deserialize_query_string::<GitOptions>(NAME, url).map(|mut options| {
+            if self.options.reference.is_some() {
+                options.reference = self.options.reference.clone();
+            }
+            if self.options.path.is_some() {
+                options.path = self.options.path.clone();
+            }
+            if self.options.cache_dir.is_some() {
+                options.cache_dir = self.options.cache_dir.clone();
+            }
+            options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+            options
+        })
+    }
+
+    /// Removes `git+` prefix, query string, and fragment of the URL.
+    fn remote_url(url: &Url) -> String {
+        let mut remote_url = url.clone();
+        remote_url.set_query(None);
+        remote_url.set_fragment(None);
+        let remote_url = remote_url.to_string();
+        remote_url
+            .strip_prefix("git+")
+            .map(String::from)
+            .unwrap_or(remote_url)
+    }
+
+    /// Per-user cache directory (e.g. `$XDG_CACHE_HOME`, `~/.cache`, or `%LOCALAPPDATA%`) or the temporary
+    /// directory if there is none.
+    fn default_cache_dir() -> PathBuf {
+        env::var_os("XDG_CACHE_HOME")
+            .filter(|cache_dir| !cache_dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                if cfg!(windows) {
+                    env::var_os("LOCALAPPDATA").map(PathBuf::from)
+                } else {
+                    env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache"))
+                }
+            })
+            .unwrap_or_else(env::temp_dir)
+            .join("plugx-config-git")
+    }
+
+    fn repository_dir(remote_url: &str, options: &GitOptions) -> PathBuf {
+        let cache_dir = options
+            .cache_dir
+            .clone()
+            .unwrap_or_else(Self::default_cache_dir);
+        let cache_dir = if cache_dir.is_relative() {
+            env::current_dir()
+                .map(|current_dir| current_dir.join(&cache_dir))
+                .unwrap_or(cache_dir)
+        } else {
+            cache_dir
+        };
+        // FNV-1a of the remote URL:
+        let hash = remote_url
+            .as_bytes()
+            .iter()
+            .fold(0xcbf29ce484222325_u64, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+            });
+        cache_dir.join(format!("{hash:016x}"))
+    }
+
+    /// Creates the directory (and its missing parents) which is only accessible by the current user on Unix.
+    fn create_private_dir(dir: &Path) -> Result<(), io::Error> {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        cfg_if! {
+            if #[cfg(unix)] {
+                use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+                builder.mode(0o700).create(dir)?;
+                // An existing directory may have been created with other permissions:
+                fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            } else {
+                builder.create(dir)
+            }
+        }
+    }
+
+    /// Maps libgit2 error codes to soft errors.
+    fn soft_error(error: &git2::Error) -> Option<SoftErrorsGit> {
+        match error.code() {
+            ErrorCode::NotFound => Some(SoftErrorsGit::NotFound),
+            ErrorCode::Auth | ErrorCode::Certificate => Some(SoftErrorsGit::NoAccess),
+            _ => None,
+        }
+    }
+
+    /// Tries each kind of credentials once, so rejected ones yield [ErrorCode::Auth] instead of being retried.
+    fn remote_callbacks() -> RemoteCallbacks<'static> {
+        let maybe_config = git2::Config::open_default().ok();
+        let mut tried = CredentialType::empty();
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |url, maybe_username, allowed| {
+            if allowed.contains(CredentialType::SSH_KEY) && !tried.contains(CredentialType::SSH_KEY)
+            {
+                tried.insert(CredentialType::SSH_KEY);
+                return Cred::ssh_key_from_agent(maybe_username.unwrap_or("git"));
+            }
+            if allowed.contains(CredentialType::USER_PASS_PLAINTEXT)
+                && !tried.contains(CredentialType::USER_PASS_PLAINTEXT)
+            {
+                tried.insert(CredentialType::USER_PASS_PLAINTEXT);
+                if let Some(config) = maybe_config.as_ref() {
+                    return Cred::credential_helper(config, url, maybe_username);
+                }
+            }
+            if allowed.contains(CredentialType::DEFAULT) && !tried.contains(CredentialType::DEFAULT)
+            {
+                tried.insert(CredentialType::DEFAULT);
+                return Cred::default();
+            }
+            Err(git2::Error::new(
+                ErrorCode::Auth,
+                ErrorClass::Callback,
+                "No usable credentials",
+            ))
+        });
+        callbacks
+    }
+
+    /// Clones (or updates the existing clone of) the repository and checks out the reference.
+    fn sync(repository_dir: &Path, remote_url: &str, reference: &str) -> Result<(), git2::Error> {
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::trace!(dir=?repository_dir, remote=remote_url, reference=reference, "Fetching git repository");
+            } else if #[cfg(feature = "logging")] {
+                log::trace!(
+                    "msg=\"Fetching git repository\" dir={repository_dir:?} remote={remote_url:?} reference={reference:?}"
+                );
+            }
+        }
+        let repository = match Repository::open(repository_dir) {
+            Ok(repository) => repository,
+            Err(_) => {
+                Self::create_private_dir(repository_dir).map_err(|error| {
+                    git2::Error::new(
+                        ErrorCode::GenericError,
+                        ErrorClass::Os,
+                        format!("Could not create directory {repository_dir:?} ({error})"),
+                    )
+                })?;
+                Repository::init(repository_dir)?
+            }
+        };
+        let mut remote = repository.remote_anonymous(remote_url)?;
+        let maybe_reference_name = {
+            let connection =
+                remote.connect_auth(Direction::Fetch, Some(Self::remote_callbacks()), None)?;
+            let name_list = [
+                reference.to_string(),
+                format!("refs/heads/{reference}"),
+                format!("refs/tags/{reference}"),
+            ];
+            let maybe_reference_name = connection
+                .list()?
+                .iter()
+                .find(|head| name_list.iter().any(|name| name == head.name()))
+                .map(|head| head.name().to_string());
+            maybe_reference_name
+        };
+        let refspec = match maybe_reference_name {
+            Some(reference_name) => reference_name,
+            // Commit hashes are not listed:
+            None if reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit()) => {
+                reference.to_string()
+            }
+            None => {
+                return Err(git2::Error::new(
+                    ErrorCode::NotFound,
+                    ErrorClass::Reference,
+                    format!("Could not find git reference `{reference}`"),
+                ))
+            }
+        };
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(Self::remote_callbacks());
+        // The local transport of libgit2 does not support shallow fetches:
+        if !remote_url.starts_with("file:") {
+            fetch_options.depth(1);
+        }
+        remote.fetch(&[refspec.as_str()], Some(&mut fetch_options), None)?;
+        let commit = repository.find_reference("FETCH_HEAD")?.peel_to_commit()?;
+        repository.set_head_detached(commit.id())?;
+        repository.checkout_head(Some(CheckoutBuilder::new().force()))
+    }
+
+    /// Resolves the `path` option inside the clone and rejects paths outside of it.
+    fn resolve_path(repository_dir: &Path, maybe_path: Option<&str>) -> Result<PathBuf, io::Error> {
+        let repository_dir = repository_dir.canonicalize()?;
+        let Some(path) = maybe_path else {
+            return Ok(repository_dir);
+        };
+        let path = repository_dir
+            .join(path.trim_start_matches('/'))
+            .canonicalize()?;
+        if path.starts_with(&repository_dir) {
+            Ok(path)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Path {path:?} is outside of the repository"),
+            ))
+        }
+    }
+}
+
+impl Display for Git {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(NAME)
+    }
+}
+
+impl Loader for Git {
+    /// In this case `["git", "git+https", "git+ssh", "git+file"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SCHEME_LIST.iter().cloned().map(String::from).collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        let remote_url = Self::remote_url(url);
+        let repository_dir = Self::repository_dir(remote_url.as_str(), &options);
+        let reference = options.reference.as_deref().unwrap_or("HEAD");
+        let skip_soft_error = |soft_error: SoftErrorsGit| {
+            skip_soft_errors
+                && (options.soft_errors.skip_all() || options.soft_errors.contains(&soft_error))
+        };
+        if let Err(error) = Self::sync(&repository_dir, remote_url.as_str(), reference) {
+            return match Self::soft_error(&error) {
+                Some(soft_error) if skip_soft_error(soft_error) => {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::info!(url=%url, error=%error, skip_error=true, "Could not fetch git repository");
+                        } else if #[cfg(feature = "logging")] {
+                            log::info!(
+                                "msg=\"Could not fetch git repository\" url=\"{url}\" error={:?} skip_error=true",
+                                error.to_string()
+                            );
+                        }
+                    }
+                    Ok(Vec::new())
+                }
+                Some(SoftErrorsGit::NoAccess) => Err(Error::NoAccess {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                }),
+                Some(SoftErrorsGit::NotFound) => Err(Error::NotFound {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    item: format!("git reference `{reference}`").into(),
+                }),
+                None => Err(Error::Load {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    description: "fetch git repository".to_string().into(),
+                    source: error.into(),
+                }),
+            };
+        }
+        let path = match Self::resolve_path(&repository_dir, options.path.as_deref()) {
+            Ok(path) => path,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return if skip_soft_error(SoftErrorsGit::NotFound) {
+                    Ok(Vec::new())
+                } else {
+                    Err(Error::NotFound {
+                        loader: NAME.to_string(),
+                        url: url.clone(),
+                        item: format!("path `{}`", options.path.as_deref().unwrap_or_default())
+                            .into(),
+                    })
+                };
+            }
+            Err(error) if error.kind() == io::ErrorKind::InvalidInput => {
+                return Err(Error::InvalidUrl {
+                    loader: NAME.to_string(),
+                    url: url.to_string(),
+                    source: error.into(),
+                })
+            }
+            Err(error) => {
+                return Err(Error::Load {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    description: "resolve path inside git repository".to_string().into(),
+                    source: error.into(),
+                })
+            }
+        };
+        let fs_url = Url::from_file_path(&path).map_err(|_| Error::InvalidUrl {
+            loader: NAME.to_string(),
+            url: url.to_string(),
+            source: anyhow!("Could not make a file URL from {path:?}"),
+        })?;
+        let mut fs_loader = Fs::new();
+        if skip_soft_error(SoftErrorsGit::NotFound) {
+            fs_loader.add_soft_error(SoftErrorsFs::NotFound);
+        }
+        Ok(fs_loader
+            .load(&fs_url, maybe_whitelist, skip_soft_errors)?
+            .into_iter()
+            .map(|(plugin_name, mut entity)| {
+                *entity.url_mut() = url.clone();
+                (plugin_name, entity)
+            })
+            .collect())
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -0,0 +1,208 @@
+//! A loader wrapper that changes results of another loader.
+//!
+//! ### Example
+//! ```rust
+//! use plugx_config::{
+//!     entity::ConfigurationEntity,
+//!     loader::{Loader, closure::Closure, map::MappedLoader},
+//!     ext::url::Url,
+//! };
+//!
+//! let loader_fn = |url: &Url, _: Option<&[String]>, _: bool| {
+//!     Ok([
+//!         ("foo", "yml", "hello: world"),
+//!         ("bar", "json", "{\"hello\": \"world\"}"),
+//!     ]
+//!     .into_iter()
+//!     .map(|(plugin_name, format, contents)| {
+//!         let entity = ConfigurationEntity::new(plugin_name, url.clone(), plugin_name, "legacy")
+//!             .with_format(format)
+//!             .with_contents(contents);
+//!         (plugin_name.to_string(), entity)
+//!     })
+//!     .collect())
+//! };
+//! let legacy_loader = Closure::new("legacy", Box::new(loader_fn), "legacy");
+//! // Drop JSON configurations and prefix all plugin names with `legacy_`:
+//! let loader = MappedLoader::new(legacy_loader, Box::new(|loaded| {
+//!     loaded
+//!         .into_iter()
+//!         .filter(|(_, entity)| entity.maybe_format() != Some(&"json".to_string()))
+//!         .map(|(plugin_name, mut entity)| {
+//!             let plugin_name = format!("legacy_{plugin_name}");
+//!             *entity.plugin_name_mut() = plugin_name.clone();
+//!             (plugin_name, entity)
+//!         })
+//!         .collect()
+//! }));
+//! assert_eq!(loader.scheme_list(), vec!["legacy".to_string()]);
+//! assert_eq!(loader.name(), "legacy");
+//!
+//! let url = "legacy://".parse().unwrap();
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! assert_eq!(loaded.len(), 1);
+//! assert_eq!(loaded[0].0, "legacy_foo");
+//!
+//! // Whitelists contain mapped plugin names:
+//! let whitelist = ["legacy_foo".to_string()];
+//! assert_eq!(loader.load(&url, Some(&whitelist), false).unwrap().len(), 1);
+//! let whitelist = ["foo".to_string()];
+//! assert!(loader.load(&url, Some(&whitelist), false).unwrap().is_empty());
+//! ```
+//!
+//! See [crate::loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{Error, Loader},
+    LogOptions,
+};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::Arc,
+};
+use url::Url;
+
+/// A `|Vec<(String, ConfigurationEntity)>| -> Vec<(String, ConfigurationEntity)>` [Fn]
+pub type BoxedMapFn = Box<
+    dyn Fn(Vec<(String, ConfigurationEntity)>) -> Vec<(String, ConfigurationEntity)> + Send + Sync,
+>;
+
+/// [BoxedMapFn] that is shared between clones of a [MappedLoader].
+type SharedMapFn = Arc<
+    dyn Fn(Vec<(String, ConfigurationEntity)>) -> Vec<(String, ConfigurationEntity)> + Send + Sync,
+>;
+
+/// Applies a [Fn] to results of the inner loader.
+///
+/// The mapper may rename plugins, so the inner loader loads all plugins and the whitelist is applied to the mapped
+/// results.
+pub struct MappedLoader<L: Loader> {
+    loader: L,
+    mapper: SharedMapFn,
+}
+
+impl<L: Loader> Debug for MappedLoader<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedLoader")
+            .field("loader", &self.loader)
+            .finish()
+    }
+}
+
+impl<L: Loader> Display for MappedLoader<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.loader.name().as_str())
+    }
+}
+
+impl<L: Loader> MappedLoader<L> {
+    pub fn new(loader: L, mapper: BoxedMapFn) -> Self {
+        Self {
+            loader,
+            mapper: Arc::from(mapper),
+        }
+    }
+
+    pub fn set_mapper(&mut self, mapper: BoxedMapFn) {
+        self.mapper = Arc::from(mapper)
+    }
+
+    pub fn with_mapper(mut self, mapper: BoxedMapFn) -> Self {
+        self.set_mapper(mapper);
+        self
+    }
+
+    pub fn loader(&self) -> &L {
+        &self.loader
+    }
+
+    pub fn loader_mut(&mut self) -> &mut L {
+        &mut self.loader
+    }
+
+    pub fn into_loader(self) -> L {
+        self.loader
+    }
+
+    fn map(
+        &self,
+        loaded: Vec<(String, ConfigurationEntity)>,
+        maybe_whitelist: Option<&[String]>,
+    ) -> Vec<(String, ConfigurationEntity)> {
+        let mut loaded = (self.mapper)(loaded);
+        if let Some(whitelist) = maybe_whitelist {
+            loaded.retain(|(plugin_name, _)| whitelist.contains(plugin_name))
+        }
+        loaded
+    }
+}
+
+impl<L: Loader> Loader for MappedLoader<L> {
+    fn name(&self) -> String {
+        self.loader.name()
+    }
+
+    fn scheme_list(&self) -> Vec<String> {
+        self.loader.scheme_list()
+    }
+
+    fn matches_scheme(&self, scheme: &str) -> bool {
+        self.loader.matches_scheme(scheme)
+    }
+
+    fn supports_soft_errors(&self) -> bool {
+        self.loader.supports_soft_errors()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.loader.validate_url(url)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.loader.set_log_options(log_options)
+    }
+
+    /// Wraps a clone of the inner loader if it can be cloned.
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        self.loader.boxed_clone().map(|loader| {
+            Box::new(MappedLoader {
+                loader,
+                mapper: self.mapper.clone(),
+            }) as Box<dyn Loader>
+        })
+    }
+
+    /// Maps plans of the inner loader like its loaded configurations.
+    fn plan(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        let Some(entity_list) = self.loader.plan(url, None, skip_soft_errors)? else {
+            return Ok(None);
+        };
+        let planned = entity_list
+            .into_iter()
+            .map(|entity| (entity.plugin_name().clone(), entity))
+            .collect();
+        Ok(Some(
+            self.map(planned, maybe_whitelist)
+                .into_iter()
+                .map(|(_, entity)| entity)
+                .collect(),
+        ))
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        self.loader
+            .load(url, None, skip_soft_errors)
+            .map(|loaded| self.map(loaded, maybe_whitelist))
+    }
+}
@@ -14,19 +14,103 @@
 //!
 //! Note that generally you do not need to implement [Loader], provided [mod@closure] lets you make your
 //! own loader with just one [Fn] closure.
+//!
+//! ### Options of a custom loader
+//! Built-in loaders read their options from the URL query string and let them be set programmatically too. Below is
+//! the same pattern for your own loader (`qs` Cargo feature):
+//! * Derive [Default] and [Deserialize] for the options struct and use `#[serde(default)]` so options that are not in
+//!   the query string keep their default values.
+//! * Wrap optional options in [Option] so programmatically set ones can be told apart from unset ones.
+//! * Use [SoftErrors] for soft errors and [SoftErrors::merge] to combine ones from URL and programmatically set ones.
+//! * Deserialize options via [deserialize_query_string] (which also validates URLs in
+//!   [Loader::validate_url]).
+//!
+//! ```rust
+//! # #[cfg(feature = "qs")] {
+//! use plugx_config::{
+//!     ext::{serde::Deserialize, url::Url},
+//!     loader::{deserialize_query_string, Error, SoftErrors},
+//! };
+//!
+//! #[derive(Debug, Clone, PartialEq, Deserialize)]
+//! #[serde(rename_all = "kebab-case")]
+//! enum MySoftErrors {
+//!     NotFound,
+//!     Timeout,
+//! }
+//!
+//! #[derive(Debug, Clone, Default, Deserialize)]
+//! #[serde(default, rename_all = "kebab-case")]
+//! struct MyOptions {
+//!     timeout: Option<u64>,
+//!     soft_errors: SoftErrors<MySoftErrors>,
+//! }
+//!
+//! #[derive(Debug, Default)]
+//! struct MyLoader {
+//!     options: MyOptions,
+//! }
+//!
+//! impl MyLoader {
+//!     fn set_timeout(&mut self, timeout: u64) {
+//!         self.options.timeout = Some(timeout);
+//!     }
+//!
+//!     // Programmatically set options override the ones from the URL:
+//!     fn get_options(&self, url: &Url) -> Result<MyOptions, Error> {
+//!         deserialize_query_string("my-loader", url).map(|mut options: MyOptions| {
+//!             if self.options.timeout.is_some() {
+//!                 options.timeout = self.options.timeout;
+//!             }
+//!             options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+//!             options
+//!         })
+//!     }
+//! }
+//!
+//! let mut loader = MyLoader::default();
+//! let options = loader.get_options(&"my:///?soft-errors=not-found".parse().unwrap()).unwrap();
+//! assert_eq!(options.timeout, None);
+//! assert!(options.soft_errors.contains(&MySoftErrors::NotFound));
+//! loader.set_timeout(10);
+//! let options = loader.get_options(&"my:///?timeout=5".parse().unwrap()).unwrap();
+//! assert_eq!(options.timeout, Some(10));
+//! assert!(loader.get_options(&"my:///?timeout=soon".parse().unwrap()).is_err());
+//! # }
+//! ```
 
-use crate::entity::ConfigurationEntity;
+use crate::{entity::ConfigurationEntity, LogOptions};
+use cfg_if::cfg_if;
 use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::fmt::{Debug, Display};
+use std::io;
 use std::marker::PhantomData;
 use url::Url;
 
+#[cfg(feature = "aws")]
+pub mod aws;
+#[cfg(feature = "cache")]
+pub mod cached;
 pub mod closure;
+#[cfg(feature = "embedded")]
+pub mod embedded;
 #[cfg(feature = "env")]
 pub mod env;
 #[cfg(feature = "fs")]
 pub mod fs;
+#[cfg(feature = "git")]
+pub mod git;
+pub mod map;
+#[cfg(feature = "mongodb")]
+pub mod mongodb;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod schemes;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(all(unix, feature = "unix-socket"))]
+pub mod unix_socket;
 
 /// Load error type.
 #[derive(Debug, thiserror::Error)]
@@ -83,6 +167,56 @@ pub enum Error {
     Other(#[from] anyhow::Error),
 }
 
+impl Error {
+    /// Handles an [io::Error] of a filesystem-like loader based on its soft errors which are detected from the error
+    /// kind (e.g. `SoftErrorsFs` of [mod@fs] loader).
+    ///
+    /// Returns `Ok(())` if the error should be skipped, otherwise returns [Error::Load] which says `{loader} could not
+    /// {description} {url}`.
+    pub fn from_io<'de, T>(
+        loader: &str,
+        url: &Url,
+        description: &str,
+        error: io::Error,
+        soft_errors: &SoftErrors<T>,
+        skip_soft_errors: bool,
+    ) -> Result<(), Error>
+    where
+        T: Deserialize<'de> + PartialEq + TryFrom<io::ErrorKind>,
+    {
+        let is_soft_error = soft_errors.skip_all()
+            || T::try_from(error.kind())
+                .map(|error| soft_errors.contains(&error))
+                .unwrap_or_default();
+        if skip_soft_errors && is_soft_error {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::info!(
+                        loader=loader,
+                        url=%url,
+                        error=%error,
+                        skip_error=true,
+                        "Could not {description}"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::info!(
+                        "msg=\"Could not {description}\" loader={loader:?} url=\"{url}\" error={:?} skip_error=true",
+                        error.to_string()
+                    );
+                }
+            }
+            Ok(())
+        } else {
+            Err(Error::Load {
+                loader: loader.to_string(),
+                url: url.clone(),
+                description: description.to_string().into(),
+                source: error.into(),
+            })
+        }
+    }
+}
+
 /// Soft errors deserializer wrapper for URL query strings.
 ///
 /// ### Example
@@ -141,13 +275,42 @@ struct SoftErrorsVisitor<T> {
     _marker: PhantomData<T>,
 }
 
+impl<'de, T: Deserialize<'de>> SoftErrorsVisitor<T> {
+    fn from_part_list<E: serde::de::Error>(parts: Vec<String>) -> Result<SoftErrors<T>, E> {
+        if parts.contains(&"all".to_string()) {
+            Ok(SoftErrors::All)
+        } else {
+            Ok(SoftErrors::List(Vec::deserialize(
+                parts.into_deserializer(),
+            )?))
+        }
+    }
+}
+
 /// A trait to load configurations for one or more plugins.
 pub trait Loader: Send + Sync + Debug + Display {
+    /// Name of this loader which is used in logs and errors.
+    ///
+    /// Defaults to its [Display] output.
+    fn name(&self) -> String {
+        format!("{self}")
+    }
+
     /// List of URL schemes that this loader supports.
     ///
     /// Different URL may be assigned to this loader by their scheme value.
     fn scheme_list(&self) -> Vec<String>;
 
+    /// Whether URLs with `scheme` are assigned to this loader.
+    ///
+    /// Defaults to checking [Self::scheme_list]. Override it to match schemes by a pattern (e.g. any scheme ending in
+    /// `+config`).
+    fn matches_scheme(&self, scheme: &str) -> bool {
+        self.scheme_list()
+            .iter()
+            .any(|inner_scheme| inner_scheme == scheme)
+    }
+
     /// Main method that actually loads configurations.
     ///
     /// * Checks the `url` and detects its own options from it.
@@ -160,21 +323,153 @@ pub trait Loader: Send + Sync + Debug + Display {
         maybe_whitelist: Option<&[String]>,
         skip_soft_errors: bool,
     ) -> Result<Vec<(String, ConfigurationEntity)>, Error>;
+
+    /// Whether [Self::load] only returns configurations of plugins in its whitelist.
+    ///
+    /// Defaults to `true`. If it's `false`, [crate::Configuration] drops configurations of other plugins after loading.
+    fn supports_whitelist(&self) -> bool {
+        true
+    }
+
+    /// Whether [Self::load] honors `skip_soft_errors`.
+    ///
+    /// Defaults to `true`. If it's `false`, [crate::Configuration] logs a warning (once per process) when `skip_soft_errors`
+    /// is `true`.
+    fn supports_soft_errors(&self) -> bool {
+        true
+    }
+
+    /// Checks the URL (e.g. its query string options) without loading anything.
+    ///
+    /// [crate::Configuration::add_url] calls it to reject invalid URLs early. Defaults to `Ok(())`.
+    fn validate_url(&self, _url: &Url) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Sets options of trace logs (see [crate::Configuration::set_trace_contents]).
+    ///
+    /// Defaults to ignoring them.
+    fn set_log_options(&mut self, _log_options: LogOptions) {}
+
+    /// Clones this loader (see [crate::Configuration::subset]).
+    ///
+    /// Defaults to [None] which means the loader can not be cloned. [Clone] loaders implement it as
+    /// `Some(Box::new(self.clone()))`.
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        None
+    }
+
+    /// Lists configurations that [Self::load] would load, without reading their contents.
+    ///
+    /// Yields [None] (which is the default) if the loader can not list them without actually loading them.
+    fn plan(
+        &self,
+        _url: &Url,
+        _maybe_whitelist: Option<&[String]>,
+        _skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        Ok(None)
+    }
 }
 
+/// Lets loader wrappers (e.g. [schemes::Schemes]) wrap boxed clones of their inner loaders.
+impl Loader for Box<dyn Loader> {
+    fn name(&self) -> String {
+        self.as_ref().name()
+    }
+
+    fn scheme_list(&self) -> Vec<String> {
+        self.as_ref().scheme_list()
+    }
+
+    fn matches_scheme(&self, scheme: &str) -> bool {
+        self.as_ref().matches_scheme(scheme)
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        self.as_ref().load(url, maybe_whitelist, skip_soft_errors)
+    }
+
+    fn supports_whitelist(&self) -> bool {
+        self.as_ref().supports_whitelist()
+    }
+
+    fn supports_soft_errors(&self) -> bool {
+        self.as_ref().supports_soft_errors()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.as_ref().validate_url(url)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.as_mut().set_log_options(log_options)
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        self.as_ref().boxed_clone()
+    }
+
+    fn plan(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        self.as_ref().plan(url, maybe_whitelist, skip_soft_errors)
+    }
+}
+
+/// Former name of [Loader].
+///
+/// It is implemented for every [Loader], so it still works as a bound (e.g. `T: ConfigurationLoader`). Implement
+/// [Loader] itself for new types.
+#[deprecated(since = "0.3.1", note = "Use `plugx_config::loader::Loader` instead")]
+pub trait ConfigurationLoader: Loader {}
+
+#[allow(deprecated)]
+impl<T: Loader + ?Sized> ConfigurationLoader for T {}
+
+/// Former name of [Error].
+#[deprecated(since = "0.3.1", note = "Use `plugx_config::loader::Error` instead")]
+pub type ConfigurationLoadError = Error;
+
 #[cfg(feature = "qs")]
 /// Checks query-string part of URL and tries to deserialize it to provided type. (`qs` Cargo feature)
 ///
+/// Supported query syntax:
+/// * Flat options: `?strip-slash=true&stream-threshold=1024`
+/// * Lists in bracket style (brackets may be percent-encoded): `?soft-errors[]=not-found&soft-errors[]=no-access` or
+///   `?soft-errors[0]=not-found&soft-errors[1]=no-access`
+/// * Nested options: `?options[key]=value`
+/// * [SoftErrors] also accepts `all` or dot separated values: `?soft-errors=not-found.no-access`
+///
 /// For usage example see [SoftErrors].
 pub fn deserialize_query_string<T: serde::de::DeserializeOwned>(
     loader_name: impl AsRef<str>,
     url: &Url,
 ) -> Result<T, Error> {
-    serde_qs::from_str(url.query().unwrap_or_default()).map_err(|error| Error::InvalidUrl {
-        loader: loader_name.as_ref().to_string(),
-        source: error.into(),
-        url: url.to_string(),
-    })
+    // Non-strict mode also accepts percent-encoded brackets (e.g. `soft-errors%5B%5D=not-found`):
+    serde_qs::Config::new(5, false)
+        .deserialize_str(url.query().unwrap_or_default())
+        .map_err(|error| Error::InvalidUrl {
+            loader: loader_name.as_ref().to_string(),
+            source: error.into(),
+            url: url.to_string(),
+        })
+}
+
+/// Removes username and password of the URL.
+pub(crate) fn without_userinfo(url: &Url) -> Url {
+    let mut url = url.clone();
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url
 }
 
 impl<'de, T: Deserialize<'de>> SoftErrors<T> {
@@ -226,6 +521,23 @@ impl<'de, T: Deserialize<'de> + PartialEq> SoftErrors<T> {
             true
         }
     }
+
+    /// Combines two soft errors (e.g. ones from URL and ones set programmatically).
+    ///
+    /// [SoftErrors::All] wins and two lists are concatenated without duplicates.
+    pub fn merge(self, other: SoftErrors<T>) -> SoftErrors<T> {
+        match (self, other) {
+            (Self::List(mut soft_errors), Self::List(other_soft_errors)) => {
+                other_soft_errors.into_iter().for_each(|soft_error| {
+                    if !soft_errors.contains(&soft_error) {
+                        soft_errors.push(soft_error)
+                    }
+                });
+                Self::List(soft_errors)
+            }
+            _ => Self::All,
+        }
+    }
 }
 
 impl<'de, T: Deserialize<'de>> Default for SoftErrors<T> {
@@ -241,25 +553,33 @@ where
     type Value = SoftErrors<T>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("`all` or dot separated soft errors for configuration loader")
+        formatter
+            .write_str("`all` or dot separated (or a list of) soft errors for configuration loader")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
-        let parts: Vec<_> = v
-            .split('.')
-            .filter(|item| !item.is_empty())
-            .map(String::from)
-            .collect();
-        if parts.contains(&"all".to_string()) {
-            Ok(SoftErrors::All)
-        } else {
-            Ok(SoftErrors::List(Vec::deserialize(
-                parts.into_deserializer(),
-            )?))
+        Self::from_part_list(
+            v.split('.')
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut parts = Vec::new();
+        while let Some(item) = seq.next_element::<String>()? {
+            item.split('.')
+                .filter(|item| !item.is_empty())
+                .for_each(|item| parts.push(item.to_string()));
         }
+        Self::from_part_list(parts)
     }
 
     fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
@@ -282,7 +602,8 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for SoftErrors<T> {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_str(SoftErrorsVisitor {
+        // `any` instead of `str` to also accept lists (e.g. `soft-errors[]=a&soft-errors[]=b` in query strings):
+        deserializer.deserialize_any(SoftErrorsVisitor {
             _marker: PhantomData,
         })
     }
@@ -0,0 +1,381 @@
+//! MongoDB configuration loader (`mongodb` feature).
+//!
+//! * Supported schema: `mongodb`
+//! * The database is the URL path (e.g. `mongodb://localhost:27017/my-app?collection=config`) or the `database`
+//!   option and the collection is the `collection` option (default `config`).
+//! * Every document of the collection is the configuration of one plugin and its `_id` is the plugin name (which is
+//!   lowercased, so use lowercase `_id`s to make whitelists match them). A document either has `format` and
+//!   `contents` string fields (e.g. `{_id: "foo", format: "yaml", contents: "..."}`) which are parsed later like
+//!   other loaders, or a `config` sub-document (e.g. `{_id: "foo", config: {...}}`) which is used as the parsed
+//!   configuration directly.
+//! * Options: `database`, `collection`, and `soft-errors` (`not-found` for a missing collection and `no-access` for
+//!   authentication failures). Other query options (e.g. `authSource`) are passed to the MongoDB driver.
+//! * It uses the synchronous MongoDB client which runs its own Tokio runtime, so loading fails inside an async context
+//!   (use `tokio::task::spawn_blocking` for that).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use plugx_config::{Configuration, Url};
+//!
+//! let configuration = Configuration::new()
+//!     .with_url("mongodb://localhost:27017/my-app?collection=plugins&soft-errors=not-found".parse().unwrap())
+//!     .unwrap();
+//! let merged = configuration.load_parse_merge(true).unwrap();
+//! ```
+//!
+//! See [loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{self, Error, Loader, SoftErrors},
+    LogOptions,
+};
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use mongodb::{
+    bson::{doc, Document},
+    error::ErrorKind,
+    sync::Client,
+};
+use plugx_input::Input;
+use serde::Deserialize;
+use std::fmt::{Debug, Display, Formatter};
+use tokio::runtime::Handle;
+use url::Url;
+
+pub const NAME: &str = "MongoDB";
+pub const SCHEME_LIST: &[&str] = &["mongodb"];
+const OPTION_LIST: &[&str] = &["database", "collection", "soft-errors"];
+
+/// Loads configurations from a MongoDB collection.
+#[derive(Debug, Default, Clone)]
+pub struct Mongodb {
+    options: MongodbOptions,
+    log_options: LogOptions,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MongodbOptions {
+    database: Option<String>,
+    collection: String,
+    soft_errors: SoftErrors<SoftErrorsMongodb>,
+}
+
+impl Default for MongodbOptions {
+    fn default() -> Self {
+        Self {
+            database: None,
+            collection: default::collection(),
+            soft_errors: Default::default(),
+        }
+    }
+}
+
+/// Supported soft errors when loading from MongoDB.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoftErrorsMongodb {
+    NotFound,
+    NoAccess,
+}
+
+pub mod default {
+    #[inline(always)]
+    pub fn collection() -> String {
+        "config".to_string()
+    }
+}
+
+impl Mongodb {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Database to load configurations from if the URL has no path.
+    pub fn set_database<D: AsRef<str>>(&mut self, database: D) {
+        self.options.database = Some(database.as_ref().to_string());
+    }
+
+    /// Database to load configurations from if the URL has no path.
+    pub fn with_database<D: AsRef<str>>(mut self, database: D) -> Self {
+        self.set_database(database);
+        self
+    }
+
+    /// Collection to load configurations from.
+    pub fn set_collection<C: AsRef<str>>(&mut self, collection: C) {
+        self.options.collection = collection.as_ref().to_string();
+    }
+
+    /// Collection to load configurations from.
+    pub fn with_collection<C: AsRef<str>>(mut self, collection: C) -> Self {
+        self.set_collection(collection);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsMongodb) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsMongodb) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<MongodbOptions, Error> {
+        loader::deserialize_query_string::<MongodbOptions>(NAME, url).map(|mut options| {
+            let path = url.path().trim_matches('/');
+            if !path.is_empty() {
+                options.database = Some(path.to_string());
+            } else if options.database.is_none() {
+                options.database = self.options.database.clone();
+            }
+            if self.options.collection != default::collection() {
+                options.collection = self.options.collection.clone()
+            }
+            options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+            options
+        })
+    }
+
+    /// Removes our own options from the URL to pass the rest to the MongoDB driver.
+    fn driver_url(url: &Url) -> Url {
+        let mut driver_url = url.clone();
+        let query_list: Vec<_> = url
+            .query_pairs()
+            .filter(|(key, _)| !OPTION_LIST.contains(&key.as_ref()))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        if query_list.is_empty() {
+            driver_url.set_query(None);
+        } else {
+            driver_url
+                .query_pairs_mut()
+                .clear()
+                .extend_pairs(query_list);
+        }
+        driver_url
+    }
+
+    /// Converts one document of the collection to a plugin name and its entity.
+    #[doc(hidden)]
+    pub fn entity_from_document(
+        url: &Url,
+        database: &str,
+        collection: &str,
+        document: Document,
+    ) -> Result<(String, ConfigurationEntity), anyhow::Error> {
+        let plugin_name = document
+            .get_str("_id")
+            .map_err(|_| anyhow!("Document `_id` should be a string"))?
+            .to_lowercase();
+        let entity = ConfigurationEntity::new(
+            format!("{database}/{collection}/{plugin_name}"),
+            url.clone(),
+            &plugin_name,
+            NAME,
+        );
+        let entity = if let Ok(config) = document.get_document("config") {
+            let parsed: Input = mongodb::bson::from_document(config.clone()).map_err(|error| {
+                anyhow!("Could not convert `config` of `{plugin_name}` document ({error})")
+            })?;
+            entity.with_parsed_contents(parsed)
+        } else {
+            let format = document.get_str("format").map_err(|_| {
+                anyhow!("Document `{plugin_name}` should have `config` or `format` and `contents` fields")
+            })?;
+            let contents = document.get_str("contents").map_err(|_| {
+                anyhow!("Document `{plugin_name}` should have `config` or `format` and `contents` fields")
+            })?;
+            entity
+                .with_format(format.to_lowercase())
+                .with_contents(contents)
+        };
+        Ok((plugin_name, entity))
+    }
+}
+
+impl Display for Mongodb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(NAME)
+    }
+}
+
+impl Loader for Mongodb {
+    /// In this case `["mongodb"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SCHEME_LIST.iter().cloned().map(String::from).collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        let database = options
+            .database
+            .as_deref()
+            .ok_or_else(|| Error::InvalidUrl {
+                loader: NAME.to_string(),
+                url: url.to_string(),
+                source: anyhow!(
+                    "Could not detect database name from URL path or `database` option"
+                ),
+            })?;
+        let collection = options.collection.as_str();
+        if collection.is_empty() {
+            return Err(Error::InvalidUrl {
+                loader: NAME.to_string(),
+                url: url.to_string(),
+                source: anyhow!("Collection name is empty"),
+            });
+        }
+        if maybe_whitelist
+            .map(|whitelist| whitelist.is_empty())
+            .unwrap_or_default()
+        {
+            return Ok(Vec::new());
+        }
+        let skip_soft_error = |soft_error: SoftErrorsMongodb| {
+            skip_soft_errors
+                && (options.soft_errors.skip_all() || options.soft_errors.contains(&soft_error))
+        };
+        let handle_error = |description: &str, error: mongodb::error::Error| {
+            if matches!(*error.kind, ErrorKind::Authentication { .. }) {
+                if skip_soft_error(SoftErrorsMongodb::NoAccess) {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::info!(url=%url, skip_error=true, "No access to MongoDB");
+                        } else if #[cfg(feature = "logging")] {
+                            log::info!("msg=\"No access to MongoDB\" url={:?} skip_error=true", url.to_string());
+                        }
+                    }
+                    return Ok(Vec::new());
+                }
+                return Err(Error::NoAccess {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                });
+            }
+            Err(Error::Load {
+                loader: NAME.to_string(),
+                url: url.clone(),
+                description: description.to_string().into(),
+                source: error.into(),
+            })
+        };
+        // The driver panics if it blocks inside another runtime:
+        if Handle::try_current().is_ok() {
+            return Err(Error::Load {
+                loader: NAME.to_string(),
+                url: url.clone(),
+                description: "block async runtime to connect to".to_string().into(),
+                source: anyhow!(
+                    "Use `tokio::task::spawn_blocking` to load inside an async runtime"
+                ),
+            });
+        }
+        let client = match Client::with_uri_str(Self::driver_url(url).as_str()) {
+            Ok(client) => client,
+            Err(error) => return handle_error("connect to", error),
+        };
+        let collection_handle = client.database(database).collection::<Document>(collection);
+        let collection_name_list = match client
+            .database(database)
+            .list_collection_names(doc! {"name": collection})
+        {
+            Ok(collection_name_list) => collection_name_list,
+            Err(error) => return handle_error("list collections of", error),
+        };
+        if collection_name_list.is_empty() {
+            return if skip_soft_error(SoftErrorsMongodb::NotFound) {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::info!(url=%url, collection=collection, skip_error=true, "Could not find collection");
+                    } else if #[cfg(feature = "logging")] {
+                        log::info!(
+                            "msg=\"Could not find collection\" url={:?} collection={collection:?} skip_error=true",
+                            url.to_string()
+                        );
+                    }
+                }
+                Ok(Vec::new())
+            } else {
+                Err(Error::NotFound {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    item: format!("collection `{database}.{collection}`").into(),
+                })
+            };
+        }
+        // Plugin names are lowercased `_id`s:
+        let filter = maybe_whitelist.map(|whitelist| {
+            let whitelist: Vec<_> = whitelist.iter().map(|name| name.to_lowercase()).collect();
+            doc! {"_id": {"$in": whitelist}}
+        });
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::trace!(url=%url, filter=?filter, "Querying configuration collection");
+            } else if #[cfg(feature = "logging")] {
+                log::trace!("msg=\"Querying configuration collection\" url={:?} filter={filter:?}", url.to_string());
+            }
+        }
+        let cursor = match collection_handle.find(filter, None) {
+            Ok(cursor) => cursor,
+            Err(error) => return handle_error("query", error),
+        };
+        let mut result = Vec::new();
+        for maybe_document in cursor {
+            let document = match maybe_document {
+                Ok(document) => document,
+                Err(error) => return handle_error("read documents of", error),
+            };
+            let (plugin_name, entity) = Self::entity_from_document(
+                url, database, collection, document,
+            )
+            .map_err(|source| Error::Load {
+                loader: NAME.to_string(),
+                url: url.clone(),
+                description: "read malformed document from".to_string().into(),
+                source,
+            })?;
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::trace!(
+                        plugin=plugin_name,
+                        format=entity.maybe_format(),
+                        contents=entity
+                            .maybe_contents()
+                            .map(|contents| self.log_options.contents(contents)),
+                        "Read configuration document"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::trace!(
+                        "msg=\"Read configuration document\" plugin={plugin_name:?} format={:?} contents={:?}",
+                        entity.maybe_format(),
+                        entity
+                            .maybe_contents()
+                            .map(|contents| self.log_options.contents(contents))
+                    );
+                }
+            }
+            result.push((plugin_name, entity));
+        }
+        Ok(result)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.log_options = log_options;
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -0,0 +1,127 @@
+//! A loader wrapper that replaces URL schemes of another loader.
+//!
+//! ### Example
+//! ```rust
+//! use plugx_config::{
+//!     entity::ConfigurationEntity,
+//!     loader::{Loader, closure::Closure, schemes::Schemes},
+//!     ext::url::Url,
+//! };
+//!
+//! let loader_fn = |url: &Url, _: Option<&[String]>, _: bool| {
+//!     let entity = ConfigurationEntity::new(url.scheme(), url.clone(), "foo", "generic")
+//!         .with_format("yml")
+//!         .with_contents("hello: world");
+//!     Ok([("foo".to_string(), entity)].to_vec())
+//! };
+//! let generic_loader = Closure::new("generic", Box::new(loader_fn), "generic");
+//! let loader = Schemes::new(generic_loader, &["foo", "bar"]);
+//! assert_eq!(loader.scheme_list(), vec!["foo".to_string(), "bar".to_string()]);
+//! assert_eq!(loader.name(), "generic");
+//!
+//! let url = "bar://".parse().unwrap();
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! assert_eq!(loaded[0].1.item(), "bar");
+//! ```
+//!
+//! See [crate::loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{Error, Loader},
+    LogOptions,
+};
+use std::fmt::{Debug, Display, Formatter};
+use url::Url;
+
+/// Uses the inner loader for the given schemes instead of its own ones.
+#[derive(Debug)]
+pub struct Schemes<L: Loader> {
+    loader: L,
+    scheme_list: Vec<String>,
+}
+
+impl<L: Loader> Display for Schemes<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.loader.name().as_str())
+    }
+}
+
+impl<L: Loader> Schemes<L> {
+    pub fn new<S: AsRef<str>>(loader: L, scheme_list: &[S]) -> Self {
+        Self {
+            loader,
+            scheme_list: scheme_list
+                .iter()
+                .map(|scheme| scheme.as_ref().to_string())
+                .collect(),
+        }
+    }
+
+    pub fn loader(&self) -> &L {
+        &self.loader
+    }
+
+    pub fn loader_mut(&mut self) -> &mut L {
+        &mut self.loader
+    }
+
+    pub fn into_loader(self) -> L {
+        self.loader
+    }
+}
+
+impl<L: Loader> Loader for Schemes<L> {
+    fn name(&self) -> String {
+        self.loader.name()
+    }
+
+    /// The given schemes regardless of the inner loader's own.
+    fn scheme_list(&self) -> Vec<String> {
+        self.scheme_list.clone()
+    }
+
+    fn supports_whitelist(&self) -> bool {
+        self.loader.supports_whitelist()
+    }
+
+    fn supports_soft_errors(&self) -> bool {
+        self.loader.supports_soft_errors()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.loader.validate_url(url)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.loader.set_log_options(log_options)
+    }
+
+    /// Wraps a clone of the inner loader if it can be cloned.
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        self.loader.boxed_clone().map(|loader| {
+            Box::new(Schemes {
+                loader,
+                scheme_list: self.scheme_list.clone(),
+            }) as Box<dyn Loader>
+        })
+    }
+
+    fn plan(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Option<Vec<ConfigurationEntity>>, Error> {
+        self.loader.plan(url, maybe_whitelist, skip_soft_errors)
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        self.loader.load(url, maybe_whitelist, skip_soft_errors)
+    }
+}
@@ -0,0 +1,291 @@
+//! SQLite configuration loader (`sqlite` feature).
+//!
+//! * Supported schema: `sqlite`
+//! * The URL path is the database file and the table (default `config`) must have `plugin`, `format`,
+//!   and `contents` text columns. Every row becomes one [ConfigurationEntity].
+//! * Options: `table` (e.g. `sqlite:///path/to/app.db?table=plugin_config`) and `soft-errors`.
+//!
+//! ### Example
+//! ```rust
+//! use tempdir::TempDir;
+//! use plugx_config::loader::{Loader, sqlite::Sqlite};
+//! use url::Url;
+//!
+//! let tmp_dir = TempDir::new("sqlite-example").unwrap();
+//! let db_file = tmp_dir.path().join("app.db");
+//! let connection = rusqlite::Connection::open(&db_file).unwrap();
+//! connection.execute_batch(r#"
+//!     CREATE TABLE config (plugin TEXT, format TEXT, contents TEXT);
+//!     INSERT INTO config VALUES ('foo', 'json', '{"hello": "world"}');
+//!     INSERT INTO config VALUES ('bar', 'env', 'HELLO="world"');
+//! "#).unwrap();
+//! let url = Url::try_from(format!("sqlite://{}", db_file.to_str().unwrap()).as_str()).unwrap();
+//!
+//! let loader = Sqlite::new();
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! assert_eq!(loaded.len(), 2);
+//! let (_, foo) = loaded.iter().find(|(plugin_name, _)| plugin_name == "foo").expect("`foo` plugin config");
+//! assert_eq!(foo.maybe_format(), Some(&"json".to_string()));
+//! assert_eq!(foo.maybe_contents(), Some(&"{\"hello\": \"world\"}".to_string()));
+//!
+//! // Only load `bar`:
+//! let whitelist = ["bar".into()].to_vec();
+//! let loaded = loader.load(&url, Some(&whitelist), false).unwrap();
+//! assert_eq!(loaded.len(), 1);
+//!
+//! // A missing database file is a soft error:
+//! let url = Url::try_from(format!("sqlite://{}/nothing.db?soft-errors=not-found", tmp_dir.path().to_str().unwrap()).as_str()).unwrap();
+//! assert!(loader.load(&url, None, false).is_err());
+//! assert!(loader.load(&url, None, true).unwrap().is_empty());
+//! ```
+//!
+//! See [loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{self, Error, Loader, SoftErrors},
+    LogOptions,
+};
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use percent_encoding::percent_decode_str;
+use rusqlite::{params_from_iter, Connection, OpenFlags};
+use serde::Deserialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::PathBuf;
+use url::Url;
+
+pub const NAME: &str = "SQLite";
+pub const SCHEME_LIST: &[&str] = &["sqlite"];
+
+/// Loads configurations from a SQLite database table.
+#[derive(Debug, Default, Clone)]
+pub struct Sqlite {
+    options: SqliteOptions,
+    log_options: LogOptions,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SqliteOptions {
+    table: String,
+    soft_errors: SoftErrors<SoftErrorsSqlite>,
+}
+
+impl Default for SqliteOptions {
+    fn default() -> Self {
+        Self {
+            table: default::table(),
+            soft_errors: Default::default(),
+        }
+    }
+}
+
+/// Supported soft errors when loading from SQLite.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoftErrorsSqlite {
+    NotFound,
+}
+
+pub mod default {
+    #[inline(always)]
+    pub fn table() -> String {
+        "config".to_string()
+    }
+}
+
+impl Sqlite {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Table to load `plugin`, `format`, and `contents` columns from.
+    pub fn set_table<T: AsRef<str>>(&mut self, table: T) {
+        self.options.table = table.as_ref().to_string();
+    }
+
+    /// Table to load `plugin`, `format`, and `contents` columns from.
+    pub fn with_table<T: AsRef<str>>(mut self, table: T) -> Self {
+        self.set_table(table);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsSqlite) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsSqlite) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<SqliteOptions, Error> {
+        loader::deserialize_query_string::<SqliteOptions>(NAME, url).map(|mut options| {
+            if self.options.table != default::table() {
+                options.table = self.options.table.clone()
+            }
+            options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+            options
+        })
+    }
+
+    fn url_to_path(url: &Url) -> Result<PathBuf, Error> {
+        // URL paths are percent-encoded (e.g. `/my%20app.db` for `/my app.db`):
+        let url_path = percent_decode_str(url.path())
+            .decode_utf8()
+            .map_err(|error| Error::InvalidUrl {
+                loader: NAME.to_string(),
+                url: url.to_string(),
+                source: error.into(),
+            })?;
+        cfg_if! {
+            if #[cfg(target_os="windows")] {
+                Ok(PathBuf::from(url_path.strip_prefix('/').unwrap_or(&url_path)))
+            } else {
+                Ok(PathBuf::from(url_path.as_ref()))
+            }
+        }
+    }
+}
+
+impl Display for Sqlite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(NAME)
+    }
+}
+
+impl Loader for Sqlite {
+    /// In this case `["sqlite"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SCHEME_LIST.iter().cloned().map(String::from).collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url)?;
+        Self::url_to_path(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        let table = options.table.as_str();
+        if table.is_empty()
+            || !table
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '_')
+        {
+            return Err(Error::InvalidUrl {
+                loader: NAME.to_string(),
+                url: url.to_string(),
+                source: anyhow!("Invalid table name {table:?}"),
+            });
+        }
+        let path = Self::url_to_path(url)?;
+        if !path.is_file() {
+            return if skip_soft_errors
+                && (options.soft_errors.skip_all()
+                    || options.soft_errors.contains(&SoftErrorsSqlite::NotFound))
+            {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::info!(url=%url, skip_error=true, "Could not find database file");
+                    } else if #[cfg(feature = "logging")] {
+                        log::info!(
+                            "msg=\"Could not find database file\" url={:?} skip_error=true",
+                            url.to_string()
+                        );
+                    }
+                }
+                Ok(Vec::new())
+            } else {
+                Err(Error::NotFound {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    item: format!("database file `{path:?}`").into(),
+                })
+            };
+        }
+        let load_error = |description: &str, source: rusqlite::Error| Error::Load {
+            loader: NAME.to_string(),
+            url: url.clone(),
+            description: description.to_string().into(),
+            source: source.into(),
+        };
+        let connection = Connection::open_with_flags(
+            &path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|error| load_error("open database file", error))?;
+        let mut query = format!("SELECT plugin, format, contents FROM \"{table}\"");
+        if let Some(whitelist) = maybe_whitelist {
+            query += " WHERE lower(plugin) IN (";
+            query += vec!["?"; whitelist.len()].join(", ").as_str();
+            query += ")";
+        }
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::trace!(url=%url, query=query, "Querying configuration table");
+            } else if #[cfg(feature = "logging")] {
+                log::trace!("msg=\"Querying configuration table\" url={:?} query={query:?}", url.to_string());
+            }
+        }
+        let mut statement = connection
+            .prepare(query.as_str())
+            .map_err(|error| load_error("prepare query for", error))?;
+        let mut rows = statement
+            .query(params_from_iter(maybe_whitelist.unwrap_or_default()))
+            .map_err(|error| load_error("query", error))?;
+        let mut result = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .map_err(|error| load_error("read rows of", error))?
+        {
+            let (plugin_name, format, contents) = (|| {
+                Ok::<_, rusqlite::Error>((
+                    row.get::<_, String>(0)?.to_lowercase(),
+                    row.get::<_, String>(1)?.to_lowercase(),
+                    row.get::<_, String>(2)?,
+                ))
+            })()
+            .map_err(|error| load_error("read malformed row from", error))?;
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    tracing::trace!(
+                        plugin=plugin_name,
+                        format=format,
+                        contents=self.log_options.contents(contents.as_str()),
+                        "Read configuration row"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    log::trace!(
+                        "msg=\"Read configuration row\" plugin={plugin_name:?} format={format:?} contents={:?}",
+                        self.log_options.contents(contents.as_str())
+                    );
+                }
+            }
+            let entity = ConfigurationEntity::new(
+                format!("{table}/{plugin_name}"),
+                url.clone(),
+                &plugin_name,
+                NAME,
+            )
+            .with_format(format)
+            .with_contents(contents);
+            result.push((plugin_name, entity));
+        }
+        Ok(result)
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.log_options = log_options;
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -0,0 +1,413 @@
+//! Unix domain socket configuration loader (`unix-socket` feature on Unix).
+//!
+//! * Supported schema: `unix`
+//! * The URL path is the percent-encoded socket file (e.g. `unix:///run/config-agent.sock`).
+//! * Options: `timeout` (in seconds for the whole request from connecting to reading the response; defaults to `5`
+//!   and `0` disables it), `max-response-size` (in bytes; defaults to 16 MiB and larger responses are load errors),
+//!   and `soft-errors` (`not-found` skips a missing socket file or a refused connection, e.g. if the agent is not
+//!   running).
+//!
+//! ### Protocol
+//! The loader connects to the socket, writes one request line, and shuts down its writing half. The request line is
+//! `*` for all plugins, or the comma separated list of whitelisted plugin names. The agent responds with zero or more
+//! records and closes the connection. Each record is a header line `<plugin> <format> <length>` followed by exactly
+//! `<length>` bytes of UTF-8 contents (e.g. `foo json 18\n{"hello": "world"}`).
+//!
+//! ### Example
+//! ```rust
+//! use std::{io::{Read, Write}, os::unix::net::UnixListener, thread};
+//! use tempdir::TempDir;
+//! use plugx_config::loader::{Loader, unix_socket::UnixSocket};
+//! use url::Url;
+//!
+//! let tmp_dir = TempDir::new("unix-socket-example").unwrap();
+//! let socket_file = tmp_dir.path().join("agent.sock");
+//! let listener = UnixListener::bind(&socket_file).unwrap();
+//! let agent = thread::spawn(move || {
+//!     let (mut stream, _) = listener.accept().unwrap();
+//!     let mut request = String::new();
+//!     stream.read_to_string(&mut request).unwrap();
+//!     assert_eq!(request, "*\n");
+//!     let contents = "{\"hello\": \"world\"}";
+//!     write!(stream, "foo json {}\n{contents}", contents.len()).unwrap();
+//! });
+//! let url = Url::try_from(format!("unix://{}", socket_file.to_str().unwrap()).as_str()).unwrap();
+//!
+//! let loader = UnixSocket::new();
+//! let loaded = loader.load(&url, None, false).unwrap();
+//! agent.join().unwrap();
+//! assert_eq!(loaded.len(), 1);
+//! let (plugin_name, foo) = &loaded[0];
+//! assert_eq!(plugin_name, "foo");
+//! assert_eq!(foo.maybe_format(), Some(&"json".to_string()));
+//! assert_eq!(foo.maybe_contents(), Some(&"{\"hello\": \"world\"}".to_string()));
+//!
+//! // A socket file that does not exist (e.g. the agent is not running) is a soft error:
+//! let url = Url::try_from(format!("unix://{}/nothing.sock?soft-errors=not-found", tmp_dir.path().to_str().unwrap()).as_str()).unwrap();
+//! assert!(loader.load(&url, None, false).is_err());
+//! assert!(loader.load(&url, None, true).unwrap().is_empty());
+//! ```
+//!
+//! See [loader] documentation to known how loaders work.
+
+use crate::{
+    entity::ConfigurationEntity,
+    loader::{self, Error, Loader, SoftErrors},
+    LogOptions,
+};
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use std::{
+    ffi::OsStr,
+    fmt::{Debug, Display, Formatter},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::Shutdown,
+    os::unix::{ffi::OsStrExt, net::UnixStream},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use url::Url;
+
+pub const NAME: &str = "UnixSocket";
+pub const SCHEME_LIST: &[&str] = &["unix"];
+
+/// Loads configurations from a local agent over a Unix domain socket.
+#[derive(Debug, Default, Clone)]
+pub struct UnixSocket {
+    options: UnixSocketOptions,
+    log_options: LogOptions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct UnixSocketOptions {
+    timeout: Option<u64>,
+    max_response_size: Option<u64>,
+    soft_errors: SoftErrors<SoftErrorsUnixSocket>,
+}
+
+/// Supported soft errors when loading from a Unix domain socket.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoftErrorsUnixSocket {
+    /// The socket file does not exist or the connection is refused.
+    NotFound,
+}
+
+pub mod default {
+    #[inline(always)]
+    pub fn timeout() -> u64 {
+        5
+    }
+
+    #[inline(always)]
+    pub fn max_response_size() -> u64 {
+        16 * 1024 * 1024
+    }
+}
+
+impl UnixSocket {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Timeout in seconds for the whole request from connecting to reading the response.
+    pub fn set_timeout(&mut self, timeout_in_seconds: u64) {
+        self.options.timeout = Some(timeout_in_seconds);
+    }
+
+    /// Timeout in seconds for the whole request from connecting to reading the response.
+    pub fn with_timeout(mut self, timeout_in_seconds: u64) -> Self {
+        self.set_timeout(timeout_in_seconds);
+        self
+    }
+
+    /// Maximum number of bytes of a response.
+    pub fn set_max_response_size(&mut self, max_response_size: u64) {
+        self.options.max_response_size = Some(max_response_size);
+    }
+
+    /// Maximum number of bytes of a response.
+    pub fn with_max_response_size(mut self, max_response_size: u64) -> Self {
+        self.set_max_response_size(max_response_size);
+        self
+    }
+
+    pub fn add_soft_error(&mut self, error: SoftErrorsUnixSocket) {
+        self.options.soft_errors.add_soft_error(error)
+    }
+
+    pub fn with_soft_error(mut self, error: SoftErrorsUnixSocket) -> Self {
+        self.add_soft_error(error);
+        self
+    }
+
+    fn get_options(&self, url: &Url) -> Result<UnixSocketOptions, Error> {
+        loader::deserialize_query_string::<UnixSocketOptions>(NAME, url).map(|mut options| {
+            if self.options.timeout.is_some() {
+                options.timeout = self.options.timeout;
+            }
+            if self.options.max_response_size.is_some() {
+                options.max_response_size = self.options.max_response_size;
+            }
+            options.soft_errors = options.soft_errors.merge(self.options.soft_errors.clone());
+            options
+        })
+    }
+
+    /// Parses records of a response (see module documentation).
+    pub fn parse_response(response: &[u8]) -> Result<Vec<(String, String, String)>, anyhow::Error> {
+        let mut reader = BufReader::new(response);
+        let mut record_list = Vec::new();
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 {
+                break;
+            }
+            let header = header.trim_end_matches(['\r', '\n']);
+            if header.is_empty() {
+                continue;
+            }
+            let (plugin_name, format, length) = match header.split(' ').collect::<Vec<_>>()[..] {
+                [plugin_name, format, length] if !plugin_name.is_empty() && !format.is_empty() => (
+                    plugin_name.to_lowercase(),
+                    format.to_lowercase(),
+                    length.parse::<usize>().map_err(|error| {
+                        anyhow!("Invalid length in header {header:?} ({error})")
+                    })?,
+                ),
+                _ => {
+                    return Err(anyhow!(
+                        "Invalid header {header:?} (expected `<plugin> <format> <length>`)"
+                    ))
+                }
+            };
+            // The length is not trusted for allocating the buffer:
+            let mut contents = Vec::new();
+            (&mut reader)
+                .take(length as u64)
+                .read_to_end(&mut contents)
+                .map_err(|error| {
+                    anyhow!("Could not read {length} bytes of `{plugin_name}` contents ({error})")
+                })?;
+            if contents.len() != length {
+                return Err(anyhow!(
+                    "Could not read {length} bytes of `{plugin_name}` contents (only {} bytes remained)",
+                    contents.len()
+                ));
+            }
+            let contents = String::from_utf8(contents).map_err(|error| {
+                anyhow!("Could not decode `{plugin_name}` contents to UTF-8 ({error})")
+            })?;
+            record_list.push((plugin_name, format, contents));
+        }
+        Ok(record_list)
+    }
+
+    fn request(
+        path: &Path,
+        request: &str,
+        maybe_timeout: Option<Duration>,
+        max_response_size: u64,
+    ) -> Result<Vec<u8>, (&'static str, io::Error)> {
+        let maybe_deadline = maybe_timeout.map(|timeout| Instant::now() + timeout);
+        // Socket timeouts apply to each read and write, so they are set to what remains of the whole timeout:
+        let remaining_timeout = || {
+            maybe_deadline
+                .map(|deadline| {
+                    deadline
+                        .checked_duration_since(Instant::now())
+                        .filter(|remaining| !remaining.is_zero())
+                        .ok_or_else(|| io::Error::from(io::ErrorKind::TimedOut))
+                })
+                .transpose()
+        };
+        let mut stream = UnixStream::connect(path).map_err(|error| ("connect to", error))?;
+        remaining_timeout()
+            .and_then(|maybe_timeout| stream.set_write_timeout(maybe_timeout))
+            .and_then(|_| stream.write_all(format!("{request}\n").as_bytes()))
+            .and_then(|_| stream.shutdown(Shutdown::Write))
+            .map_err(|error| ("write request to", error))?;
+        let mut response = Vec::new();
+        let mut buffer = [0; 8192];
+        loop {
+            let read = remaining_timeout()
+                .and_then(|maybe_timeout| stream.set_read_timeout(maybe_timeout))
+                .and_then(|_| stream.read(&mut buffer));
+            match read {
+                Ok(0) => break,
+                Ok(size) => {
+                    response.extend_from_slice(&buffer[..size]);
+                    if response.len() as u64 > max_response_size {
+                        return Err((
+                            "read response from",
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("Response is larger than `max-response-size` ({max_response_size} bytes)"),
+                            ),
+                        ));
+                    }
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(("read response from", error)),
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl Display for UnixSocket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(NAME)
+    }
+}
+
+impl Loader for UnixSocket {
+    /// In this case `["unix"]`.
+    fn scheme_list(&self) -> Vec<String> {
+        SCHEME_LIST.iter().cloned().map(String::from).collect()
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<(), Error> {
+        self.get_options(url).map(|_| ())
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, Error> {
+        let options = self.get_options(url)?;
+        // URL paths are percent-encoded (e.g. `/run/my%20agent.sock` for `/run/my agent.sock`):
+        let path = PathBuf::from(OsStr::from_bytes(
+            &percent_decode_str(url.path()).collect::<Vec<u8>>(),
+        ));
+        let request = match maybe_whitelist {
+            Some([]) => return Ok(Vec::new()),
+            Some(whitelist) => whitelist.join(","),
+            None => "*".to_string(),
+        };
+        let timeout_in_seconds = options.timeout.unwrap_or_else(default::timeout);
+        cfg_if! {
+            if #[cfg(feature = "tracing")] {
+                tracing::trace!(url=%url, request=request, "Requesting configurations");
+            } else if #[cfg(feature = "logging")] {
+                log::trace!("msg=\"Requesting configurations\" url={:?} request={request:?}", url.to_string());
+            }
+        }
+        let response = match Self::request(
+            &path,
+            &request,
+            (timeout_in_seconds > 0).then(|| Duration::from_secs(timeout_in_seconds)),
+            options
+                .max_response_size
+                .unwrap_or_else(default::max_response_size),
+        ) {
+            Ok(response) => response,
+            Err((_, error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                return if skip_soft_errors
+                    && (options.soft_errors.skip_all()
+                        || options
+                            .soft_errors
+                            .contains(&SoftErrorsUnixSocket::NotFound))
+                {
+                    cfg_if! {
+                        if #[cfg(feature = "tracing")] {
+                            tracing::info!(url=%url, error=%error, skip_error=true, "Could not connect to socket");
+                        } else if #[cfg(feature = "logging")] {
+                            log::info!(
+                                "msg=\"Could not connect to socket\" url={:?} error={:?} skip_error=true",
+                                url.to_string(),
+                                error.to_string()
+                            );
+                        }
+                    }
+                    Ok(Vec::new())
+                } else {
+                    Err(Error::NotFound {
+                        loader: NAME.to_string(),
+                        url: url.clone(),
+                        item: format!("socket `{path:?}` ({error})").into(),
+                    })
+                };
+            }
+            Err((_, error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+                ) =>
+            {
+                return Err(Error::Timeout {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    timeout_in_seconds: timeout_in_seconds as usize,
+                })
+            }
+            Err((description, error)) => {
+                return Err(Error::Load {
+                    loader: NAME.to_string(),
+                    url: url.clone(),
+                    description: description.to_string().into(),
+                    source: error.into(),
+                })
+            }
+        };
+        let record_list =
+            Self::parse_response(response.as_slice()).map_err(|error| Error::Load {
+                loader: NAME.to_string(),
+                url: url.clone(),
+                description: "parse response of".to_string().into(),
+                source: error,
+            })?;
+        Ok(record_list
+            .into_iter()
+            .filter(|(plugin_name, _, _)| {
+                maybe_whitelist
+                    .map(|whitelist| whitelist.contains(plugin_name))
+                    .unwrap_or(true)
+            })
+            .map(|(plugin_name, format, contents)| {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::trace!(
+                            plugin=plugin_name,
+                            format=format,
+                            contents=self.log_options.contents(contents.as_str()),
+                            "Read configuration record"
+                        );
+                    } else if #[cfg(feature = "logging")] {
+                        log::trace!(
+                            "msg=\"Read configuration record\" plugin={plugin_name:?} format={format:?} contents={:?}",
+                            self.log_options.contents(contents.as_str())
+                        );
+                    }
+                }
+                let entity = ConfigurationEntity::new(
+                    format!("{}/{plugin_name}", url.path()),
+                    url.clone(),
+                    &plugin_name,
+                    NAME,
+                )
+                .with_format(format)
+                .with_contents(contents);
+                (plugin_name, entity)
+            })
+            .collect())
+    }
+
+    fn set_log_options(&mut self, log_options: LogOptions) {
+        self.log_options = log_options;
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Loader>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -1,3 +1,5 @@
+use std::{cell::Cell, fmt::Display};
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! is_debug_level_enabled {
@@ -29,3 +31,68 @@ macro_rules! is_trace_level_enabled {
         }
     }};
 }
+
+/// Options of trace logs that loaders and parsers emit (see [crate::Configuration::set_trace_contents]).
+///
+/// Loaders get them via [crate::loader::Loader::set_log_options]. Parsers are shared and mostly stateless, so they
+/// read them via [LogOptions::current] while a [crate::Configuration] parses on the current thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogOptions {
+    /// Whether contents (e.g. read files and parsed outputs) are logged. Otherwise only lengths of read contents are
+    /// logged and parsed outputs are omitted.
+    pub trace_contents: bool,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            trace_contents: true,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_LOG_OPTIONS: Cell<LogOptions> = const { Cell::new(LogOptions { trace_contents: true }) };
+}
+
+impl LogOptions {
+    /// Returns `contents` itself if [LogOptions::trace_contents] is `true`, otherwise only its length.
+    pub fn contents<T: AsRef<str>>(&self, contents: T) -> String {
+        let contents = contents.as_ref();
+        if self.trace_contents {
+            contents.to_string()
+        } else {
+            format!("<{} bytes>", contents.len())
+        }
+    }
+
+    /// Returns `output` formatted if [LogOptions::trace_contents] is `true`, otherwise `<omitted>` without formatting
+    /// it.
+    pub fn output<T: Display>(&self, output: &T) -> String {
+        if self.trace_contents {
+            output.to_string()
+        } else {
+            "<omitted>".to_string()
+        }
+    }
+
+    /// Returns options of the configuration that is parsing on the current thread, or the default ones.
+    pub fn current() -> Self {
+        CURRENT_LOG_OPTIONS.with(Cell::get)
+    }
+
+    /// Runs `f` with `self` as [LogOptions::current] and restores the previous options afterward (even on panic).
+    #[cfg(feature = "configuration")]
+    pub(crate) fn scope<T, F: FnOnce() -> T>(self, f: F) -> T {
+        struct Restore(LogOptions);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_LOG_OPTIONS.with(|current| current.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CURRENT_LOG_OPTIONS.with(|current| current.replace(self)));
+        f()
+    }
+}
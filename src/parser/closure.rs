@@ -55,7 +55,7 @@ use crate::parser::Parser;
 use plugx_input::Input;
 use std::fmt::{Debug, Display, Formatter};
 
-/// A `|&[u8]| -> Result<Input, ConfigurationParserError>` [Fn] to parse contents.
+/// A `|&[u8]| -> anyhow::Result<Input>` [Fn] to parse contents.
 pub type BoxedParserFn = Box<dyn Fn(&[u8]) -> anyhow::Result<Input> + Send + Sync>;
 /// A `|&[u8]| -> Option<bool>` [Fn] to validate contents.
 pub type BoxedValidatorFn = Box<dyn Fn(&[u8]) -> Option<bool> + Send + Sync>;
@@ -76,13 +76,20 @@ impl Display for Closure {
 
 impl Debug for Closure {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ConfigurationParserFn")
+        f.debug_struct("Closure")
             .field("name", &self.name)
             .field("supported_format_list", &self.supported_format_list)
             .finish()
     }
 }
 
+/// Former name of [Closure].
+#[deprecated(
+    since = "0.3.1",
+    note = "Use `plugx_config::parser::closure::Closure` instead"
+)]
+pub type ConfigurationParserFn = Closure;
+
 impl Closure {
     pub fn new<N: AsRef<str>, F: AsRef<str>>(
         name: N,
@@ -96,13 +96,19 @@ impl Parser for Env {
         .map_err(|error| anyhow!(error))?;
         cfg_if! {
             if #[cfg(feature = "tracing")] {
+                let log_options = crate::LogOptions::current();
                 tracing::trace!(
-                    input=text,
-                    output=%map,
+                    input=log_options.contents(text.as_str()),
+                    output=log_options.output(&map),
                     "Parsed environment-variable contents"
                 );
             } else if #[cfg(feature = "logging")] {
-                log::trace!("msg=\"Parsed environment-variable contents\" input={text:?} output={:?}", map.to_string());
+                let log_options = crate::LogOptions::current();
+                log::trace!(
+                    "msg=\"Parsed environment-variable contents\" input={:?} output={:?}",
+                    log_options.contents(text.as_str()),
+                    log_options.output(&map)
+                );
             }
         }
         Ok(map)
@@ -115,6 +121,10 @@ impl Parser for Env {
             Some(false)
         }
     }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        Some(Box::new(self.clone()))
+    }
 }
 
 impl Env {
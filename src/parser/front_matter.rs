@@ -0,0 +1,142 @@
+//! A parser wrapper that parses front matter of documents (e.g. Markdown files) and ignores the rest.
+//!
+//! The front matter is the block between the first two fence lines (`---` for YAML and `+++` for TOML) at the
+//! beginning of the contents. Supported formats default to `md` and `markdown`.
+//!
+//! ### Example
+//! ```rust
+//! # #[cfg(feature = "yaml")] {
+//! use plugx_config::parser::{Parser, front_matter::FrontMatter};
+//!
+//! let bytes = br#"---
+//! server:
+//!   address: 127.0.0.1
+//! ---
+//! # My plugin
+//!
+//! This is not a part of configuration.
+//! "#;
+//!
+//! let parser = FrontMatter::yaml();
+//! assert_eq!(parser.supported_format_list(), vec!["md".to_string(), "markdown".to_string()]);
+//! assert_eq!(parser.is_format_supported(bytes.as_slice()), Some(true));
+//! let parsed = parser.parse(bytes.as_slice()).unwrap();
+//! assert!(parsed.as_map().contains_key("server"));
+//!
+//! // No front matter:
+//! assert_eq!(parser.is_format_supported(b"# My plugin"), Some(false));
+//! assert!(parser.parse(b"# My plugin").is_err());
+//! # }
+//! ```
+
+use crate::parser::Parser;
+use anyhow::anyhow;
+use plugx_input::Input;
+use std::fmt::{Debug, Display, Formatter};
+
+/// Parses front matter of contents with the inner parser.
+#[derive(Debug, Clone)]
+pub struct FrontMatter<P: Parser> {
+    parser: P,
+    fence: String,
+    supported_format_list: Vec<String>,
+}
+
+impl<P: Parser> Display for FrontMatter<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(format!("{} front matter", self.parser.name()).as_str())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl FrontMatter<crate::parser::yaml::Yaml> {
+    /// YAML front matter between `---` lines.
+    pub fn yaml() -> Self {
+        Self::new(crate::parser::yaml::Yaml::new(), "---")
+    }
+}
+
+#[cfg(feature = "toml")]
+impl FrontMatter<crate::parser::toml::Toml> {
+    /// TOML front matter between `+++` lines.
+    pub fn toml() -> Self {
+        Self::new(crate::parser::toml::Toml::new(), "+++")
+    }
+}
+
+impl<P: Parser> FrontMatter<P> {
+    pub fn new<F: AsRef<str>>(parser: P, fence: F) -> Self {
+        Self {
+            parser,
+            fence: fence.as_ref().to_string(),
+            supported_format_list: vec!["md".into(), "markdown".into()],
+        }
+    }
+
+    pub fn set_format_list<F: AsRef<str>>(&mut self, format_list: &[F]) {
+        self.supported_format_list = format_list
+            .iter()
+            .map(|format| format.as_ref().to_lowercase())
+            .collect();
+    }
+
+    pub fn with_format_list<F: AsRef<str>>(mut self, format_list: &[F]) -> Self {
+        self.set_format_list(format_list);
+        self
+    }
+
+    pub fn parser(&self) -> &P {
+        &self.parser
+    }
+
+    pub fn parser_mut(&mut self) -> &mut P {
+        &mut self.parser
+    }
+
+    /// Returns contents between the first two fence lines if the contents starts with a fence line.
+    pub fn extract<'a>(&self, text: &'a str) -> Option<&'a str> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut line_list = text.split_inclusive('\n');
+        if line_list.next()?.trim_end() != self.fence {
+            return None;
+        }
+        let start = text.find('\n')? + 1;
+        let mut end = start;
+        for line in line_list {
+            if line.trim_end() == self.fence {
+                return Some(&text[start..end]);
+            }
+            end += line.len();
+        }
+        None
+    }
+}
+
+impl<P: Parser> Parser for FrontMatter<P> {
+    fn supported_format_list(&self) -> Vec<String> {
+        self.supported_format_list.clone()
+    }
+
+    fn try_parse(&self, bytes: &[u8]) -> anyhow::Result<Input> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|error| anyhow!("Could not decode contents to UTF-8 ({error})"))?;
+        let front_matter = self
+            .extract(text)
+            .ok_or_else(|| anyhow!("Could not find front matter between `{}` lines", self.fence))?;
+        self.parser.try_parse(front_matter.as_bytes())
+    }
+
+    fn is_format_supported(&self, bytes: &[u8]) -> Option<bool> {
+        Some(
+            std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|text| self.extract(text))
+                .map(|front_matter| {
+                    self.parser
+                        .is_format_supported(front_matter.as_bytes())
+                        .unwrap_or(true)
+                })
+                .unwrap_or(false),
+        )
+    }
+}
@@ -0,0 +1,230 @@
+//! HCL (e.g. Terraform-style) configuration parser.
+//!
+//! This is only usable if you enabled `hcl` Cargo feature.
+//!
+//! * Attributes become keys of maps.
+//! * Blocks become nested maps and their labels become nested keys (e.g. `resource "a" "b" {}` is
+//!   `{"resource": {"a": {"b": {}}}}`). Repeated blocks with the same identifier and labels become a list of maps.
+//! * Expressions are not evaluated. Only literal values (booleans, numbers, strings, lists, objects, and heredocs
+//!   without interpolations) and simple references (e.g. `var.name` which is kept as the string `"var.name"`) are
+//!   supported. Other expressions (e.g. `null`, function calls, operations, conditionals, and interpolations) yield
+//!   an error.
+//!
+//! ### Example
+//! ```rust
+//! use plugx_config::parser::{Parser, hcl::Hcl};
+//! use plugx_input::Input;
+//!
+//! let bytes = br#"
+//! region = "eu-west-1"
+//! zones = ["a", "b"]
+//!
+//! server "web" {
+//!   port = 8080
+//!   source = var.web_source
+//! }
+//! "#;
+//!
+//! let parser = Hcl::new();
+//! let parsed: Input = parser.parse(bytes.as_slice()).unwrap();
+//! let map = parsed.as_map();
+//! assert_eq!(map.get("region").unwrap(), &Input::from("eu-west-1"));
+//! let web = map.get("server").unwrap().as_map().get("web").unwrap().as_map();
+//! assert_eq!(web.get("port").unwrap(), &Input::from(8080));
+//! assert_eq!(web.get("source").unwrap(), &Input::from("var.web_source"));
+//! assert!(parser.parse(b"port = 80 + 1".as_slice()).is_err());
+//! ```
+
+use crate::parser::Parser;
+use anyhow::{anyhow, bail};
+use cfg_if::cfg_if;
+use hcl::{template::Element, Body, Expression, ObjectKey, Structure, Template, TraversalOperator};
+use plugx_input::Input;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
+};
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Hcl;
+
+impl Hcl {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Display for Hcl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HCL")
+    }
+}
+
+impl Parser for Hcl {
+    fn supported_format_list(&self) -> Vec<String> {
+        ["hcl".into(), "tf".into()].into()
+    }
+
+    fn try_parse(&self, bytes: &[u8]) -> anyhow::Result<Input> {
+        let text = String::from_utf8(bytes.to_vec())
+            .map_err(|error| anyhow!("Could not decode contents to UTF-8 ({error})"))?;
+        let body = hcl::parse(text.as_str()).map_err(|error| anyhow!(error))?;
+        body_to_input(body).inspect(|_parsed| {
+            cfg_if! {
+                if #[cfg(feature = "tracing")] {
+                    let log_options = crate::LogOptions::current();
+                    tracing::trace!(
+                        input=log_options.contents(text.as_str()),
+                        output=log_options.output(_parsed),
+                        "Parsed HCL contents"
+                    );
+                } else if #[cfg(feature = "logging")] {
+                    let log_options = crate::LogOptions::current();
+                    log::trace!(
+                        "msg=\"Parsed HCL contents\" input={:?} output={:?}",
+                        log_options.contents(text.as_str()),
+                        log_options.output(_parsed)
+                    );
+                }
+            }
+        })
+    }
+
+    fn is_format_supported(&self, bytes: &[u8]) -> Option<bool> {
+        Some(self.try_parse(bytes).is_ok())
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        Some(Box::new(*self))
+    }
+}
+
+fn body_to_input(body: Body) -> anyhow::Result<Input> {
+    let mut map = HashMap::new();
+    for structure in body.into_inner() {
+        match structure {
+            Structure::Attribute(attribute) => {
+                let key = attribute.key.to_string();
+                let value = expression_to_input(attribute.expr)
+                    .map_err(|error| anyhow!("Invalid attribute `{key}`: {error}"))?;
+                map.insert(key, value);
+            }
+            Structure::Block(block) => {
+                let key_list: Vec<_> = [block.identifier.to_string()]
+                    .into_iter()
+                    .chain(block.labels.into_iter().map(|label| label.into_inner()))
+                    .collect();
+                let value = body_to_input(block.body)?;
+                insert_block(&mut map, key_list.as_slice(), value)?;
+            }
+        }
+    }
+    Ok(Input::from(map))
+}
+
+fn insert_block(
+    map: &mut HashMap<String, Input>,
+    key_list: &[String],
+    value: Input,
+) -> anyhow::Result<()> {
+    let Some((key, key_list)) = key_list.split_first() else {
+        return Ok(());
+    };
+    if key_list.is_empty() {
+        if let Some(existing) = map.remove(key) {
+            let list = if existing.is_list() {
+                let mut list = existing.as_list().clone();
+                list.push(value);
+                list
+            } else {
+                [existing, value].into()
+            };
+            map.insert(key.clone(), Input::from(list));
+        } else {
+            map.insert(key.clone(), value);
+        }
+        return Ok(());
+    }
+    let inner = map
+        .entry(key.clone())
+        .or_insert_with(|| Input::from(HashMap::<String, Input>::new()));
+    if !inner.is_map() {
+        bail!("Block `{key}` conflicts with an attribute or another block");
+    }
+    insert_block(inner.map_mut(), key_list, value)
+}
+
+fn expression_to_input(expression: Expression) -> anyhow::Result<Input> {
+    Ok(match expression {
+        Expression::Bool(value) => Input::from(value),
+        Expression::Number(number) => {
+            if let Some(value) = number.as_i64().filter(|_| !number.is_f64()) {
+                Input::from(value as isize)
+            } else if let Some(value) = number.as_f64() {
+                Input::from(value)
+            } else {
+                bail!("Unsupported number `{number}`")
+            }
+        }
+        Expression::String(value) => Input::from(value),
+        Expression::Array(expression_list) => Input::from(
+            expression_list
+                .into_iter()
+                .map(expression_to_input)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ),
+        Expression::Object(object) => Input::from(
+            object
+                .into_iter()
+                .map(|(key, expression)| {
+                    let key = match key {
+                        ObjectKey::Identifier(identifier) => identifier.to_string(),
+                        ObjectKey::Expression(Expression::String(key)) => key,
+                        ObjectKey::Expression(expression) => {
+                            bail!("Unsupported object key `{expression}`")
+                        }
+                        _ => bail!("Unsupported object key"),
+                    };
+                    expression_to_input(expression).map(|value| (key, value))
+                })
+                .collect::<anyhow::Result<HashMap<_, _>>>()?,
+        ),
+        Expression::Parenthesis(expression) => expression_to_input(*expression)?,
+        Expression::TemplateExpr(template_expression) => {
+            let template = Template::from_expr(&template_expression)?;
+            template
+                .elements()
+                .iter()
+                .map(|element| match element {
+                    Element::Literal(literal) => Ok(literal.as_str()),
+                    _ => Err(anyhow!(
+                        "Template interpolations and directives are not supported"
+                    )),
+                })
+                .collect::<anyhow::Result<String>>()
+                .map(Input::from)?
+        }
+        Expression::Variable(variable) => Input::from(variable.to_string()),
+        Expression::Traversal(traversal) => {
+            if matches!(traversal.expr, Expression::Variable(_))
+                && traversal.operators.iter().all(|operator| {
+                    matches!(
+                        operator,
+                        TraversalOperator::GetAttr(_) | TraversalOperator::LegacyIndex(_)
+                    )
+                })
+            {
+                Input::from(Expression::Traversal(traversal).to_string())
+            } else {
+                bail!(
+                    "Unsupported reference `{}` (only simple references like `var.name` are supported)",
+                    Expression::Traversal(traversal)
+                )
+            }
+        }
+        Expression::Null => bail!("`null` values are not supported"),
+        expression => {
+            bail!("Unsupported expression `{expression}` (expressions are not evaluated)")
+        }
+    })
+}
@@ -0,0 +1,94 @@
+//! [HJSON](https://hjson.github.io/) configuration parser.
+//!
+//! This is only usable if you enabled `hjson` Cargo feature.
+//!
+//! ### Example
+//! ```rust
+//! use plugx_config::parser::{Parser, hjson::Hjson};
+//! use plugx_input::Input;
+//!
+//! let bytes = br#"
+//! {
+//!     // Comments are supported!
+//!     hello: ["w", "o", "l", "d"]
+//!     foo: {
+//!         bar: {
+//!             baz: Qux
+//!             abc: 3.14
+//!         }
+//!         xyz: false
+//!     }
+//! }
+//! "#;
+//!
+//! let parser = Hjson::new();
+//! let parsed: Input = parser.parse(bytes.as_slice()).unwrap();
+//! assert!(parsed.is_map());
+//! let map = parsed.as_map();
+//! assert!(
+//!     map.len() == 2 &&
+//!     map.contains_key("foo") &&
+//!     map.contains_key("hello")
+//! );
+//! let bar = map.get("foo").unwrap().as_map().get("bar").unwrap();
+//! assert_eq!(bar.as_map().get("baz").unwrap(), &"Qux".into());
+//! ```
+
+use crate::parser::Parser;
+use anyhow::anyhow;
+use cfg_if::cfg_if;
+use plugx_input::Input;
+use std::fmt::{Debug, Display, Formatter};
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Hjson;
+
+impl Hjson {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Display for Hjson {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HJSON")
+    }
+}
+
+impl Parser for Hjson {
+    fn supported_format_list(&self) -> Vec<String> {
+        ["hjson".into()].into()
+    }
+
+    fn try_parse(&self, bytes: &[u8]) -> anyhow::Result<Input> {
+        deser_hjson::from_slice(bytes)
+            .inspect(|_parsed: &Input| {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        let log_options = crate::LogOptions::current();
+                        tracing::trace!(
+                            input=log_options.contents(String::from_utf8_lossy(bytes)),
+                            output=log_options.output(_parsed),
+                            "Parsed HJSON contents"
+                        );
+                    } else if #[cfg(feature = "logging")] {
+                        let log_options = crate::LogOptions::current();
+                        log::trace!(
+                            "msg=\"Parsed HJSON contents\" input={:?} output={:?}",
+                            log_options.contents(String::from_utf8_lossy(bytes)),
+                            log_options.output(_parsed)
+                        );
+                    }
+                }
+            })
+            .map_err(|error| anyhow!(error))
+    }
+
+    fn is_format_supported(&self, bytes: &[u8]) -> Option<bool> {
+        Some(deser_hjson::from_slice::<Input>(bytes).is_ok())
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        Some(Box::new(*self))
+    }
+}
@@ -32,11 +32,14 @@
 //! );
 //! ```
 
-use crate::parser::Parser;
+use crate::parser::{Error, Parser};
 use anyhow::anyhow;
 use cfg_if::cfg_if;
 use plugx_input::Input;
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    io::Read,
+};
 
 #[derive(Clone, Copy, Default)]
 pub struct Json;
@@ -49,7 +52,7 @@ impl Display for Json {
 
 impl Debug for Json {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ConfigurationParserJson").finish()
+        f.debug_struct("Json").finish()
     }
 }
 
@@ -66,23 +69,24 @@ impl Parser for Json {
 
     fn try_parse(&self, bytes: &[u8]) -> anyhow::Result<Input> {
         serde_json::from_slice(bytes)
-            .map(|parsed: Input| {
+            .inspect(|_parsed: &Input| {
                 cfg_if! {
                     if #[cfg(feature = "tracing")] {
+                        let log_options = crate::LogOptions::current();
                         tracing::trace!(
-                            input=String::from_utf8_lossy(bytes).to_string(),
-                            output=%parsed,
+                            input=log_options.contents(String::from_utf8_lossy(bytes)),
+                            output=log_options.output(_parsed),
                             "Parsed JSON contents"
                         );
                     } else if #[cfg(feature = "logging")] {
+                        let log_options = crate::LogOptions::current();
                         log::trace!(
                             "msg=\"Parsed JSON contents\" input={:?} output={:?}",
-                            String::from_utf8_lossy(bytes).to_string(),
-                            parsed.to_string()
+                            log_options.contents(String::from_utf8_lossy(bytes)),
+                            log_options.output(_parsed)
                         );
                     }
                 }
-                parsed
             })
             .map_err(|error| anyhow!(error))
     }
@@ -90,4 +94,33 @@ impl Parser for Json {
     fn is_format_supported(&self, bytes: &[u8]) -> Option<bool> {
         Some(serde_json::from_slice::<serde_json::Value>(bytes).is_ok())
     }
+
+    fn parse_stream(&self, reader: &mut dyn Read) -> Result<Input, Error> {
+        serde_json::from_reader(reader)
+            .inspect(|_parsed: &Input| {
+                cfg_if! {
+                    if #[cfg(feature = "tracing")] {
+                        tracing::trace!(
+                            output=crate::LogOptions::current().output(_parsed),
+                            "Parsed JSON stream"
+                        );
+                    } else if #[cfg(feature = "logging")] {
+                        log::trace!(
+                            "msg=\"Parsed JSON stream\" output={:?}",
+                            crate::LogOptions::current().output(_parsed)
+                        );
+                    }
+                }
+            })
+            .map_err(|error| Error::Parse {
+                data: "<stream>".to_string(),
+                parser: self.name(),
+                supported_format_list: self.supported_format_list(),
+                source: anyhow!(error),
+            })
+    }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        Some(Box::new(*self))
+    }
 }
@@ -1,11 +1,18 @@
 //! Configuration parser trait and implementations.
 
 use plugx_input::Input;
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    io::Read,
+};
 use thiserror::Error;
 
 #[cfg(feature = "env")]
 pub mod env;
+#[cfg(feature = "hcl")]
+pub mod hcl;
+#[cfg(feature = "hjson")]
+pub mod hjson;
 #[cfg(feature = "json")]
 pub mod json;
 #[cfg(feature = "toml")]
@@ -14,12 +21,18 @@ pub mod toml;
 pub mod yaml;
 
 pub mod closure;
+pub mod front_matter;
 
 /// Parser error type.
 #[derive(Debug, Error)]
 pub enum Error {
     /// Could not parse contents.
-    #[error("{parser} with supported formats {supported_format_list:?} could not parse `{data}`")]
+    ///
+    /// Use [Error::position] to get line and column of the error if the underlying parser reports them.
+    #[error(
+        "{parser} with supported formats {supported_format_list:?} could not parse `{data}`{}",
+        display_position(error_position(.data.as_bytes(), .source))
+    )]
     Parse {
         data: String,
         parser: String,
@@ -27,12 +40,59 @@ pub enum Error {
         source: anyhow::Error,
     },
     /// Could not find parser or guess format to choose correct parser.
-    #[error("Could not found parser to parse format `{format}`")]
-    ParserNotFound { format: String },
+    ///
+    /// `available_format_list` contains formats of all given parsers.
+    #[error(
+        "Could not found parser to parse format `{format}` (available: {})",
+        display_format_list(available_format_list)
+    )]
+    ParserNotFound {
+        format: String,
+        available_format_list: Vec<String>,
+    },
+}
+
+/// Line and column (starting from 1) of a parse error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
 }
 
+impl Error {
+    /// Line and column of [Error::Parse] errors of the built-in parsers (JSON, YAML, TOML, HJSON, and HCL).
+    pub fn position(&self) -> Option<Position> {
+        if let Self::Parse { data, source, .. } = self {
+            error_position(data.as_bytes(), source)
+        } else {
+            None
+        }
+    }
+}
+
+/// Former name of [Parser].
+///
+/// It is implemented for every [Parser], so it still works as a bound (e.g. `T: ConfigurationParser`). Implement
+/// [Parser] itself for new types.
+#[deprecated(since = "0.3.1", note = "Use `plugx_config::parser::Parser` instead")]
+pub trait ConfigurationParser: Parser {}
+
+#[allow(deprecated)]
+impl<T: Parser + ?Sized> ConfigurationParser for T {}
+
+/// Former name of [Error].
+#[deprecated(since = "0.3.1", note = "Use `plugx_config::parser::Error` instead")]
+pub type ConfigurationParserError = Error;
+
 /// A trait to parse configuration contents.
 pub trait Parser: Send + Sync + Debug + Display {
+    /// Name of this parser which is used in logs and errors.
+    ///
+    /// Defaults to its [Display] output.
+    fn name(&self) -> String {
+        format!("{self}")
+    }
+
     /// Supported format list (e.g. "yml")
     fn supported_format_list(&self) -> Vec<String>;
 
@@ -42,12 +102,116 @@ pub trait Parser: Send + Sync + Debug + Display {
     /// Checks if provided byte slice is ok for future parsing. (e.g. is it YAML at all or not)
     fn is_format_supported(&self, bytes: &[u8]) -> Option<bool>;
 
+    /// Clones this parser (see [crate::Configuration::subset]).
+    ///
+    /// Defaults to [None] which means the parser can not be cloned. [Clone] parsers implement it as
+    /// `Some(Box::new(self.clone()))`.
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        None
+    }
+
     fn parse(&self, bytes: &[u8]) -> Result<Input, Error> {
         self.try_parse(bytes).map_err(|source| Error::Parse {
             data: String::from_utf8_lossy(bytes).to_string(),
-            parser: format!("{self}"),
+            parser: self.name(),
             supported_format_list: self.supported_format_list(),
             source,
         })
     }
+
+    /// Parses contents of a reader to [Input].
+    ///
+    /// Defaults to reading everything and calling [Parser::parse]. Parsers that can deserialize directly from a
+    /// reader (e.g. JSON) override it to avoid buffering whole contents.
+    fn parse_stream(&self, reader: &mut dyn Read) -> Result<Input, Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|error| Error::Parse {
+                data: String::new(),
+                parser: self.name(),
+                supported_format_list: self.supported_format_list(),
+                source: error.into(),
+            })?;
+        self.parse(bytes.as_slice())
+    }
+}
+
+/// Detects line and column of errors of the built-in parsers (JSON, YAML, TOML, HJSON, and HCL).
+///
+/// `bytes` are the parsed contents which are only needed for TOML errors that have a byte span instead.
+pub fn error_position(bytes: &[u8], error: &anyhow::Error) -> Option<Position> {
+    #[cfg(not(feature = "toml"))]
+    let _ = bytes;
+    #[cfg(not(any(
+        feature = "json",
+        feature = "yaml",
+        feature = "toml",
+        feature = "hcl",
+        feature = "hjson"
+    )))]
+    let _ = error;
+    #[cfg(feature = "json")]
+    if let Some(error) = error.downcast_ref::<serde_json::Error>() {
+        return (error.line() > 0).then(|| Position {
+            line: error.line(),
+            column: error.column(),
+        });
+    }
+    #[cfg(feature = "yaml")]
+    if let Some(error) = error.downcast_ref::<serde_yaml::Error>() {
+        return error.location().map(|location| Position {
+            line: location.line(),
+            column: location.column(),
+        });
+    }
+    #[cfg(feature = "toml")]
+    if let Some(error) = error.downcast_ref::<::toml::de::Error>() {
+        return error.span().map(|span| {
+            let before = &bytes[..span.start.min(bytes.len())];
+            let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+            let line_start = before
+                .iter()
+                .rposition(|byte| *byte == b'\n')
+                .map(|index| index + 1)
+                .unwrap_or_default();
+            let column = String::from_utf8_lossy(&before[line_start..])
+                .chars()
+                .count()
+                + 1;
+            Position { line, column }
+        });
+    }
+    #[cfg(feature = "hcl")]
+    if let Some(::hcl::Error::Parse(error)) = error.downcast_ref::<::hcl::Error>() {
+        return Some(Position {
+            line: error.location().line(),
+            column: error.location().column(),
+        });
+    }
+    #[cfg(feature = "hjson")]
+    if let Some(
+        deser_hjson::Error::Syntax { line, col, .. } | deser_hjson::Error::Serde { line, col, .. },
+    ) = error.downcast_ref::<deser_hjson::Error>()
+    {
+        return Some(Position {
+            line: *line,
+            column: *col,
+        });
+    }
+    None
+}
+
+fn display_format_list(format_list: &[String]) -> String {
+    if format_list.is_empty() {
+        "none".to_string()
+    } else {
+        format_list.join(", ")
+    }
+}
+
+fn display_position(maybe_position: Option<Position>) -> String {
+    maybe_position
+        .map(|Position { line, column }| format!(" at line {line} column {column}"))
+        .unwrap_or_default()
 }
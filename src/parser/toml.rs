@@ -25,6 +25,19 @@
 //!     map.contains_key("hello")
 //! );
 //! ```
+//!
+//! ### Dates and times
+//! TOML offset datetimes, local datetimes, local dates, and local times are parsed to strings in their TOML (RFC 3339)
+//! representation (e.g. `dt = 1979-05-27T07:32:00Z` is parsed to the string `"1979-05-27T07:32:00Z"`) with no loss of
+//! precision or offset.
+//!
+//! ```rust
+//! use plugx_config::parser::{Parser, toml::Toml};
+//! use plugx_input::Input;
+//!
+//! let parsed = Toml::new().parse(b"dt = 1979-05-27T00:32:00.999999-07:00".as_slice()).unwrap();
+//! assert_eq!(parsed.as_map().get("dt"), Some(&Input::from("1979-05-27T00:32:00.999999-07:00")));
+//! ```
 
 use crate::parser::Parser;
 use anyhow::anyhow;
@@ -57,22 +70,28 @@ impl Parser for Toml {
             .map_err(|error| anyhow!("Could not decode contents to UTF-8 ({error})"))
             .and_then(|text| {
                 toml::from_str(text.as_str())
-                    .map(|parsed: Input| {
+                    .and_then(|mut value: toml::Value| {
+                        datetimes_to_strings(&mut value);
+                        value.try_into()
+                    })
+                    .inspect(|_parsed: &Input| {
                         cfg_if! {
                             if #[cfg(feature = "tracing")] {
+                                let log_options = crate::LogOptions::current();
                                 tracing::trace!(
-                                    input=text,
-                                    output=%parsed,
+                                    input=log_options.contents(text.as_str()),
+                                    output=log_options.output(_parsed),
                                     "Parsed TOML contents"
                                 );
                             } else if #[cfg(feature = "logging")] {
+                                let log_options = crate::LogOptions::current();
                                 log::trace!(
-                                    "msg=\"Parsed TOML contents\" input={text:?} output={:?}",
-                                    parsed.to_string()
+                                    "msg=\"Parsed TOML contents\" input={:?} output={:?}",
+                                    log_options.contents(text.as_str()),
+                                    log_options.output(_parsed)
                                 );
                             }
                         }
-                        parsed
                     })
                     .map_err(|error| anyhow!(error))
             })
@@ -85,4 +104,20 @@ impl Parser for Toml {
             false
         })
     }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        Some(Box::new(*self))
+    }
+}
+
+/// `toml` serializes datetimes as `{"$__toml_private_datetime": "..."}` maps, so we replace them with their strings.
+fn datetimes_to_strings(value: &mut toml::Value) {
+    match value {
+        toml::Value::Datetime(datetime) => *value = toml::Value::String(datetime.to_string()),
+        toml::Value::Array(array) => array.iter_mut().for_each(datetimes_to_strings),
+        toml::Value::Table(table) => table
+            .iter_mut()
+            .for_each(|(_, value)| datetimes_to_strings(value)),
+        _ => (),
+    }
 }
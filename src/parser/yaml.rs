@@ -44,6 +44,31 @@
 //! assert_eq!(parsed.as_map().get("hello").unwrap(), &list);
 //! ```
 //!
+//! ### Timestamps
+//! YAML has no timestamp type in this parser, so timestamps are kept as strings exactly as written (e.g.
+//! `ts: 2001-12-15T02:59:43Z` is parsed to the string `"2001-12-15T02:59:43Z"`).
+//!
+//! Anchors, aliases, and merge keys are resolved:
+//! ```rust
+//! use plugx_config::parser::{Parser, yaml::Yaml};
+//! use plugx_input::Input;
+//!
+//! let bytes = br#"
+//! defaults: &defaults
+//!   host: localhost
+//!   port: 8080
+//! server:
+//!   <<: *defaults
+//!   port: 80
+//! "#;
+//!
+//! let parsed: Input = Yaml::new().try_parse(bytes.as_slice()).unwrap();
+//! let server = parsed.as_map().get("server").unwrap().as_map();
+//! assert!(!server.contains_key("<<"));
+//! assert_eq!(server.get("host").unwrap(), &"localhost".into());
+//! assert_eq!(server.get("port").unwrap(), &80.into());
+//! ```
+//!
 
 use crate::parser::Parser;
 use anyhow::anyhow;
@@ -73,23 +98,29 @@ impl Parser for Yaml {
 
     fn try_parse(&self, bytes: &[u8]) -> anyhow::Result<Input> {
         serde_yaml::from_slice(bytes)
-            .map(|parsed: Input| {
+            .and_then(|mut value: serde_yaml::Value| {
+                // `serde_yaml` resolves aliases but keeps merge keys as literal `<<` keys:
+                value.apply_merge()?;
+                serde_yaml::from_value(value)
+            })
+            .inspect(|_parsed: &Input| {
                 cfg_if! {
                     if #[cfg(feature = "tracing")] {
+                        let log_options = crate::LogOptions::current();
                         tracing::trace!(
-                            input=String::from_utf8_lossy(bytes).to_string(),
-                            output=%parsed,
+                            input=log_options.contents(String::from_utf8_lossy(bytes)),
+                            output=log_options.output(_parsed),
                             "Parsed YAML contents"
                         );
                     } else if #[cfg(feature = "logging")] {
+                        let log_options = crate::LogOptions::current();
                         log::trace!(
                             "msg=\"Parsed YAML contents\" input={:?} output={:?}",
-                            String::from_utf8_lossy(bytes).to_string(),
-                            parsed.to_string()
+                            log_options.contents(String::from_utf8_lossy(bytes)),
+                            log_options.output(_parsed)
                         );
                     }
                 }
-                parsed
             })
             .map_err(|error| anyhow!(error))
     }
@@ -97,4 +128,8 @@ impl Parser for Yaml {
     fn is_format_supported(&self, bytes: &[u8]) -> Option<bool> {
         Some(serde_yaml::from_slice::<serde_yaml::Value>(bytes).is_ok())
     }
+
+    fn boxed_clone(&self) -> Option<Box<dyn Parser>> {
+        Some(Box::new(*self))
+    }
 }
@@ -0,0 +1,42 @@
+#![cfg(feature = "aws")]
+
+use plugx_config::{
+    loader::{aws::SecretsManager, Error, Loader},
+    Url,
+};
+
+#[test]
+fn secrets_manager_without_request() {
+    let loader = SecretsManager::new().with_region("eu-west-1");
+    // Plugin `db` is not in whitelist, so nothing is requested:
+    let url: Url = "secretsmanager://prod/my-app/database?plugin=db"
+        .parse()
+        .unwrap();
+    let whitelist = ["foo".to_string()];
+    assert!(loader
+        .load(&url, Some(&whitelist), false)
+        .unwrap()
+        .is_empty());
+    // Without secret ID:
+    let url: Url = "secretsmanager://".parse().unwrap();
+    assert!(matches!(
+        loader.load(&url, None, true),
+        Err(Error::InvalidUrl { .. })
+    ));
+}
+
+#[test]
+fn secrets_manager_inside_current_thread_runtime() {
+    let loader = SecretsManager::new().with_region("eu-west-1");
+    let url: Url = "secretsmanager://prod/my-app/database?plugin=db"
+        .parse()
+        .unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    // Returns an error instead of panicking:
+    assert!(matches!(
+        runtime.block_on(async { loader.load(&url, None, false) }),
+        Err(Error::Load { .. })
+    ));
+}
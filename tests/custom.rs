@@ -0,0 +1,174 @@
+#![cfg(feature = "configuration")]
+
+use plugx_config::{
+    entity::ConfigurationEntity,
+    loader::{Error as LoaderError, Loader},
+    parser::Parser,
+    Configuration, Input, Url,
+};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+struct KeyValueLoader;
+
+impl Display for KeyValueLoader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key-Value")
+    }
+}
+
+impl Loader for KeyValueLoader {
+    fn scheme_list(&self) -> Vec<String> {
+        ["kv".into()].into()
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        maybe_whitelist: Option<&[String]>,
+        _skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, LoaderError> {
+        Ok([("foo", "hello:world"), ("bar", "x:1")]
+            .into_iter()
+            .filter(|(plugin_name, _)| {
+                maybe_whitelist
+                    .map(|whitelist| whitelist.contains(&plugin_name.to_string()))
+                    .unwrap_or(true)
+            })
+            .map(|(plugin_name, contents)| {
+                (
+                    plugin_name.to_string(),
+                    ConfigurationEntity::new(plugin_name, url.clone(), plugin_name, self.name())
+                        .with_format("kv")
+                        .with_contents(contents),
+                )
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+struct KeyValueParser;
+
+impl Display for KeyValueParser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key-Value")
+    }
+}
+
+impl Parser for KeyValueParser {
+    fn supported_format_list(&self) -> Vec<String> {
+        ["kv".into()].into()
+    }
+
+    fn try_parse(&self, bytes: &[u8]) -> anyhow::Result<Input> {
+        let text = String::from_utf8(bytes.to_vec())?;
+        let mut input = Input::new_map();
+        text.lines()
+            .filter_map(|line| line.split_once(':'))
+            .for_each(|(key, value)| {
+                input.map_mut().insert(key.into(), value.into());
+            });
+        Ok(input)
+    }
+
+    fn is_format_supported(&self, bytes: &[u8]) -> Option<bool> {
+        Some(bytes.contains(&b':'))
+    }
+}
+
+#[test]
+fn custom_loader_and_parser() {
+    let configuration = Configuration::new()
+        .with_loader(KeyValueLoader)
+        .with_parser(KeyValueParser)
+        .with_url("kv://".parse().unwrap())
+        .unwrap();
+    assert_eq!(KeyValueLoader.name(), "Key-Value");
+    assert_eq!(KeyValueParser.name(), "Key-Value");
+    let merged = configuration.load_parse_merge(false).unwrap();
+    let (_, foo) = merged
+        .iter()
+        .find(|(plugin_name, _)| plugin_name == "foo")
+        .expect("`foo` plugin config");
+    assert_eq!(foo.as_map().get("hello"), Some(&"world".into()));
+    let (_, bar) = merged
+        .iter()
+        .find(|(plugin_name, _)| plugin_name == "bar")
+        .expect("`bar` plugin config");
+    assert_eq!(bar.as_map().get("x"), Some(&"1".into()));
+}
+
+/// Handles any scheme ending in `+config` (e.g. `vault+config`).
+#[derive(Debug)]
+struct PatternLoader;
+
+impl Display for PatternLoader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Pattern")
+    }
+}
+
+impl Loader for PatternLoader {
+    fn scheme_list(&self) -> Vec<String> {
+        ["*+config".into()].into()
+    }
+
+    fn matches_scheme(&self, scheme: &str) -> bool {
+        scheme.ends_with("+config")
+    }
+
+    fn load(
+        &self,
+        url: &Url,
+        _maybe_whitelist: Option<&[String]>,
+        _skip_soft_errors: bool,
+    ) -> Result<Vec<(String, ConfigurationEntity)>, LoaderError> {
+        let (source, _) = url.scheme().split_once('+').unwrap();
+        Ok([(
+            "foo".to_string(),
+            ConfigurationEntity::new(source, url.clone(), "foo", self.name())
+                .with_format("kv")
+                .with_contents(format!("{source}:true")),
+        )]
+        .into())
+    }
+}
+
+#[test]
+fn loader_matching_scheme_pattern() {
+    let mut configuration = Configuration::new()
+        .with_loader(PatternLoader)
+        .with_parser(KeyValueParser)
+        .with_url("vault+config://".parse().unwrap())
+        .unwrap()
+        .with_url("s3+config://bucket/".parse().unwrap())
+        .unwrap();
+    assert!(configuration.has_loader(&"other+config://".parse().unwrap()));
+    assert!(!configuration.has_loader(&"config://".parse().unwrap()));
+    assert!(configuration.add_url("config://".parse().unwrap()).is_err());
+    let merged = configuration.load_parse_merge(false).unwrap();
+    let (_, foo) = merged
+        .iter()
+        .find(|(plugin_name, _)| plugin_name == "foo")
+        .expect("`foo` plugin config");
+    assert_eq!(foo.as_map().get("vault"), Some(&"true".into()));
+    assert_eq!(foo.as_map().get("s3"), Some(&"true".into()));
+}
+
+#[test]
+#[allow(deprecated)]
+fn former_trait_names() {
+    use plugx_config::{loader::ConfigurationLoader, parser::ConfigurationParser};
+
+    fn scheme_list<L: ConfigurationLoader>(loader: &L) -> Vec<String> {
+        loader.scheme_list()
+    }
+
+    fn format_list<P: ConfigurationParser>(parser: &P) -> Vec<String> {
+        parser.supported_format_list()
+    }
+
+    assert_eq!(scheme_list(&KeyValueLoader), vec!["kv".to_string()]);
+    assert_eq!(format_list(&KeyValueParser), vec!["kv".to_string()]);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "embedded")]
+
+use include_dir::{include_dir, Dir};
+use plugx_config::{
+    loader::{embedded::Embedded, Error, Loader},
+    Url,
+};
+
+static CONFIG_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/tests/etc");
+
+#[test]
+fn recursive() {
+    let loader = Embedded::new(&CONFIG_DIR);
+    let url: Url = "embedded:///".parse().unwrap();
+    assert_eq!(loader.load(&url, None, false).unwrap().len(), 4);
+    let url: Url = "embedded:///?recursive=true".parse().unwrap();
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 5);
+    assert!(loaded.iter().any(|(plugin_name, _)| plugin_name == "quux"));
+    let url: Url = "embedded:///nested".parse().unwrap();
+    assert_eq!(loader.load(&url, None, false).unwrap().len(), 1);
+}
+
+#[test]
+fn not_found() {
+    let loader = Embedded::new(&CONFIG_DIR);
+    let url: Url = "embedded:///nothing?soft-errors=not-found".parse().unwrap();
+    assert!(matches!(
+        loader.load(&url, None, false),
+        Err(Error::NotFound { .. })
+    ));
+    assert!(loader.load(&url, None, true).unwrap().is_empty());
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "configuration")]
+
+use plugx_config::{entity::ConfigurationEntity, Url};
+
+fn entity() -> ConfigurationEntity {
+    let url: Url = "xyz://".parse().unwrap();
+    ConfigurationEntity::new("item", url, "foo", "loader")
+}
+
+#[test]
+fn checksum() {
+    let hello = entity().with_contents("hello: world");
+    assert_eq!(
+        hello.checksum(),
+        entity().with_contents("hello: world").checksum()
+    );
+    assert_ne!(
+        hello.checksum(),
+        entity().with_contents("hello: World").checksum()
+    );
+    // FNV-1a of `a`:
+    assert_eq!(
+        entity().with_contents("a").checksum(),
+        Some(0xaf63dc4c8601ec8c)
+    );
+    assert_eq!(entity().checksum(), None);
+}
+
+#[test]
+fn checksum_of_parsed_contents() {
+    use plugx_config::Input;
+    use std::collections::HashMap;
+
+    let parsed = |value: &str| {
+        entity().with_parsed_contents(Input::from(HashMap::from([
+            ("hello".to_string(), Input::from(value)),
+            ("list".to_string(), Input::from(vec![Input::from(1)])),
+        ])))
+    };
+    let hello = parsed("world");
+    assert!(hello.checksum().is_some());
+    assert_eq!(hello.checksum(), parsed("world").checksum());
+    assert_ne!(hello.checksum(), parsed("World").checksum());
+    assert_ne!(
+        entity().with_parsed_contents(Input::new_map()).checksum(),
+        entity()
+            .with_parsed_contents(Input::from(Vec::<Input>::new()))
+            .checksum()
+    );
+}
+
+#[test]
+fn checksum_of_streamed_contents() {
+    use std::{
+        io::{Cursor, Read},
+        sync::Arc,
+    };
+
+    let streamed = entity().with_contents_reader(Arc::new(|| {
+        Ok(Box::new(Cursor::new(b"hello: world".to_vec())) as Box<dyn Read + Send>)
+    }));
+    assert_eq!(streamed.checksum(), None);
+}
+
+#[test]
+fn metadata() {
+    use plugx_config::{loader::closure::Closure, Configuration};
+
+    let configuration = Configuration::new()
+        .with_loader(Closure::new(
+            "metadata-loader",
+            Box::new(|url: &Url, _: Option<&[String]>, _: bool| {
+                let entity =
+                    ConfigurationEntity::new("item", url.clone(), "foo", "metadata-loader")
+                        .with_metadata("commit", "0123abc")
+                        .with_metadata("version", "3");
+                Ok(vec![("foo".to_string(), entity)])
+            }),
+            "metadata",
+        ))
+        .with_url("metadata://".parse().unwrap())
+        .unwrap();
+    let loaded = configuration.load(false).unwrap();
+    let metadata = loaded[0].1[0].metadata();
+    assert_eq!(metadata.get("commit"), Some(&"0123abc".to_string()));
+    assert_eq!(
+        metadata.keys().collect::<Vec<_>>(),
+        vec!["commit", "version"]
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn reparse() {
+    use plugx_config::{
+        parser::{json::Json, Parser},
+        Input,
+    };
+
+    let parser_list: Vec<Box<dyn Parser>> = vec![Box::new(Json::new())];
+    let mut entity = entity()
+        .with_format("json")
+        .with_contents(r#"{"port": 80}"#);
+    assert!(!entity.is_parsed());
+    entity.parse_contents_mut(parser_list.as_slice()).unwrap();
+    assert!(entity.is_parsed());
+
+    *entity.maybe_contents_mut() = Some(r#"{"port": 8080}"#.to_string());
+    // Parsed contents are still cached:
+    assert_eq!(
+        entity.maybe_parsed_contents().unwrap().as_map().get("port"),
+        Some(&Input::from(80))
+    );
+    let parsed = entity.reparse(parser_list.as_slice()).unwrap();
+    assert_eq!(parsed.as_map().get("port"), Some(&Input::from(8080)));
+
+    entity.clear_parsed();
+    assert!(!entity.is_parsed());
+
+    *entity.maybe_contents_mut() = Some("{".to_string());
+    entity
+        .parse_contents_mut(parser_list.as_slice())
+        .unwrap_err();
+    assert!(entity.reparse(parser_list.as_slice()).is_err());
+    assert!(!entity.is_parsed());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn merge_into() {
+    use plugx_config::{
+        parser::{json::Json, Parser},
+        Input,
+    };
+
+    let parser_list: Vec<Box<dyn Parser>> = vec![Box::new(Json::new())];
+    let first = entity()
+        .with_format("json")
+        .with_contents(r#"{"host": "localhost", "port": 80}"#);
+    let second_url: Url = "xyz://second".parse().unwrap();
+    let second = ConfigurationEntity::new("item", second_url, "foo", "loader")
+        .with_parsed_contents(Input::from(std::collections::HashMap::from([(
+            "port".to_string(),
+            Input::from(8080),
+        )])));
+
+    let mut merged = Input::new_map();
+    // Unparsed contents need a parser:
+    assert!(first.merge_into(&mut merged, "foo", &[]).is_err());
+    first
+        .merge_into(&mut merged, "foo", parser_list.as_slice())
+        .unwrap();
+    assert!(!first.is_parsed());
+    second.merge_into(&mut merged, "foo", &[]).unwrap();
+    assert_eq!(merged.as_map().get("host"), Some(&Input::from("localhost")));
+    assert_eq!(merged.as_map().get("port"), Some(&Input::from(8080)));
+}
+
+#[test]
+fn parser_not_found() {
+    use plugx_config::parser::{closure::Closure, Error, Parser};
+    use plugx_config::Input;
+
+    let parser_list: Vec<Box<dyn Parser>> = vec![
+        Box::new(Closure::new(
+            "A",
+            "a",
+            Box::new(|_: &[u8]| Ok(Input::new_map())),
+        )),
+        Box::new(
+            Closure::new("B", "b", Box::new(|_: &[u8]| Ok(Input::new_map())))
+                .with_format_list(&["b", "a"]),
+        ),
+    ];
+    let entity = entity().with_format("conf").with_contents("x");
+    let error = entity.parse_contents(parser_list.as_slice()).unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::ParserNotFound { format, available_format_list }
+            if format == "conf" && available_format_list == &["a", "b"]
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Could not found parser to parse format `conf` (available: a, b)"
+    );
+    assert!(entity
+        .parse_contents(&[])
+        .unwrap_err()
+        .to_string()
+        .ends_with("(available: none)"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn format_or_guess() {
+    use plugx_config::parser::{json::Json, Parser};
+
+    let parser_list: Vec<Box<dyn Parser>> = vec![Box::new(Json::new())];
+    let explicit = entity().with_format("yml").with_contents(r#"{"port": 80}"#);
+    assert_eq!(
+        explicit.format_or_guess(parser_list.as_slice()),
+        Some("yml".to_string())
+    );
+    let guessed = entity().with_contents(r#"{"port": 80}"#);
+    assert_eq!(
+        guessed.format_or_guess(parser_list.as_slice()),
+        Some("json".to_string())
+    );
+    assert_eq!(guessed.format_or_guess(&[]), None);
+    assert_eq!(entity().format_or_guess(parser_list.as_slice()), None);
+}
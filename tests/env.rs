@@ -0,0 +1,297 @@
+#![cfg(feature = "env")]
+
+use plugx_config::{
+    loader::{env::Env, Loader},
+    Url,
+};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn var_source() {
+    let var_list = Arc::new(Mutex::new(vec![
+        ("VAR_SOURCE__FOO__A".to_string(), "1".to_string()),
+        ("VAR_SOURCE__BAR__B".to_string(), "2".to_string()),
+        ("OTHER__BAZ__C".to_string(), "3".to_string()),
+    ]));
+    let source = var_list.clone();
+    let loader = Env::new()
+        .with_prefix("VAR_SOURCE")
+        .with_var_source(move || source.lock().unwrap().clone());
+    let url: Url = "env://".parse().unwrap();
+
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(
+        loaded
+            .iter()
+            .map(|(plugin_name, _)| plugin_name.as_str())
+            .collect::<Vec<_>>(),
+        ["foo", "bar"]
+    );
+
+    // Only changed ones:
+    *var_list.lock().unwrap() = vec![("VAR_SOURCE__BAR__B".to_string(), "4".to_string())];
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].1.maybe_contents(), Some(&"B=\"4\"".to_string()));
+}
+
+/// Serializes tests that change environment-variables of the process.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn watch_env() {
+    use plugx_config::{
+        entity::ConfigurationEntity,
+        loader::{closure::Closure, Error},
+        Configuration, Input,
+    };
+    use std::{
+        env,
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+    env::set_var("WATCH_ENV__FOO__BAR", "1");
+    // Fails only on its second call which is the first reload:
+    let call_count = Arc::new(AtomicUsize::new(0));
+    let loader_call_count = call_count.clone();
+    let flaky = Closure::new(
+        "flaky",
+        Box::new(move |url: &Url, _: Option<&[String]>, _: bool| {
+            if loader_call_count.fetch_add(1, Ordering::SeqCst) == 1 {
+                return Err(Error::NoAccess {
+                    loader: "flaky".to_string(),
+                    url: url.clone(),
+                });
+            }
+            let entity = ConfigurationEntity::new("baz", url.clone(), "baz", "flaky")
+                .with_parsed_contents(Input::new_map());
+            Ok(vec![("baz".to_string(), entity)])
+        }),
+        "flaky",
+    );
+    let configuration = Configuration::new()
+        .with_loader(flaky)
+        .with_url("flaky://".parse().unwrap())
+        .unwrap()
+        .with_url("env://?prefix=WATCH_ENV".parse().unwrap())
+        .unwrap();
+    let mut changed = None;
+    thread::scope(|scope| {
+        let handle = scope.spawn(|| {
+            configuration.watch_env(Duration::from_millis(10), false, |merged| {
+                changed = Some(merged.to_vec());
+                false
+            })
+        });
+        thread::sleep(Duration::from_millis(200));
+        // Nothing is reloaded while environment-variables are not changed:
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        env::set_var("WATCH_ENV__FOO__BAR", "2");
+        handle.join().unwrap().unwrap();
+    });
+    env::remove_var("WATCH_ENV__FOO__BAR");
+    // The failed reload is retried:
+    assert_eq!(call_count.load(Ordering::SeqCst), 3);
+    let changed = changed.unwrap();
+    let (_, foo) = changed.iter().find(|(name, _)| name == "foo").unwrap();
+    assert_eq!(foo.as_map().get("bar"), Some(&Input::from(2)));
+}
+
+#[test]
+fn supports_soft_errors() {
+    use plugx_config::{loader::map::MappedLoader, Configuration};
+
+    assert!(!Env::new().supports_soft_errors());
+    assert!(!MappedLoader::new(Env::new(), Box::new(|loaded| loaded)).supports_soft_errors());
+
+    let configuration = Configuration::new()
+        .with_loader(
+            Env::new()
+                .with_var_source(|| vec![("SOFT_ERRORS__FOO__A".to_string(), "1".to_string())]),
+        )
+        .with_url("env://?prefix=SOFT_ERRORS".parse().unwrap())
+        .unwrap();
+    let plan = configuration.plan(true).unwrap();
+    assert_eq!(plan.len(), 1);
+    assert!(!plan[0].supports_soft_errors());
+    assert_eq!(configuration.load_parse_merge(true).unwrap().len(), 1);
+}
+
+#[test]
+fn empty_value_policy() {
+    use plugx_config::loader::{env::EmptyValuePolicy, Error};
+
+    let loader = Env::new().with_prefix("EMPTY_VALUE").with_var_source(|| {
+        vec![
+            ("EMPTY_VALUE__FOO__A".to_string(), "1".to_string()),
+            ("EMPTY_VALUE__FOO__B".to_string(), "".to_string()),
+            ("EMPTY_VALUE__BAR__C".to_string(), "".to_string()),
+            ("EMPTY_VALUE__BAZ__D".to_string(), "2".to_string()),
+        ]
+    });
+    let contents = |loaded: &[(String, plugx_config::entity::ConfigurationEntity)]| {
+        loaded
+            .iter()
+            .map(|(plugin_name, entity)| {
+                (
+                    plugin_name.clone(),
+                    entity.maybe_contents().cloned().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let url: Url = "env://".parse().unwrap();
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(
+        contents(&loaded),
+        [
+            ("foo".to_string(), "A=\"1\"\nB=\"\"".to_string()),
+            ("bar".to_string(), "C=\"\"".to_string()),
+            ("baz".to_string(), "D=\"2\"".to_string()),
+        ]
+    );
+
+    let url: Url = "env://?empty_value_policy=skip".parse().unwrap();
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(
+        contents(&loaded),
+        [
+            ("foo".to_string(), "A=\"1\"".to_string()),
+            ("baz".to_string(), "D=\"2\"".to_string()),
+        ]
+    );
+
+    let url: Url = "env://?empty_value_policy=error".parse().unwrap();
+    let error = loader.load(&url, None, false).unwrap_err();
+    assert!(matches!(error, Error::Load { .. }));
+    assert!(error.to_string().contains("`EMPTY_VALUE__FOO__B`"));
+    // Only variables that are loaded are checked:
+    let whitelist = ["baz".to_string()];
+    assert_eq!(
+        contents(&loader.load(&url, Some(&whitelist), false).unwrap()),
+        [("baz".to_string(), "D=\"2\"".to_string())]
+    );
+    let lenient_loader = Env::new().with_prefix("EMPTY_VALUE").with_var_source(|| {
+        vec![
+            ("EMPTY_VALUE__FOO__A".to_string(), "1".to_string()),
+            // Without a key after the plugin name:
+            ("EMPTY_VALUE__QUX".to_string(), "".to_string()),
+        ]
+    });
+    assert_eq!(
+        contents(&lenient_loader.load(&url, None, false).unwrap()),
+        [("foo".to_string(), "A=\"1\"".to_string())]
+    );
+
+    // Programmatic options take precedence over URL options:
+    let loader = loader.with_empty_value_policy(EmptyValuePolicy::Skip);
+    assert_eq!(contents(&loader.load(&url, None, false).unwrap()).len(), 2);
+    let url: Url = "env://?empty_value_policy=invalid".parse().unwrap();
+    assert!(loader.validate_url(&url).is_err());
+}
+
+#[test]
+fn env_url_for_current_crate() {
+    // Integration tests are compiled as crate `env`:
+    let url = plugx_config::env_url_for_current_crate!();
+    assert_eq!(url.scheme(), "env");
+    assert_eq!(url.query(), Some("prefix=ENV"));
+
+    let loader = Env::new().with_var_source(|| {
+        vec![
+            ("ENV__FOO__A".to_string(), "1".to_string()),
+            ("PLUGX_CONFIG__BAR__B".to_string(), "2".to_string()),
+        ]
+    });
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+}
+
+#[test]
+fn custom_separator() {
+    use plugx_config::{loader::env::KEY_SEPARATOR_METADATA_KEY, Configuration, Input};
+
+    let configuration = Configuration::new()
+        .with_loader(Env::new().with_var_source(|| {
+            vec![
+                ("CUSTOM_X_FOO_X_A_X_B".to_string(), "1".to_string()),
+                ("CUSTOM_X_FOO_X_C__D".to_string(), "2".to_string()),
+            ]
+        }))
+        .with_url("env://?prefix=CUSTOM&separator=_X_".parse().unwrap())
+        .unwrap();
+    let loaded = configuration.load(false).unwrap();
+    assert_eq!(
+        loaded[0].1[0].metadata().get(KEY_SEPARATOR_METADATA_KEY),
+        Some(&"_X_".to_string())
+    );
+    let merged = configuration.load_parse_merge(false).unwrap();
+    assert_eq!(merged.len(), 1);
+    let foo = merged[0].1.as_map();
+    assert_eq!(
+        foo.get("a").map(|a| a.as_map().get("b")),
+        Some(Some(&Input::from(1)))
+    );
+    // The default separator is not used for nesting anymore:
+    assert_eq!(foo.get("c__d"), Some(&Input::from(2)));
+}
+
+#[test]
+fn prefix_and_separator() {
+    let var_list = || {
+        vec![
+            ("APP__FOO__A".to_string(), "1".to_string()),
+            ("APP__BAR__B__C".to_string(), "2".to_string()),
+            ("APPLE__BAZ__D".to_string(), "3".to_string()),
+            ("__QUX__E".to_string(), "4".to_string()),
+        ]
+    };
+    let load = |loader: Env| {
+        let url: Url = "env://".parse().unwrap();
+        loader
+            .with_var_source(var_list)
+            .load(&url, None, false)
+            .unwrap()
+            .into_iter()
+            .map(|(plugin_name, entity)| (plugin_name, entity.maybe_contents().unwrap().clone()))
+            .collect::<Vec<_>>()
+    };
+    let app = vec![
+        ("foo".to_string(), "A=\"1\"".to_string()),
+        ("bar".to_string(), "B__C=\"2\"".to_string()),
+    ];
+
+    // Empty prefix with `strip_prefix` loads everything and a leading separator is not a plugin:
+    assert_eq!(
+        load(Env::new().with_prefix("").with_strip_prefix(true)),
+        vec![
+            (
+                "app".to_string(),
+                "FOO__A=\"1\"\nBAR__B__C=\"2\"".to_string()
+            ),
+            ("apple".to_string(), "BAZ__D=\"3\"".to_string()),
+        ]
+    );
+    // Separator is appended to the prefix (so `APPLE` does not match):
+    assert_eq!(load(Env::new().with_prefix("APP")), app);
+    // Separator is not appended twice:
+    assert_eq!(load(Env::new().with_prefix("APP__")), app);
+    assert_eq!(
+        load(Env::new().with_prefix("APP_X_").with_separator("_X_")),
+        Vec::new()
+    );
+    // Plugin names can not be detected without a separator:
+    assert_eq!(
+        load(Env::new().with_prefix("APP").with_separator("")),
+        Vec::new()
+    );
+    assert_eq!(
+        load(Env::new().with_prefix("").with_separator("")),
+        Vec::new()
+    );
+}
@@ -0,0 +1,815 @@
+#![cfg(feature = "fs")]
+
+use plugx_config::{
+    loader::{fs::Fs, Loader},
+    Url,
+};
+use std::{env, fs};
+use tempdir::TempDir;
+
+#[test]
+fn expand_env() {
+    let tmp_dir = TempDir::new("fs-expand-env").unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "HELLO=world").unwrap();
+    env::set_var("PLUGX_FS_EXPAND_ENV_DIR", tmp_dir.path().to_str().unwrap());
+    let loader = Fs::new();
+
+    let url: Url = "file:${PLUGX_FS_EXPAND_ENV_DIR}?expand-env=true"
+        .parse()
+        .unwrap();
+    assert_eq!(loader.load(&url, None, false).unwrap().len(), 1);
+
+    let url: Url = "file:$PLUGX_FS_EXPAND_ENV_DIR/foo.env?expand-env=true"
+        .parse()
+        .unwrap();
+    assert_eq!(loader.load(&url, None, false).unwrap().len(), 1);
+
+    let url: Url = format!(
+        "file:${{PLUGX_FS_EXPAND_ENV_NOT_SET:-{}}}",
+        tmp_dir.path().to_str().unwrap()
+    )
+    .parse()
+    .unwrap();
+    assert!(loader.load(&url, None, false).is_err());
+    assert_eq!(
+        Fs::new()
+            .with_expand_env(true)
+            .load(&url, None, false)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let url: Url = "file:${PLUGX_FS_EXPAND_ENV_NOT_SET}/foo.env?expand-env=true"
+        .parse()
+        .unwrap();
+    let error = loader.load(&url, None, true).unwrap_err();
+    assert!(format!("{:#}", anyhow::anyhow!(error)).contains("PLUGX_FS_EXPAND_ENV_NOT_SET"));
+}
+
+#[test]
+fn plan() {
+    let tmp_dir = TempDir::new("fs-plan").unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "HELLO=world").unwrap();
+    fs::write(tmp_dir.path().join("bar.yml"), "hello: world").unwrap();
+    let url: Url = format!("file://{}", tmp_dir.path().to_str().unwrap())
+        .parse()
+        .unwrap();
+    let configuration = plugx_config::Configuration::new()
+        .with_url(url.clone())
+        .unwrap();
+    let mut plan = configuration.plan(false).unwrap();
+    plan.sort_by_key(|item| item.maybe_plugin_name().cloned());
+    assert_eq!(plan.len(), 2);
+    assert_eq!(plan[0].url(), &url);
+    assert_eq!(plan[0].maybe_plugin_name(), Some(&"bar".to_string()));
+    assert_eq!(plan[0].maybe_format(), Some(&"yml".to_string()));
+    assert_eq!(plan[1].maybe_plugin_name(), Some(&"foo".to_string()));
+    assert!(!plan[1].is_unknown());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn stream_threshold() {
+    use plugx_config::{Configuration, Error, Input};
+
+    let tmp_dir = TempDir::new("fs-stream-threshold").unwrap();
+    fs::write(tmp_dir.path().join("foo.json"), r#"{"list": [1, 2, 3]}"#).unwrap();
+    fs::write(tmp_dir.path().join("bar.json"), "{}").unwrap();
+    let url: Url = format!(
+        "file://{}?stream-threshold=10",
+        tmp_dir.path().to_str().unwrap()
+    )
+    .parse()
+    .unwrap();
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    let (_, foo) = loaded
+        .iter()
+        .find(|(plugin_name, _)| plugin_name == "foo")
+        .unwrap();
+    // Loaders only load, the registered parser parses it from the file:
+    assert!(foo.maybe_contents().is_none());
+    assert!(foo.maybe_contents_reader().is_some());
+    assert!(!foo.is_parsed());
+    let (_, bar) = loaded
+        .iter()
+        .find(|(plugin_name, _)| plugin_name == "bar")
+        .unwrap();
+    assert_eq!(bar.maybe_contents(), Some(&"{}".to_string()));
+
+    let configuration = Configuration::new().with_url(url).unwrap();
+    let merged = configuration.load_parse_merge(false).unwrap();
+    let (_, foo) = merged.iter().find(|(name, _)| name == "foo").unwrap();
+    let list = foo.as_map().get("list").unwrap().as_list();
+    assert_eq!(
+        list.as_slice(),
+        &[Input::from(1), Input::from(2), Input::from(3)]
+    );
+
+    fs::write(tmp_dir.path().join("foo.json"), r#"{"list": [1, 2, 3"#).unwrap();
+    assert!(matches!(
+        configuration.load_parse_merge(false),
+        Err(Error::Parse { .. })
+    ));
+}
+
+#[test]
+fn add_path() {
+    use plugx_config::Configuration;
+
+    let tmp_dir = TempDir::new("fs-add-path").unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "HELLO=world").unwrap();
+    let configuration = Configuration::new().with_path(tmp_dir.path()).unwrap();
+    assert_eq!(configuration.url_list()[0].scheme(), "file");
+    assert!(configuration.url_list()[0].path().ends_with('/'));
+    assert_eq!(configuration.load(false).unwrap().len(), 1);
+    let configuration = Configuration::new()
+        .with_path(tmp_dir.path().join("foo.env"))
+        .unwrap();
+    assert_eq!(configuration.load(false).unwrap().len(), 1);
+    assert!(Configuration::new().with_path("relative/path").is_err());
+}
+
+#[cfg(all(feature = "json", feature = "toml"))]
+#[test]
+fn normalize_source() {
+    use plugx_config::Configuration;
+
+    let tmp_dir = TempDir::new("fs-normalize-source").unwrap();
+    let foo = tmp_dir.path().join("foo.json");
+    fs::write(&foo, r#"{"list":[1,2],   "hello" :"world"}"#).unwrap();
+    let bar = tmp_dir.path().join("bar.toml");
+    fs::write(&bar, "# Comment\nhello   =   'world'\n").unwrap();
+    let configuration = Configuration::new().with_path(tmp_dir.path()).unwrap();
+
+    let normalized = configuration
+        .normalize_source(&Url::from_file_path(&foo).unwrap())
+        .unwrap();
+    assert!(normalized.contains("  \"hello\": \"world\""));
+    let normalized = configuration
+        .normalize_source(&Url::from_file_path(&bar).unwrap())
+        .unwrap();
+    assert_eq!(normalized, "hello = \"world\"\n");
+    // Aliases and input preprocessors are applied like other configurations:
+    let normalized = Configuration::new()
+        .with_alias("bar", "hello", "greeting")
+        .with_sort_keys(true)
+        .with_input_preprocessor(Box::new(
+            |_: &str, _: &Url, input: &mut plugx_config::Input| {
+                input.map_mut().insert("preprocessed".into(), true.into());
+            },
+        ))
+        .normalize_source(&Url::from_file_path(&bar).unwrap())
+        .unwrap();
+    assert_eq!(normalized, "greeting = \"world\"\npreprocessed = true\n");
+    // More than one configuration:
+    assert!(configuration
+        .normalize_source(&Url::from_directory_path(tmp_dir.path()).unwrap())
+        .is_err());
+}
+
+#[test]
+fn error_from_io() {
+    use plugx_config::loader::{fs::SoftErrorsFs, Error, SoftErrors};
+    use std::io;
+
+    let url: Url = "file:///etc/app".parse().unwrap();
+    let from_io = |kind: io::ErrorKind, soft_errors: &SoftErrors<SoftErrorsFs>, skip: bool| {
+        Error::from_io(
+            "test",
+            &url,
+            "read",
+            io::Error::from(kind),
+            soft_errors,
+            skip,
+        )
+    };
+    let not_found = SoftErrors::new_list().with_soft_error(SoftErrorsFs::NotFound);
+    let permission_denied = SoftErrors::new_list().with_soft_error(SoftErrorsFs::PermissionDenied);
+    let all = SoftErrors::new_all();
+
+    assert!(from_io(io::ErrorKind::NotFound, &not_found, true).is_ok());
+    assert!(from_io(io::ErrorKind::NotFound, &not_found, false).is_err());
+    assert!(from_io(io::ErrorKind::NotFound, &permission_denied, true).is_err());
+    assert!(from_io(io::ErrorKind::PermissionDenied, &permission_denied, true).is_ok());
+    assert!(from_io(io::ErrorKind::PermissionDenied, &not_found, true).is_err());
+    assert!(from_io(io::ErrorKind::InvalidData, &not_found, true).is_err());
+    assert!(from_io(io::ErrorKind::InvalidData, &all, true).is_ok());
+    assert!(matches!(
+        from_io(io::ErrorKind::InvalidData, &all, false),
+        Err(Error::Load { .. })
+    ));
+}
+
+#[test]
+fn validate_url() {
+    use plugx_config::Configuration;
+
+    let url: Url = "file:///etc/app?strip-slash=notabool".parse().unwrap();
+    assert!(Fs::new().validate_url(&url).is_err());
+    assert!(Configuration::new().with_url(url).is_err());
+    let url: Url = "file:///etc/app?strip-slash=true".parse().unwrap();
+    assert!(Configuration::new().with_url(url).is_ok());
+}
+
+#[test]
+fn max_file_size() {
+    use plugx_config::loader::{fs::SoftErrorsFs, Error};
+
+    let tmp_dir = TempDir::new("fs-max-file-size").unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "HELLO=world").unwrap();
+    fs::write(tmp_dir.path().join("bar.env"), "HELLO=world\n".repeat(100)).unwrap();
+    let url: Url = format!("file://{}", tmp_dir.path().to_str().unwrap())
+        .parse()
+        .unwrap();
+
+    let loader = Fs::new().with_max_file_size(100);
+    assert!(matches!(
+        loader.load(&url, None, false),
+        Err(Error::Load { .. })
+    ));
+    let loader = loader.with_soft_error(SoftErrorsFs::TooLarge);
+    let loaded = loader.load(&url, None, true).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+
+    let url: Url = format!(
+        "file://{}?max-file-size=100&soft-errors=too-large",
+        tmp_dir.path().to_str().unwrap()
+    )
+    .parse()
+    .unwrap();
+    assert_eq!(Fs::new().load(&url, None, true).unwrap().len(), 1);
+    assert!(Fs::new().load(&url, None, false).is_err());
+}
+
+#[cfg(all(feature = "json", feature = "toml"))]
+#[test]
+fn normalize_source_sort_keys() {
+    use plugx_config::Configuration;
+
+    let tmp_dir = TempDir::new("fs-normalize-source-sort-keys").unwrap();
+    let foo = tmp_dir.path().join("foo.json");
+    fs::write(
+        &foo,
+        r#"{"c": {"z": 1, "y": [{"b": 2, "a": 1}]}, "b": 2, "a": 1}"#,
+    )
+    .unwrap();
+    let bar = tmp_dir.path().join("bar.toml");
+    fs::write(&bar, "z = 1\na = 2\n[m]\ny = 3\nx = 4\n").unwrap();
+    let configuration = Configuration::new()
+        .with_path(tmp_dir.path())
+        .unwrap()
+        .with_sort_keys(true);
+
+    let normalized = configuration
+        .normalize_source(&Url::from_file_path(&foo).unwrap())
+        .unwrap();
+    assert_eq!(
+        normalized.split_whitespace().collect::<String>(),
+        r#"{"a":1,"b":2,"c":{"y":[{"a":1,"b":2}],"z":1}}"#
+    );
+    let normalized = configuration
+        .normalize_source(&Url::from_file_path(&bar).unwrap())
+        .unwrap();
+    assert_eq!(normalized, "a = 2\nz = 1\n\n[m]\nx = 4\ny = 3\n");
+}
+
+#[test]
+fn plugin_name_fn() {
+    let tmp_dir = TempDir::new("fs-plugin-name-fn").unwrap();
+    fs::write(tmp_dir.path().join("10-Database.env"), "HOST=localhost").unwrap();
+    fs::write(tmp_dir.path().join("20-cache.env"), "SIZE=10").unwrap();
+    fs::write(tmp_dir.path().join("README.env"), "").unwrap();
+    let url = Url::from_directory_path(tmp_dir.path()).unwrap();
+
+    let loader = Fs::new();
+    let mut loaded = loader.load(&url, None, false).unwrap();
+    loaded.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let plugin_name_list: Vec<_> = loaded.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(plugin_name_list, ["10-database", "20-cache", "readme"]);
+
+    // Strips ordering prefixes and skips files without them:
+    let loader = Fs::new().with_plugin_name_fn(|path| {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.split_once('-'))
+            .filter(|(order, _)| order.chars().all(|c| c.is_ascii_digit()))
+            .map(|(_, name)| name.to_string())
+    });
+    let mut loaded = loader.load(&url, None, false).unwrap();
+    loaded.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let plugin_name_list: Vec<_> = loaded.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(plugin_name_list, ["cache", "database"]);
+    assert_eq!(loaded[1].1.plugin_name(), "database");
+
+    let whitelist = ["database".to_string()];
+    assert_eq!(loader.load(&url, Some(&whitelist), false).unwrap().len(), 1);
+
+    let url = Url::from_file_path(tmp_dir.path().join("10-Database.env")).unwrap();
+    let loaded = loader.load(&url, None, false).unwrap();
+    assert_eq!(loaded[0].0, "database");
+}
+
+#[test]
+fn soft_errors_all() {
+    use plugx_config::loader::fs::SoftErrorsFs;
+
+    let tmp_dir = TempDir::new("fs-soft-errors-all").unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "HELLO=world").unwrap();
+    let loader = Fs::new().with_max_file_size(1);
+    let url = Url::from_directory_path(tmp_dir.path()).unwrap();
+    assert!(loader.load(&url, None, true).is_err());
+
+    // URL soft errors are merged with the ones set on the loader:
+    let url: Url = format!("{url}?soft-errors=not-found").parse().unwrap();
+    let loader = loader.with_soft_error(SoftErrorsFs::TooLarge);
+    assert!(loader.load(&url, None, true).unwrap().is_empty());
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn encoding() {
+    use plugx_config::loader::fs::Encoding;
+
+    let tmp_dir = TempDir::new("fs-encoding").unwrap();
+    let utf16le: Vec<u8> = [0xFF, 0xFE]
+        .into_iter()
+        .chain("HELLO=wörld".encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    fs::write(tmp_dir.path().join("foo.env"), utf16le).unwrap();
+    let utf16be: Vec<u8> = [0xFE, 0xFF]
+        .into_iter()
+        .chain("HELLO=wörld".encode_utf16().flat_map(u16::to_be_bytes))
+        .collect();
+    fs::write(tmp_dir.path().join("bar.env"), utf16be).unwrap();
+    fs::write(
+        tmp_dir.path().join("baz.env"),
+        b"\xEF\xBB\xBFHELLO=w\xC3\xB6rld",
+    )
+    .unwrap();
+    let url = Url::from_directory_path(tmp_dir.path()).unwrap();
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 3);
+    loaded.iter().for_each(|(_, entity)| {
+        assert_eq!(entity.maybe_contents().unwrap(), "HELLO=wörld");
+    });
+
+    let latin1 = tmp_dir.path().join("latin1");
+    fs::create_dir(&latin1).unwrap();
+    fs::write(latin1.join("qux.env"), b"HELLO=w\xF6rld").unwrap();
+    let url = Url::from_directory_path(&latin1).unwrap();
+    assert!(Fs::new().load(&url, None, false).is_err());
+    let url: Url = format!("{url}?encoding=latin-1").parse().unwrap();
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    assert_eq!(loaded[0].1.maybe_contents().unwrap(), "HELLO=wörld");
+    let url = Url::from_directory_path(&latin1).unwrap();
+    let loaded = Fs::new()
+        .with_encoding(Encoding::Latin1)
+        .load(&url, None, false)
+        .unwrap();
+    assert_eq!(loaded[0].1.maybe_contents().unwrap(), "HELLO=wörld");
+
+    assert_eq!(
+        Encoding::decode(vec![0x00, b'a'], Some(Encoding::Utf16Be)).unwrap(),
+        "a"
+    );
+    assert!(Encoding::decode(vec![0xFF, 0xFE, b'a'], None).is_err());
+}
+
+#[cfg(all(feature = "encoding", feature = "json"))]
+#[test]
+fn encoding_with_stream_threshold() {
+    let tmp_dir = TempDir::new("fs-encoding-stream-threshold").unwrap();
+    let utf16le: Vec<u8> = [0xFF, 0xFE]
+        .into_iter()
+        .chain(r#"{"hello": "wörld"}"#.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    fs::write(tmp_dir.path().join("foo.json"), utf16le).unwrap();
+    fs::write(
+        tmp_dir.path().join("bar.json"),
+        b"{\"hello\": \"w\xF6rld\"}",
+    )
+    .unwrap();
+    let url: Url = format!(
+        "file://{}?stream-threshold=1&encoding=latin-1",
+        tmp_dir.path().to_str().unwrap()
+    )
+    .parse()
+    .unwrap();
+    // Files that need decoding are not streamed:
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 2);
+    loaded.iter().for_each(|(_, entity)| {
+        assert!(entity.maybe_contents_reader().is_none());
+        assert_eq!(entity.maybe_contents().unwrap(), r#"{"hello": "wörld"}"#);
+    });
+}
+
+#[cfg(not(feature = "encoding"))]
+#[test]
+fn encoding_without_feature() {
+    let tmp_dir = TempDir::new("fs-encoding-without-feature").unwrap();
+    let url: Url = format!(
+        "file://{}?encoding=latin-1",
+        tmp_dir.path().to_str().unwrap()
+    )
+    .parse()
+    .unwrap();
+    assert!(matches!(
+        Fs::new().validate_url(&url),
+        Err(plugx_config::loader::Error::InvalidUrl { .. })
+    ));
+}
+
+#[test]
+fn percent_encoded_path() {
+    let tmp_dir = TempDir::new("fs-percent-encoded-path").unwrap();
+    for dir_name in ["my app", "café ☕", "100%", "a%20b", "#hash?"] {
+        let dir = tmp_dir.path().join(dir_name);
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("foo.env"), "HELLO=world").unwrap();
+        let loader = Fs::new();
+
+        let url = Url::from_directory_path(&dir).unwrap();
+        assert!(url.path().contains("%"));
+        let loaded = loader.load(&url, None, false).unwrap();
+        assert_eq!(loaded.len(), 1, "{dir_name}");
+
+        let url = Url::from_file_path(dir.join("foo.env")).unwrap();
+        let loaded = loader.load(&url, None, false).unwrap();
+        assert_eq!(loaded.len(), 1, "{dir_name}");
+        assert_eq!(
+            fs::read_to_string(loaded[0].1.item()).unwrap(),
+            "HELLO=world"
+        );
+        assert_eq!(url.to_file_path().unwrap(), dir.join("foo.env"));
+    }
+}
+
+#[test]
+fn tail() {
+    use std::io::Write;
+
+    let tmp_dir = TempDir::new("fs-tail").unwrap();
+    let path = tmp_dir.path().join("foo.env");
+    fs::write(&path, "A=1\nB=2\n").unwrap();
+    let url = Url::from_file_path(&path).unwrap();
+    let contents = |loader: &Fs, url: &Url| {
+        loader
+            .load(url, None, false)
+            .unwrap()
+            .into_iter()
+            .map(|(_, entity)| entity.maybe_contents().cloned().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let loader = Fs::new().with_tail(true);
+    assert_eq!(contents(&loader, &url), vec!["A=1\nB=2\n"]);
+    // Nothing is appended:
+    assert!(contents(&loader, &url).is_empty());
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"B=3\nC=").unwrap();
+    // Incomplete line is not read:
+    assert_eq!(contents(&loader, &url), vec!["B=3\n"]);
+    file.write_all(b"4\n").unwrap();
+    assert_eq!(contents(&loader, &url), vec!["C=4\n"]);
+    // Clones copy offsets but do not share them:
+    let cloned_loader = loader.clone();
+    assert!(contents(&cloned_loader, &url).is_empty());
+    file.write_all(b"D=5\n").unwrap();
+    assert_eq!(contents(&cloned_loader, &url), vec!["D=5\n"]);
+    assert_eq!(contents(&loader, &url), vec!["D=5\n"]);
+
+    // Truncated files are read from the beginning:
+    fs::write(&path, "E=6\n").unwrap();
+    assert_eq!(contents(&loader, &url), vec!["E=6\n"]);
+    loader.reset_tail();
+    assert_eq!(contents(&loader, &url), vec!["E=6\n"]);
+    // So are files that are rewritten past the previous offset:
+    fs::write(&path, "F=7\nG=8\n").unwrap();
+    assert_eq!(contents(&loader, &url), vec!["F=7\nG=8\n"]);
+    // And replaced files:
+    fs::write(tmp_dir.path().join("new"), "H=9\nI=10\n").unwrap();
+    fs::rename(tmp_dir.path().join("new"), &path).unwrap();
+    assert_eq!(contents(&loader, &url), vec!["H=9\nI=10\n"]);
+
+    // `max-file-size` applies to appended bytes:
+    let loader = Fs::new().with_tail(true).with_max_file_size(8);
+    assert!(loader.load(&url, None, false).is_err());
+    let loader = Fs::new().with_tail(true).with_max_file_size(10);
+    assert_eq!(contents(&loader, &url), vec!["H=9\nI=10\n"]);
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"J=11\n").unwrap();
+    let loader = loader.with_max_file_size(5);
+    assert_eq!(contents(&loader, &url), vec!["J=11\n"]);
+    fs::write(&path, "D=5\n").unwrap();
+
+    // Via URL option:
+    let mut url = url.clone();
+    url.set_query(Some("tail=true"));
+    let loader = Fs::new();
+    assert_eq!(contents(&loader, &url), vec!["D=5\n"]);
+    assert!(contents(&loader, &url).is_empty());
+
+    // Only actual loads advance offsets:
+    let configuration = plugx_config::Configuration::new()
+        .with_url(url.clone())
+        .unwrap();
+    configuration.dump_debug(None, false).unwrap();
+    assert_eq!(configuration.load(false).unwrap().len(), 1);
+    assert!(configuration.load(false).unwrap().is_empty());
+
+    // Without it:
+    url.set_query(None);
+    assert_eq!(contents(&loader, &url), vec!["D=5\n"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn recursive_and_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let tmp_dir = TempDir::new("fs-recursive").unwrap();
+    let sub_dir = tmp_dir.path().join("sub");
+    fs::create_dir(&sub_dir).unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "A=1").unwrap();
+    fs::write(sub_dir.join("bar.env"), "B=2").unwrap();
+    // `sub/loop` -> `.` -> `sub` -> ...
+    symlink(tmp_dir.path(), sub_dir.join("loop")).unwrap();
+    let other_tmp_dir = TempDir::new("fs-recursive-other").unwrap();
+    fs::write(other_tmp_dir.path().join("qux.env"), "C=3").unwrap();
+    symlink(
+        other_tmp_dir.path().join("qux.env"),
+        sub_dir.join("qux.env"),
+    )
+    .unwrap();
+    let url = Url::from_directory_path(tmp_dir.path()).unwrap();
+    let plugin_name_list = |loader: Fs| {
+        let mut plugin_name_list: Vec<_> = loader
+            .load(&url, None, false)
+            .unwrap()
+            .into_iter()
+            .map(|(plugin_name, _)| plugin_name)
+            .collect();
+        plugin_name_list.sort();
+        plugin_name_list
+    };
+
+    assert_eq!(plugin_name_list(Fs::new()), vec!["foo"]);
+    assert_eq!(
+        plugin_name_list(Fs::new().with_recursive(true)),
+        vec!["bar", "foo", "qux"]
+    );
+    assert_eq!(
+        plugin_name_list(Fs::new().with_recursive(true).with_follow_symlinks(false)),
+        vec!["bar", "foo"]
+    );
+    let mut url = url.clone();
+    url.set_query(Some("recursive=true&follow-symlinks=false"));
+    assert_eq!(Fs::new().load(&url, None, false).unwrap().len(), 2);
+
+    // Files of a plugin in different directories are merged:
+    fs::write(sub_dir.join("foo.env"), "A=2").unwrap();
+    url.set_query(Some("recursive=true&follow-symlinks=false"));
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    let foo_list: Vec<_> = loaded
+        .iter()
+        .filter(|(plugin_name, _)| plugin_name == "foo")
+        .map(|(_, entity)| entity.maybe_contents().unwrap().as_str())
+        .collect();
+    assert_eq!(foo_list, vec!["A=1", "A=2"]);
+    // But not in the same directory:
+    fs::write(sub_dir.join("foo.json"), "{}").unwrap();
+    assert!(matches!(
+        Fs::new().load(&url, None, false),
+        Err(plugx_config::loader::Error::Duplicate { .. })
+    ));
+}
+
+#[cfg(feature = "env")]
+#[test]
+fn plugin_per_dir() {
+    use plugx_config::{Configuration, Input};
+
+    let tmp_dir = TempDir::new("fs-plugin-per-dir").unwrap();
+    let foo_dir = tmp_dir.path().join("Foo");
+    fs::create_dir(&foo_dir).unwrap();
+    fs::write(foo_dir.join("10-base.env"), "A=1\nB=1").unwrap();
+    fs::write(foo_dir.join("20-override.env"), "B=2").unwrap();
+    let bar_dir = tmp_dir.path().join("bar");
+    fs::create_dir(&bar_dir).unwrap();
+    fs::write(bar_dir.join("bar.env"), "C=3").unwrap();
+    // Files directly inside the directory are skipped:
+    fs::write(tmp_dir.path().join("baz.env"), "D=4").unwrap();
+    let mut url = Url::from_directory_path(tmp_dir.path()).unwrap();
+
+    let loaded = Fs::new()
+        .with_plugin_per_dir(true)
+        .load(&url, None, false)
+        .unwrap();
+    let mut plugin_name_list: Vec<_> = loaded.iter().map(|(name, _)| name.as_str()).collect();
+    plugin_name_list.sort();
+    plugin_name_list.dedup();
+    assert_eq!(plugin_name_list, vec!["bar", "foo"]);
+    assert_eq!(
+        Fs::new()
+            .with_plugin_per_dir(true)
+            .load(&url, Some(&["bar".to_string()]), false)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    url.set_query(Some("plugin_per_dir=true"));
+    let merged = Configuration::new()
+        .with_url(url)
+        .unwrap()
+        .load_parse_merge(false)
+        .unwrap();
+    let (_, foo) = merged.iter().find(|(name, _)| name == "foo").unwrap();
+    assert_eq!(foo.as_map().get("a"), Some(&Input::from(1)));
+    assert_eq!(foo.as_map().get("b"), Some(&Input::from(2)));
+    assert!(!merged.iter().any(|(name, _)| name == "baz"));
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_file_names() {
+    use plugx_config::loader::fs::RAW_PATH_METADATA_KEY;
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    #[cfg(all(feature = "logging", not(feature = "tracing")))]
+    static WARNING_LIST: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+    #[cfg(all(feature = "logging", not(feature = "tracing")))]
+    {
+        struct WarningLogger;
+
+        impl log::Log for WarningLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) {
+                    WARNING_LIST.lock().unwrap().push(record.args().to_string());
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        log::set_logger(&WarningLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+    }
+
+    let tmp_dir = TempDir::new("fs-non-utf8").unwrap();
+    // `café.env` in Latin-1:
+    let path = tmp_dir.path().join(OsStr::from_bytes(b"caf\xe9.env"));
+    fs::write(&path, "A=1").unwrap();
+    fs::write(tmp_dir.path().join("foo.env"), "B=2").unwrap();
+    let url = Url::from_directory_path(tmp_dir.path()).unwrap();
+
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+    #[cfg(all(feature = "logging", not(feature = "tracing")))]
+    assert!(WARNING_LIST
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|warning| warning.contains("non-UTF-8") && warning.contains(r#"name="caf\xE9.env""#)));
+
+    let mut url = url;
+    url.set_query(Some("lossy-file-names=true"));
+    let mut loaded = Fs::new().load(&url, None, false).unwrap();
+    loaded.sort_by(|(plugin_1, _), (plugin_2, _)| plugin_1.cmp(plugin_2));
+    assert_eq!(loaded.len(), 2);
+    let (plugin_name, entity) = &loaded[0];
+    assert_eq!(plugin_name, "caf\u{FFFD}");
+    assert_eq!(entity.maybe_contents(), Some(&"A=1".to_string()));
+    assert!(entity.metadata().contains_key(RAW_PATH_METADATA_KEY));
+    assert_eq!(Fs::entity_path(entity), path);
+    assert!(!loaded[1].1.metadata().contains_key(RAW_PATH_METADATA_KEY));
+
+    // Directories of `plugin-per-dir` option:
+    let tmp_dir = TempDir::new("fs-non-utf8-plugin-per-dir").unwrap();
+    let directory = tmp_dir.path().join(OsStr::from_bytes(b"caf\xe9"));
+    fs::create_dir(&directory).unwrap();
+    fs::write(directory.join("base.env"), "A=1").unwrap();
+    let mut url = Url::from_directory_path(tmp_dir.path()).unwrap();
+    url.set_query(Some("plugin-per-dir=true"));
+    assert!(Fs::new().load(&url, None, false).unwrap().is_empty());
+    url.set_query(Some("plugin-per-dir=true&lossy-file-names=true"));
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "caf\u{FFFD}");
+    assert_eq!(loaded[0].1.maybe_contents(), Some(&"A=1".to_string()));
+}
+
+#[test]
+fn single_file_with_query() {
+    let tmp_dir = TempDir::new("fs-single-file-with-query").unwrap();
+    let path = tmp_dir.path().join("foo.env");
+    fs::write(&path, "HELLO=world\n".repeat(100)).unwrap();
+    let url: Url = format!(
+        "file://{}?max-file-size=100&soft-errors=too-large",
+        path.to_str().unwrap()
+    )
+    .parse()
+    .unwrap();
+    assert!(Fs::new().load(&url, None, true).unwrap().is_empty());
+    assert!(Fs::new().load(&url, None, false).is_err());
+
+    let url: Url = format!("file://{}?soft-errors=not-found", path.to_str().unwrap())
+        .parse()
+        .unwrap();
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+    assert_eq!(loaded[0].1.url(), &url);
+    assert_eq!(Fs::entity_path(&loaded[0].1), path);
+    assert!(loaded[0]
+        .1
+        .maybe_contents()
+        .unwrap()
+        .starts_with("HELLO=world"));
+
+    fs::remove_file(&path).unwrap();
+    assert!(Fs::new().load(&url, None, true).unwrap().is_empty());
+    assert!(Fs::new().load(&url, None, false).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn fifo() {
+    use plugx_config::loader::Error;
+    use std::{process::Command, thread};
+
+    let tmp_dir = TempDir::new("fs-fifo").unwrap();
+    let path = tmp_dir.path().join("foo.env");
+    assert!(Command::new("mkfifo")
+        .arg(&path)
+        .status()
+        .unwrap()
+        .success());
+    assert!(Fs::is_fifo(&path));
+    let url = Url::from_file_path(&path).unwrap();
+
+    let writer_path = path.clone();
+    let writer = thread::spawn(move || fs::write(writer_path, "HELLO=world").unwrap());
+    let loaded = Fs::new().load(&url, None, false).unwrap();
+    writer.join().unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+    assert_eq!(
+        loaded[0].1.maybe_contents(),
+        Some(&"HELLO=world".to_string())
+    );
+
+    // Nothing is written:
+    let timeout_url: Url = format!("{url}?fifo-timeout=1").parse().unwrap();
+    assert!(matches!(
+        Fs::new().load(&timeout_url, None, false),
+        Err(Error::Timeout { .. })
+    ));
+
+    let writer_path = path.clone();
+    let writer = thread::spawn(move || fs::write(writer_path, "HELLO=world").unwrap());
+    let loaded = Fs::new().load(&timeout_url, None, false).unwrap();
+    writer.join().unwrap();
+    assert_eq!(
+        loaded[0].1.maybe_contents(),
+        Some(&"HELLO=world".to_string())
+    );
+
+    // The writer closes it without writing (e.g. `: > fifo`):
+    let writer_path = path.clone();
+    let writer = thread::spawn(move || fs::write(writer_path, "").unwrap());
+    let loaded = Fs::new().load(&timeout_url, None, false).unwrap();
+    writer.join().unwrap();
+    assert_eq!(loaded[0].1.maybe_contents(), Some(&String::new()));
+
+    // Reading stops after `max-file-size` bytes, so the writer may fail with a broken pipe:
+    [
+        format!("{url}?max-file-size=100&soft-errors=too-large"),
+        format!("{url}?fifo-timeout=1&max-file-size=100&soft-errors=too-large"),
+    ]
+    .into_iter()
+    .for_each(|url| {
+        let url: Url = url.parse().unwrap();
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            let _ = fs::write(writer_path, "HELLO=world\n".repeat(100_000));
+        });
+        assert!(Fs::new().load(&url, None, false).is_err());
+        writer.join().unwrap();
+
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            let _ = fs::write(writer_path, "HELLO=world\n".repeat(100_000));
+        });
+        assert!(Fs::new().load(&url, None, true).unwrap().is_empty());
+        writer.join().unwrap();
+    });
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "git")]
+
+use plugx_config::{
+    loader::{git::Git, Error, Loader},
+    Url,
+};
+use std::{fs, path::Path, process::Command};
+use tempdir::TempDir;
+
+fn git(dir: &Path, argument_list: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=plugx", "-c", "user.email=plugx@localhost"])
+        .args(argument_list)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn commit(repository_dir: &Path, contents: &str) {
+    fs::write(repository_dir.join("config").join("foo.env"), contents).unwrap();
+    git(repository_dir, &["add", "-A"]);
+    git(repository_dir, &["commit", "-q", "-m", contents]);
+}
+
+#[test]
+fn load() {
+    let tmp_dir = TempDir::new("git-load").unwrap();
+    let repository_dir = tmp_dir.path().join("repository");
+    fs::create_dir_all(repository_dir.join("config")).unwrap();
+    git(&repository_dir, &["init", "-q", "-b", "main"]);
+    commit(&repository_dir, "HELLO=world");
+    git(&repository_dir, &["tag", "v1"]);
+    commit(&repository_dir, "HELLO=git");
+
+    let loader = Git::new().with_cache_dir(tmp_dir.path().join("cache"));
+    let remote_url = Url::from_directory_path(&repository_dir).unwrap();
+    let load = |query: &str| {
+        let url: Url = format!("git+{remote_url}?{query}").parse().unwrap();
+        loader.load(&url, None, false)
+    };
+
+    let loaded = load("ref=v1&path=config").unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+    assert_eq!(loaded[0].1.maybe_contents().unwrap(), "HELLO=world");
+    assert_eq!(loaded[0].1.url().scheme(), "git+file");
+
+    // Reuses the previous clone:
+    let loaded = load("ref=main&path=config").unwrap();
+    assert_eq!(loaded[0].1.maybe_contents().unwrap(), "HELLO=git");
+
+    // Fetches updates:
+    commit(&repository_dir, "HELLO=reload");
+    let loaded = load("ref=main&path=/config/").unwrap();
+    assert_eq!(loaded[0].1.maybe_contents().unwrap(), "HELLO=reload");
+
+    let loaded = load("ref=main&path=config/foo.env").unwrap();
+    assert_eq!(loaded.len(), 1);
+
+    assert!(matches!(
+        load("ref=v2&path=config"),
+        Err(Error::NotFound { .. })
+    ));
+    let url: Url = format!("git+{remote_url}?ref=v2&soft-errors=not-found")
+        .parse()
+        .unwrap();
+    assert!(loader.load(&url, None, true).unwrap().is_empty());
+    assert!(loader.validate_url(&url).is_ok());
+    let url: Url = format!("git+{remote_url}?soft-errors=unknown")
+        .parse()
+        .unwrap();
+    assert!(loader.validate_url(&url).is_err());
+
+    // Paths outside of the repository:
+    assert!(matches!(
+        load("ref=main&path=../"),
+        Err(Error::InvalidUrl { .. })
+    ));
+    assert!(matches!(
+        load("ref=main&path=config/../../../"),
+        Err(Error::InvalidUrl { .. })
+    ));
+    assert!(matches!(
+        load("ref=main&path=unknown"),
+        Err(Error::NotFound { .. })
+    ));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        for entry in fs::read_dir(tmp_dir.path().join("cache")).unwrap() {
+            let mode = entry.unwrap().metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+}
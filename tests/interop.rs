@@ -0,0 +1,67 @@
+#![cfg(all(
+    feature = "configuration",
+    any(feature = "json", feature = "toml", feature = "yaml")
+))]
+
+use plugx_config::Input;
+use std::collections::HashMap;
+
+fn input() -> Input {
+    Input::from(HashMap::from([
+        ("name".to_string(), Input::from("app")),
+        ("ratio".to_string(), Input::from(0.5)),
+        ("enabled".to_string(), Input::from(true)),
+        (
+            "server".to_string(),
+            Input::from(HashMap::from([
+                ("port".to_string(), Input::from(8080)),
+                ("hosts".to_string(), Input::from(["a", "b"])),
+            ])),
+        ),
+    ]))
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    use plugx_config::interop::{input_to_json, json_to_input};
+    use serde_json::json;
+
+    let value = input_to_json(&input()).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "name": "app",
+            "ratio": 0.5,
+            "enabled": true,
+            "server": {"port": 8080, "hosts": ["a", "b"]}
+        })
+    );
+    assert_eq!(json_to_input(&value).unwrap(), input());
+    assert!(json_to_input(&json!([1, null])).is_err());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml() {
+    use plugx_config::interop::{input_to_toml, toml_to_input};
+
+    let value = input_to_toml(&input()).unwrap();
+    assert_eq!(
+        value["server"]["port"],
+        toml::Value::Integer(8080),
+        "{value:?}"
+    );
+    assert_eq!(toml_to_input(&value).unwrap(), input());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml() {
+    use plugx_config::interop::{input_to_yaml, yaml_to_input};
+
+    let value = input_to_yaml(&input()).unwrap();
+    assert_eq!(value["server"]["hosts"][1], serde_yaml::Value::from("b"));
+    assert_eq!(yaml_to_input(&value).unwrap(), input());
+    assert!(yaml_to_input(&serde_yaml::Value::Null).is_err());
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "jsonschema")]
+
+use plugx_config::{
+    entity::ConfigurationEntity,
+    loader::{closure::Closure, Error as LoaderError},
+    Configuration, Error, Input, Url,
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn validate() {
+    let configuration = Configuration::new()
+        .with_loader(Closure::new(
+            "jsonschema-loader",
+            Box::new(|url: &Url, _: Option<&[String]>, _: bool| {
+                let parsed = Input::from(HashMap::from([("port".to_string(), Input::from(8080))]));
+                let entity =
+                    ConfigurationEntity::new("foo", url.clone(), "foo", "jsonschema-loader")
+                        .with_parsed_contents(parsed);
+                Ok::<_, LoaderError>(vec![("foo".to_string(), entity)])
+            }),
+            "jsonschema",
+        ))
+        .with_url("jsonschema://".parse().unwrap())
+        .unwrap();
+
+    let schema = json!({
+        "type": "object",
+        "properties": {"port": {"type": "integer", "minimum": 1}},
+        "required": ["port"]
+    });
+    let merged = configuration
+        .load_parse_merge_validate_jsonschema(&[("foo".to_string(), schema)], false)
+        .unwrap();
+    assert_eq!(merged.len(), 1);
+
+    let schema = json!({
+        "type": "object",
+        "properties": {"port": {"type": "string"}},
+        "required": ["port", "host"]
+    });
+    let error = configuration
+        .load_parse_merge_validate_jsonschema(&[("foo".to_string(), schema)], false)
+        .unwrap_err();
+    if let Error::ValidateJsonSchema {
+        plugin_name,
+        error_list,
+    } = error
+    {
+        assert_eq!(plugin_name, "foo");
+        assert_eq!(error_list.len(), 2);
+    } else {
+        panic!("Unexpected error {error:?}")
+    }
+}
@@ -0,0 +1,83 @@
+#![cfg(all(feature = "logging", not(feature = "tracing"), feature = "env"))]
+
+use plugx_config::{Configuration, Url};
+use std::{
+    env,
+    sync::{Mutex, Once},
+};
+
+static TARGET_LIST: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static MESSAGE_LIST: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static INIT: Once = Once::new();
+
+struct TargetLogger;
+
+impl log::Log for TargetLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        TARGET_LIST
+            .lock()
+            .unwrap()
+            .push(record.target().to_string());
+        MESSAGE_LIST.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+fn init_logger() {
+    INIT.call_once(|| {
+        log::set_logger(&TargetLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+}
+
+#[test]
+fn targets() {
+    init_logger();
+    env::set_var("PLUGX_LOGGING_TARGETS__FOO__BAR", "baz");
+    let url: Url = "env://?prefix=PLUGX_LOGGING_TARGETS".parse().unwrap();
+    Configuration::new()
+        .with_url(url)
+        .unwrap()
+        .load_parse_merge(false)
+        .unwrap();
+
+    let target_list = TARGET_LIST.lock().unwrap();
+    assert!(target_list
+        .iter()
+        .all(|target| target.starts_with("plugx_config::")));
+    ["plugx_config::loader::env", "plugx_config::configuration"]
+        .into_iter()
+        .for_each(|target| assert!(target_list.iter().any(|item| item == target), "{target}"));
+}
+
+#[test]
+fn trace_contents() {
+    init_logger();
+    env::set_var("PLUGX_LOGGING_CONTENTS__FOO__BAR", "s3cr3t-value");
+    let url: Url = "env://?prefix=PLUGX_LOGGING_CONTENTS".parse().unwrap();
+    let contains_value = || {
+        MESSAGE_LIST
+            .lock()
+            .unwrap()
+            .drain(..)
+            .any(|message| message.contains("s3cr3t-value"))
+    };
+
+    let configuration = Configuration::new()
+        .with_url(url)
+        .unwrap()
+        .with_trace_contents(false);
+    assert!(!configuration.trace_contents());
+    contains_value();
+    configuration.load_parse_merge(false).unwrap();
+    assert!(!contains_value());
+
+    let configuration = configuration.with_trace_contents(true);
+    configuration.load_parse_merge(false).unwrap();
+    assert!(contains_value());
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "configuration")]
+
+use plugx_config::{
+    entity::ConfigurationEntity,
+    loader::{closure::Closure, map::MappedLoader, Error},
+    Configuration, Input, Url,
+};
+
+fn legacy_configuration() -> Configuration {
+    let loader = Closure::new(
+        "legacy",
+        Box::new(|url: &Url, maybe_whitelist: Option<&[String]>, _: bool| {
+            // Legacy plugin names are never requested:
+            assert!(maybe_whitelist.is_none());
+            Ok::<_, Error>(
+                ["foo", "bar"]
+                    .into_iter()
+                    .map(|plugin_name| {
+                        let entity = ConfigurationEntity::new(
+                            plugin_name,
+                            url.clone(),
+                            plugin_name,
+                            "legacy",
+                        )
+                        .with_parsed_contents(Input::new_map());
+                        (plugin_name.to_string(), entity)
+                    })
+                    .collect(),
+            )
+        }),
+        "legacy",
+    );
+    let loader = MappedLoader::new(
+        loader,
+        Box::new(|loaded| {
+            loaded
+                .into_iter()
+                .map(|(plugin_name, mut entity)| {
+                    let plugin_name = format!("legacy_{plugin_name}");
+                    *entity.plugin_name_mut() = plugin_name.clone();
+                    (plugin_name, entity)
+                })
+                .collect()
+        }),
+    );
+    Configuration::new()
+        .with_loader(loader)
+        .with_url("legacy://".parse().unwrap())
+        .unwrap()
+}
+
+#[test]
+fn whitelist_of_mapped_names() {
+    let plugin_name_list = |configuration: Configuration| {
+        configuration
+            .load_parse_merge(false)
+            .unwrap()
+            .into_iter()
+            .map(|(plugin_name, _)| plugin_name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        plugin_name_list(legacy_configuration().with_whitelist(&["legacy_foo"])),
+        vec!["legacy_foo".to_string()]
+    );
+    assert!(plugin_name_list(legacy_configuration().with_whitelist(&["foo"])).is_empty());
+    assert_eq!(plugin_name_list(legacy_configuration()).len(), 2);
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn plan_of_mapped_names() {
+    use plugx_config::loader::{fs::Fs, Loader};
+    use tempdir::TempDir;
+
+    let tmp_dir = TempDir::new("map-plan").unwrap();
+    ["foo.env", "bar.env"]
+        .into_iter()
+        .for_each(|file_name| std::fs::write(tmp_dir.path().join(file_name), "VALUE=1").unwrap());
+    let url = Url::from_directory_path(tmp_dir.path()).unwrap();
+    let loader = MappedLoader::new(
+        Fs::new(),
+        Box::new(|loaded| {
+            loaded
+                .into_iter()
+                .map(|(plugin_name, mut entity)| {
+                    let plugin_name = format!("legacy_{plugin_name}");
+                    *entity.plugin_name_mut() = plugin_name.clone();
+                    (plugin_name, entity)
+                })
+                .collect()
+        }),
+    );
+
+    let whitelist = ["legacy_foo".to_string()];
+    let plan = loader.plan(&url, Some(&whitelist), false).unwrap().unwrap();
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].plugin_name(), "legacy_foo");
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "mongodb")]
+
+use plugx_config::{
+    loader::{mongodb::Mongodb, Error, Loader},
+    Url,
+};
+
+#[test]
+fn mongodb_without_server() {
+    let loader = Mongodb::new();
+    // Without database:
+    let url: Url = "mongodb://127.0.0.1:1".parse().unwrap();
+    assert!(matches!(
+        loader.load(&url, None, false),
+        Err(Error::InvalidUrl { .. })
+    ));
+    // Nothing is whitelisted, so nothing is requested:
+    let url: Url = "mongodb://127.0.0.1:1/app?collection=plugins"
+        .parse()
+        .unwrap();
+    assert!(loader.load(&url, Some(&[]), false).unwrap().is_empty());
+    // Unreachable server (our own options are not passed to the driver):
+    let url: Url =
+        "mongodb://127.0.0.1:1/app?collection=plugins&soft-errors=not-found&serverSelectionTimeoutMS=100"
+            .parse()
+            .unwrap();
+    assert!(matches!(
+        loader.load(&url, None, true),
+        Err(Error::Load { .. })
+    ));
+}
+
+#[test]
+fn mongodb_inside_runtime() {
+    let loader = Mongodb::new();
+    let url: Url = "mongodb://127.0.0.1:1/app".parse().unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    // Returns an error instead of panicking:
+    assert!(matches!(
+        runtime.block_on(async { loader.load(&url, None, false) }),
+        Err(Error::Load { .. })
+    ));
+}
+
+#[test]
+fn entity_from_document() {
+    use plugx_config::ext::plugx_input::Input;
+
+    let url: Url = "mongodb://127.0.0.1:1/app".parse().unwrap();
+    let document = mongodb::bson::doc! {"_id": "Foo", "format": "YAML", "contents": "a: 1"};
+    let (plugin_name, entity) =
+        Mongodb::entity_from_document(&url, "app", "config", document).unwrap();
+    assert_eq!(plugin_name, "foo");
+    assert_eq!(entity.item(), "app/config/foo");
+    assert_eq!(entity.maybe_format(), Some(&"yaml".to_string()));
+    assert_eq!(entity.maybe_contents(), Some(&"a: 1".to_string()));
+
+    let document = mongodb::bson::doc! {"_id": "bar", "config": {"port": 8080}};
+    let (_, entity) = Mongodb::entity_from_document(&url, "app", "config", document).unwrap();
+    assert_eq!(
+        entity.maybe_parsed_contents().unwrap().as_map().get("port"),
+        Some(&Input::from(8080))
+    );
+
+    // Non-string `_id`:
+    let document = mongodb::bson::doc! {"_id": 1, "config": {}};
+    assert!(Mongodb::entity_from_document(&url, "app", "config", document).is_err());
+    // Neither `config` nor `format` and `contents`:
+    let document = mongodb::bson::doc! {"_id": "baz", "format": "yaml"};
+    assert!(Mongodb::entity_from_document(&url, "app", "config", document).is_err());
+}
@@ -0,0 +1,226 @@
+use plugx_config::parser::{Error, Parser};
+
+fn position(parser: &dyn Parser, contents: &str) -> Option<(usize, usize)> {
+    match parser.parse(contents.as_bytes()) {
+        Err(error @ Error::Parse { .. }) => error
+            .position()
+            .map(|position| (position.line, position.column)),
+        other => panic!("Expected parse error, got {other:?}"),
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_error_position() {
+    use plugx_config::parser::json::Json;
+
+    let parser = Json::new();
+    assert_eq!(
+        position(&parser, "{\n  \"a\": 1,\n  \"b\": ]\n}"),
+        Some((3, 8))
+    );
+    let error = parser.parse(b"{\n  \"a\": ?\n}").unwrap_err();
+    assert!(error.to_string().ends_with(" at line 2 column 8"));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_error_position() {
+    use plugx_config::parser::yaml::Yaml;
+
+    assert_eq!(position(&Yaml::new(), "a: 1\nb: [1, 2\nc: 3"), Some((3, 2)));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_error_position() {
+    use plugx_config::parser::toml::Toml;
+
+    assert_eq!(
+        position(&Toml::new(), "a = 1\nb = \"ok\"\nc = = 3"),
+        Some((3, 5))
+    );
+}
+
+#[cfg(feature = "hjson")]
+#[test]
+fn hjson_error_position() {
+    use plugx_config::parser::hjson::Hjson;
+
+    assert_eq!(
+        position(&Hjson::new(), "{\n  a: 1\n  b: [\n}"),
+        Some((4, 1))
+    );
+}
+
+#[cfg(feature = "env")]
+#[test]
+fn no_error_position() {
+    use plugx_config::parser::env::Env;
+
+    assert_eq!(position(&Env::new(), "="), None);
+}
+
+#[cfg(feature = "hcl")]
+#[test]
+fn hcl() {
+    use plugx_config::parser::hcl::Hcl;
+    use plugx_input::Input;
+
+    let parser = Hcl::new();
+    assert_eq!(
+        parser.supported_format_list(),
+        vec!["hcl".to_string(), "tf".to_string()]
+    );
+    let parsed = parser
+        .parse(
+            br#"
+ratio = 0.5
+enabled = true
+tags = { "team" = "core", env = "prod" }
+description = <<EOT
+hello
+EOT
+
+resource "aws_instance" "web" {
+  ami = var.ami
+  count = (2)
+}
+
+resource "aws_instance" "db" {}
+
+ingress {
+  port = 80
+}
+
+ingress {
+  port = 443
+}
+"#
+            .as_slice(),
+        )
+        .unwrap();
+    let map = parsed.as_map();
+    assert_eq!(map.get("ratio").unwrap(), &Input::from(0.5));
+    assert_eq!(map.get("enabled").unwrap(), &Input::from(true));
+    assert_eq!(
+        map.get("tags").unwrap().as_map().get("team").unwrap(),
+        &Input::from("core")
+    );
+    assert_eq!(map.get("description").unwrap(), &Input::from("hello\n"));
+    let instance = map
+        .get("resource")
+        .unwrap()
+        .as_map()
+        .get("aws_instance")
+        .unwrap()
+        .as_map();
+    assert_eq!(instance.len(), 2);
+    let web = instance.get("web").unwrap().as_map();
+    assert_eq!(web.get("ami").unwrap(), &Input::from("var.ami"));
+    assert_eq!(web.get("count").unwrap(), &Input::from(2));
+    let ingress = map.get("ingress").unwrap().as_list();
+    assert_eq!(ingress.len(), 2);
+    assert_eq!(ingress[1].as_map().get("port").unwrap(), &Input::from(443));
+
+    for unsupported in [
+        "a = null",
+        "a = 1 + 2",
+        "a = max(1, 2)",
+        "a = \"${var.b}\"",
+        "a = var.b[0]",
+        "a = true ? 1 : 2",
+    ] {
+        assert!(
+            parser.parse(unsupported.as_bytes()).is_err(),
+            "{unsupported}"
+        );
+    }
+    assert_eq!(parser.is_format_supported(b"a = 1"), Some(true));
+    assert_eq!(parser.is_format_supported(b"a = 1 + 2"), Some(false));
+    assert_eq!(position(&parser, "a = 1\nb = = 2"), Some((2, 5)));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_anchors_and_merge_keys() {
+    use plugx_config::parser::yaml::Yaml;
+
+    let bytes = br#"
+defaults: &defaults
+  adapter: postgres
+  host: localhost
+  port: 5432
+hosts: &hosts
+  - a
+  - b
+development:
+  <<: *defaults
+  database: dev
+production:
+  <<: [*defaults, {timeout: 10}]
+  host: db.example.com
+replicas: *hosts
+"#;
+    let parsed = Yaml::new().parse(bytes.as_slice()).unwrap();
+    let map = parsed.as_map();
+    let development = map.get("development").unwrap().as_map();
+    assert!(!development.contains_key("<<"));
+    assert_eq!(development.get("adapter").unwrap(), &"postgres".into());
+    assert_eq!(development.get("host").unwrap(), &"localhost".into());
+    assert_eq!(development.get("port").unwrap(), &5432.into());
+    assert_eq!(development.get("database").unwrap(), &"dev".into());
+    let production = map.get("production").unwrap().as_map();
+    assert!(!production.contains_key("<<"));
+    // Explicit keys take precedence over merged ones:
+    assert_eq!(production.get("host").unwrap(), &"db.example.com".into());
+    assert_eq!(production.get("port").unwrap(), &5432.into());
+    assert_eq!(production.get("timeout").unwrap(), &10.into());
+    assert_eq!(map.get("replicas").unwrap(), &["a", "b"].into());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_datetimes() {
+    use plugx_config::parser::toml::Toml;
+
+    let bytes = br#"
+dt = 1979-05-27T07:32:00Z
+offset = 1979-05-27T00:32:00.999999-07:00
+local-datetime = 1979-05-27T07:32:00
+local-date = 1979-05-27
+local-time = 07:32:00
+list = [1979-05-27T07:32:00Z]
+"#;
+    let parsed = Toml::new().parse(bytes.as_slice()).unwrap();
+    let map = parsed.as_map();
+    assert_eq!(map.get("dt").unwrap(), &"1979-05-27T07:32:00Z".into());
+    assert_eq!(
+        map.get("offset").unwrap(),
+        &"1979-05-27T00:32:00.999999-07:00".into()
+    );
+    assert_eq!(
+        map.get("local-datetime").unwrap(),
+        &"1979-05-27T07:32:00".into()
+    );
+    assert_eq!(map.get("local-date").unwrap(), &"1979-05-27".into());
+    assert_eq!(map.get("local-time").unwrap(), &"07:32:00".into());
+    assert_eq!(map.get("list").unwrap(), &["1979-05-27T07:32:00Z"].into());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_timestamps() {
+    use plugx_config::parser::yaml::Yaml;
+
+    let bytes =
+        b"ts: 2001-12-15T02:59:43Z\nprecise: 2001-12-14t21:59:43.10-05:00\ndate: 2002-12-14";
+    let parsed = Yaml::new().parse(bytes.as_slice()).unwrap();
+    let map = parsed.as_map();
+    assert_eq!(map.get("ts").unwrap(), &"2001-12-15T02:59:43Z".into());
+    assert_eq!(
+        map.get("precise").unwrap(),
+        &"2001-12-14t21:59:43.10-05:00".into()
+    );
+    assert_eq!(map.get("date").unwrap(), &"2002-12-14".into());
+}
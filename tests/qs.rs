@@ -0,0 +1,176 @@
+#![cfg(feature = "fs")]
+
+use plugx_config::{
+    loader::{
+        deserialize_query_string,
+        fs::{FsOptions, SoftErrorsFs},
+        Error, SoftErrors,
+    },
+    Url,
+};
+use serde::Deserialize;
+
+fn options(url: &str) -> Result<FsOptions, Error> {
+    deserialize_query_string("test", &url.parse::<Url>().unwrap())
+}
+
+fn soft_errors(url: &str) -> SoftErrors<SoftErrorsFs> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Options {
+        #[serde(default)]
+        soft_errors: SoftErrors<SoftErrorsFs>,
+    }
+    deserialize_query_string::<Options>("test", &url.parse::<Url>().unwrap())
+        .unwrap()
+        .soft_errors
+}
+
+#[test]
+fn flat() {
+    assert!(options("file:/etc?soft-errors=not-found&strip-slash=true").is_ok());
+    assert!(options("file:/etc?strip-slash=true&soft-errors=all").is_ok());
+    assert!(options("file:/etc?strip-slash=yes").is_err());
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors=not-found&strip-slash=true"),
+        SoftErrors::new_list().with_soft_error(SoftErrorsFs::NotFound)
+    );
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors=not-found.permission-denied"),
+        SoftErrors::new_list()
+            .with_soft_error(SoftErrorsFs::NotFound)
+            .with_soft_error(SoftErrorsFs::PermissionDenied)
+    );
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors=all"),
+        SoftErrors::new_all()
+    );
+    assert_eq!(soft_errors("file:/etc"), SoftErrors::new_list());
+}
+
+#[test]
+fn list() {
+    let expected = SoftErrors::new_list()
+        .with_soft_error(SoftErrorsFs::NotFound)
+        .with_soft_error(SoftErrorsFs::PermissionDenied);
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors[]=not-found&soft-errors[]=permission-denied"),
+        expected
+    );
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors[0]=not-found&soft-errors[1]=permission-denied"),
+        expected
+    );
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors%5B%5D=not-found&soft-errors%5B%5D=permission-denied"),
+        expected
+    );
+    assert_eq!(
+        soft_errors("file:/etc?soft-errors[]=not-found&soft-errors[]=all"),
+        SoftErrors::new_all()
+    );
+    assert!(options("file:/etc?soft-errors[]=not-found&strip-slash=true").is_ok());
+    assert!(options("file:/etc?soft-errors[]=unknown").is_err());
+}
+
+#[test]
+fn nested() {
+    #[derive(Debug, Default, PartialEq, Deserialize)]
+    #[serde(default, rename_all = "kebab-case")]
+    struct Inner {
+        name: String,
+        soft_errors: SoftErrors<SoftErrorsFs>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, rename_all = "kebab-case")]
+    struct Options {
+        inner: Inner,
+    }
+
+    let url: Url = "file:/etc?inner[name]=foo&inner[soft-errors][]=not-found"
+        .parse()
+        .unwrap();
+    let options: Options = deserialize_query_string("test", &url).unwrap();
+    assert_eq!(
+        options.inner,
+        Inner {
+            name: "foo".to_string(),
+            soft_errors: SoftErrors::new_list().with_soft_error(SoftErrorsFs::NotFound),
+        }
+    );
+}
+
+#[test]
+fn merge_soft_errors() {
+    let not_found = || SoftErrors::new_list().with_soft_error(SoftErrorsFs::NotFound);
+    let permission_denied =
+        || SoftErrors::new_list().with_soft_error(SoftErrorsFs::PermissionDenied);
+
+    assert_eq!(
+        SoftErrors::new_all().merge(not_found()),
+        SoftErrors::new_all()
+    );
+    assert_eq!(
+        not_found().merge(SoftErrors::new_all()),
+        SoftErrors::new_all()
+    );
+    assert_eq!(
+        not_found().merge(permission_denied()),
+        not_found().with_soft_error(SoftErrorsFs::PermissionDenied)
+    );
+    assert_eq!(
+        not_found()
+            .with_soft_error(SoftErrorsFs::TooLarge)
+            .merge(permission_denied().with_soft_error(SoftErrorsFs::NotFound)),
+        not_found()
+            .with_soft_error(SoftErrorsFs::TooLarge)
+            .with_soft_error(SoftErrorsFs::PermissionDenied)
+    );
+    assert_eq!(
+        SoftErrors::<SoftErrorsFs>::new_list().merge(SoftErrors::new_list()),
+        SoftErrors::new_list()
+    );
+}
+
+#[test]
+fn serde_default() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(default, rename_all = "kebab-case")]
+    struct Options {
+        retry: usize,
+        name: String,
+        soft_errors: SoftErrors<SoftErrorsFs>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Self {
+                retry: 3,
+                name: "default".to_string(),
+                soft_errors: SoftErrors::new_list().with_soft_error(SoftErrorsFs::NotFound),
+            }
+        }
+    }
+
+    let options =
+        |url: &str| deserialize_query_string::<Options>("test", &url.parse::<Url>().unwrap());
+    assert_eq!(options("file:/etc").unwrap(), Options::default());
+    assert_eq!(options("file:/etc?").unwrap(), Options::default());
+    assert_eq!(
+        options("file:/etc?retry=5&soft-errors=all").unwrap(),
+        Options {
+            retry: 5,
+            soft_errors: SoftErrors::new_all(),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        options("file:/etc?name=foo").unwrap(),
+        Options {
+            name: "foo".to_string(),
+            ..Default::default()
+        }
+    );
+    assert!(options("file:/etc?retry=many").is_err());
+}
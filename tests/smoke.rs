@@ -1,3 +1,11 @@
+#![cfg(all(
+    feature = "env",
+    feature = "fs",
+    feature = "json",
+    feature = "yaml",
+    feature = "toml"
+))]
+
 #[test]
 fn smoke() -> Result<(), anyhow::Error> {
     cfg_if::cfg_if! {
@@ -0,0 +1,80 @@
+#![cfg(feature = "sqlite")]
+
+use plugx_config::{
+    loader::{sqlite::Sqlite, Error, Loader},
+    Url,
+};
+use rusqlite::Connection;
+use std::path::Path;
+use tempdir::TempDir;
+
+fn database(path: &Path, sql: &str) -> Url {
+    let connection = Connection::open(path).unwrap();
+    connection
+        .execute_batch("CREATE TABLE config (plugin TEXT, format TEXT, contents TEXT);")
+        .unwrap();
+    connection.execute_batch(sql).unwrap();
+    Url::from_file_path(path)
+        .map(|url| url.as_str().replacen("file://", "sqlite://", 1))
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn whitelist() {
+    let tmp_dir = TempDir::new("sqlite-whitelist").unwrap();
+    let url = database(
+        tmp_dir.path().join("app.db").as_path(),
+        r#"
+            INSERT INTO config VALUES ('Foo', 'JSON', '{"hello": "world"}');
+            INSERT INTO config VALUES ('bar', 'env', 'HELLO="world"');
+        "#,
+    );
+    let loader = Sqlite::new();
+    let plugin_name_list = |whitelist: &[String]| {
+        loader
+            .load(&url, Some(whitelist), false)
+            .unwrap()
+            .into_iter()
+            .map(|(plugin_name, entity)| {
+                assert_eq!(entity.plugin_name(), &plugin_name);
+                plugin_name
+            })
+            .collect::<Vec<_>>()
+    };
+    // Plugin names and formats are lowercased:
+    assert_eq!(plugin_name_list(&["foo".into()]), vec!["foo".to_string()]);
+    let loaded = loader.load(&url, Some(&["foo".into()]), false).unwrap();
+    assert_eq!(loaded[0].1.maybe_format(), Some(&"json".to_string()));
+    assert!(plugin_name_list(&["baz".into()]).is_empty());
+    // Nothing is whitelisted:
+    assert!(plugin_name_list(&[]).is_empty());
+    assert_eq!(loader.load(&url, None, false).unwrap().len(), 2);
+}
+
+#[test]
+fn malformed_row() {
+    let tmp_dir = TempDir::new("sqlite-malformed").unwrap();
+    let url = database(
+        tmp_dir.path().join("app.db").as_path(),
+        "INSERT INTO config VALUES ('foo', 'json', NULL);",
+    );
+    assert!(matches!(
+        Sqlite::new().load(&url, None, true),
+        Err(Error::Load { .. })
+    ));
+}
+
+#[test]
+fn percent_encoded_path() {
+    let tmp_dir = TempDir::new("sqlite-path").unwrap();
+    let url = database(
+        tmp_dir.path().join("my app.db").as_path(),
+        "INSERT INTO config VALUES ('foo', 'json', '{}');",
+    );
+    assert!(url.path().ends_with("/my%20app.db"));
+    let loader = Sqlite::new();
+    assert!(loader.validate_url(&url).is_ok());
+    assert_eq!(loader.load(&url, None, false).unwrap().len(), 1);
+}
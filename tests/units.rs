@@ -0,0 +1,57 @@
+#![cfg(feature = "units")]
+
+use plugx_config::{
+    entity::ConfigurationEntity,
+    loader::{closure::Closure, Error as LoaderError},
+    Configuration, Error, Input, Url,
+};
+use std::collections::HashMap;
+
+fn configuration(timeout: &'static str) -> Configuration {
+    Configuration::new()
+        .with_loader(Closure::new(
+            "units-loader",
+            Box::new(move |url: &Url, _: Option<&[String]>, _: bool| {
+                let server = Input::from(HashMap::from([
+                    ("timeout".to_string(), Input::from(timeout)),
+                    ("max-size".to_string(), Input::from("10 KiB")),
+                    ("retries".to_string(), Input::from(3)),
+                ]));
+                let parsed = Input::from(HashMap::from([
+                    ("server".to_string(), server),
+                    ("timeout".to_string(), Input::from("1m")),
+                ]));
+                let entity = ConfigurationEntity::new("foo", url.clone(), "foo", "units-loader")
+                    .with_parsed_contents(parsed);
+                Ok::<_, LoaderError>(vec![("foo".to_string(), entity)])
+            }),
+            "units",
+        ))
+        .with_url("units://".parse().unwrap())
+        .unwrap()
+}
+
+#[test]
+fn convert() {
+    let merged = configuration("1h 30s")
+        .with_duration_keys(&["*.*.timeout", "bar.timeout"])
+        .with_bytesize_keys(&["foo.server.max-size", "foo.server.retries"])
+        .load_parse_merge(false)
+        .unwrap();
+    let foo = merged[0].1.as_map();
+    let server = foo.get("server").unwrap().as_map();
+    assert_eq!(server.get("timeout"), Some(&Input::from(3_630_000)));
+    assert_eq!(server.get("max-size"), Some(&Input::from(10_240)));
+    // Not a string:
+    assert_eq!(server.get("retries"), Some(&Input::from(3)));
+    // Not matched:
+    assert_eq!(foo.get("timeout"), Some(&Input::from("1m")));
+}
+
+#[test]
+fn invalid() {
+    let result = configuration("soon")
+        .with_duration_keys(&["foo.server.timeout"])
+        .load_parse_merge(false);
+    assert!(matches!(result, Err(Error::Other(_))));
+}
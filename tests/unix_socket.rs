@@ -0,0 +1,119 @@
+#![cfg(all(unix, feature = "unix-socket"))]
+
+use plugx_config::{
+    loader::{unix_socket::UnixSocket, Error, Loader},
+    Url,
+};
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixListener,
+    path::Path,
+    thread::{self, JoinHandle},
+};
+use tempdir::TempDir;
+
+/// Accepts one connection, responds with `response`, and returns the request.
+fn agent(path: &Path, response: &'static str) -> JoinHandle<String> {
+    let listener = UnixListener::bind(path).unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        stream.read_to_string(&mut request).unwrap();
+        stream.write_all(response.as_bytes()).unwrap();
+        request
+    })
+}
+
+#[test]
+fn whitelist_and_errors() {
+    let tmp_dir = TempDir::new("unix-socket").unwrap();
+    let socket_file = tmp_dir.path().join("agent.sock");
+    let url = Url::try_from(format!("unix://{}", socket_file.to_str().unwrap()).as_str()).unwrap();
+    let loader = UnixSocket::new();
+
+    let handle = agent(&socket_file, "Foo ENV 5\nA=1\nB\nbar yml 6\nb: 2\n\n");
+    let whitelist = ["foo".to_string(), "baz".to_string()];
+    let loaded = loader.load(&url, Some(&whitelist), false).unwrap();
+    assert_eq!(handle.join().unwrap(), "foo,baz\n");
+    // `bar` is not whitelisted:
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, "foo");
+    assert_eq!(loaded[0].1.maybe_format(), Some(&"env".to_string()));
+    assert_eq!(loaded[0].1.maybe_contents(), Some(&"A=1\nB".to_string()));
+
+    // Malformed responses are load errors:
+    std::fs::remove_file(&socket_file).unwrap();
+    let handle = agent(&socket_file, "foo json 100\n{}");
+    let error = loader.load(&url, None, true).unwrap_err();
+    handle.join().unwrap();
+    assert!(matches!(error, Error::Load { .. }), "{error:?}");
+
+    // Responses larger than `max-response-size` are load errors:
+    std::fs::remove_file(&socket_file).unwrap();
+    let handle = agent(&socket_file, "foo env 3\nA=1");
+    let large_url =
+        Url::try_from(format!("{url}?max-response-size=8&soft-errors=all").as_str()).unwrap();
+    let error = loader.load(&large_url, None, true).unwrap_err();
+    handle.join().unwrap();
+    assert!(matches!(error, Error::Load { .. }), "{error:?}");
+
+    // The socket file exists but nothing listens on it:
+    assert!(matches!(
+        loader.load(&url, None, false).unwrap_err(),
+        Error::NotFound { .. }
+    ));
+    assert!(loader.load(&url, None, true).is_err());
+    let loader =
+        loader.with_soft_error(plugx_config::loader::unix_socket::SoftErrorsUnixSocket::NotFound);
+    assert!(loader.load(&url, None, true).unwrap().is_empty());
+}
+
+#[test]
+fn untrusted_lengths_and_paths() {
+    // Lengths are checked against what remains of the response before being used:
+    let error = UnixSocket::parse_response(b"foo json 18446744073709551615\n{}").unwrap_err();
+    assert!(
+        error.to_string().contains("only 2 bytes remained"),
+        "{error}"
+    );
+
+    // URL paths are percent-decoded:
+    let tmp_dir = TempDir::new("unix-socket").unwrap();
+    let socket_file = tmp_dir.path().join("my agent.sock");
+    let url = Url::try_from(format!("unix://{}", socket_file.to_str().unwrap()).as_str()).unwrap();
+    assert!(url.path().ends_with("/my%20agent.sock"));
+    let handle = agent(&socket_file, "foo env 3\nA=1");
+    let loaded = UnixSocket::new().load(&url, None, false).unwrap();
+    handle.join().unwrap();
+    assert_eq!(loaded[0].1.maybe_contents(), Some(&"A=1".to_string()));
+}
+
+#[test]
+fn timeout_of_whole_request() {
+    let tmp_dir = TempDir::new("unix-socket").unwrap();
+    let socket_file = tmp_dir.path().join("agent.sock");
+    let url = Url::try_from(format!("unix://{}", socket_file.to_str().unwrap()).as_str()).unwrap();
+    let listener = UnixListener::bind(&socket_file).unwrap();
+    // Writes one byte every 200 milliseconds which never reaches a per-read timeout:
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        stream.read_to_string(&mut request).unwrap();
+        for byte in "foo env 100\n"
+            .bytes()
+            .chain(std::iter::repeat(b'#'))
+            .take(20)
+        {
+            if stream.write_all(&[byte]).is_err() {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(200));
+        }
+    });
+    let error = UnixSocket::new()
+        .with_timeout(1)
+        .load(&url, None, false)
+        .unwrap_err();
+    handle.join().unwrap();
+    assert!(matches!(error, Error::Timeout { .. }), "{error:?}");
+}